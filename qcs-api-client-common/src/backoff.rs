@@ -7,6 +7,8 @@ use std::{error::Error as _, time::Duration};
 
 use qcs_dependencies_client::http::StatusCode;
 
+use crate::configuration::settings::RetrySettings;
+
 use ::backoff::backoff::Backoff;
 pub use ::backoff::*;
 
@@ -40,29 +42,89 @@ pub fn duration_from_response(
     headers: &qcs_dependencies_client::http::HeaderMap,
     backoff: &mut ExponentialBackoff,
 ) -> Option<Duration> {
-    use time::{OffsetDateTime, format_description::well_known::Rfc2822};
+    duration_from_retryable_response(status_code_is_retry(status), headers, backoff)
+}
 
-    if status_code_is_retry(status) {
-        if let Some(value) = headers.get(qcs_dependencies_client::http::header::RETRY_AFTER) {
-            if let Ok(value) = value.to_str() {
-                if let Ok(value) = value.parse::<u64>() {
-                    return Some(Duration::from_secs(value));
-                } else if let Ok(date) = OffsetDateTime::parse(value, &Rfc2822) {
-                    let duration = date - OffsetDateTime::now_utc();
-                    // Convert from time::Duration to std::time::Duration
-                    // This will fail if the number is too large or negative
-                    let std_duration: Duration = duration.try_into().ok()?;
-                    return Some(std_duration);
-                }
-            }
-        }
+/// Like [`duration_from_response`], but retries the HTTP status codes configured in `settings`
+/// rather than the default set.
+#[must_use]
+pub fn duration_from_response_with_settings(
+    status: StatusCode,
+    headers: &qcs_dependencies_client::http::HeaderMap,
+    backoff: &mut ExponentialBackoff,
+    settings: &RetrySettings,
+) -> Option<Duration> {
+    duration_from_retryable_response(settings.is_retryable_http_status(status), headers, backoff)
+}
 
-        backoff.next_backoff()
+fn duration_from_retryable_response(
+    retryable: bool,
+    headers: &qcs_dependencies_client::http::HeaderMap,
+    backoff: &mut ExponentialBackoff,
+) -> Option<Duration> {
+    if retryable {
+        duration_from_retry_after_or_backoff(headers, backoff)
     } else {
         None
     }
 }
 
+/// Return the duration specified by a `Retry-After` header, if any, otherwise the next backoff.
+fn duration_from_retry_after_or_backoff(
+    headers: &qcs_dependencies_client::http::HeaderMap,
+    backoff: &mut ExponentialBackoff,
+) -> Option<Duration> {
+    match parse_retry_after(headers) {
+        Some(RetryAfter::Delay(duration)) => Some(duration),
+        Some(RetryAfter::Invalid) => None,
+        None => backoff.next_backoff(),
+    }
+}
+
+/// Return the duration specified by a `Retry-After` header (or gRPC metadata entry), if it is
+/// present and specifies either a number of seconds or a date in the future.
+#[must_use]
+pub fn duration_from_retry_after(
+    headers: &qcs_dependencies_client::http::HeaderMap,
+) -> Option<Duration> {
+    match parse_retry_after(headers) {
+        Some(RetryAfter::Delay(duration)) => Some(duration),
+        Some(RetryAfter::Invalid) | None => None,
+    }
+}
+
+/// A parsed `Retry-After` header.
+enum RetryAfter {
+    /// The delay before the request may be retried.
+    Delay(Duration),
+    /// The header specifies a date that can't be converted to a delay, e.g. because it has
+    /// already passed.
+    Invalid,
+}
+
+/// Parse a `Retry-After` header, if there is one with a number of seconds or a date.
+fn parse_retry_after(headers: &qcs_dependencies_client::http::HeaderMap) -> Option<RetryAfter> {
+    use time::{OffsetDateTime, format_description::well_known::Rfc2822};
+
+    let value = headers
+        .get(qcs_dependencies_client::http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?;
+    if let Ok(value) = value.parse::<u64>() {
+        return Some(RetryAfter::Delay(Duration::from_secs(value)));
+    }
+
+    let date = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    let duration = date - OffsetDateTime::now_utc();
+    // Convert from time::Duration to std::time::Duration
+    // This will fail if the number is too large or negative
+    Some(
+        duration
+            .try_into()
+            .map_or(RetryAfter::Invalid, RetryAfter::Delay),
+    )
+}
+
 fn can_retry_method(method: &qcs_dependencies_client::http::Method) -> bool {
     // Safe means the method is essentially read-only (see https://datatracker.ietf.org/doc/html/rfc7231#section-4.2.1)
     // Idempotent means multiple identical requests have the same side-effects as a single one (see https://datatracker.ietf.org/doc/html/rfc7231#section-4.2.2)
@@ -82,20 +144,31 @@ pub fn duration_from_reqwest_error(
     error: &qcs_dependencies_client::reqwest::Error,
     backoff: &mut ExponentialBackoff,
 ) -> Option<Duration> {
-    if can_retry_method(method) {
-        if error.is_timeout()
-            || error.is_connect()
-            || error.is_request()
-            || error
-                .source()
-                .and_then(|inner| inner.downcast_ref::<hyper::Error>())
-                .map(|hyper_error| hyper_error.is_closed())
-                .unwrap_or_default()
-        {
-            backoff.next_backoff()
-        } else {
-            None
-        }
+    if can_retry_method(method) && reqwest_error_retry_cause(error).is_some() {
+        backoff.next_backoff()
+    } else {
+        None
+    }
+}
+
+/// The cause of a retry after `error`, e.g. `timeout`, or `None` if the error is not one that
+/// makes sense to retry. See [`duration_from_reqwest_error`].
+#[must_use]
+pub fn reqwest_error_retry_cause(
+    error: &qcs_dependencies_client::reqwest::Error,
+) -> Option<&'static str> {
+    if error.is_timeout() {
+        Some("timeout")
+    } else if error.is_connect() {
+        Some("connect")
+    } else if error.is_request() {
+        Some("request")
+    } else if error
+        .source()
+        .and_then(|inner| inner.downcast_ref::<hyper::Error>())
+        .is_some_and(hyper::Error::is_closed)
+    {
+        Some("connection_closed")
     } else {
         None
     }
@@ -109,17 +182,21 @@ pub fn duration_from_io_error(
     error: &std::io::Error,
     backoff: &mut ExponentialBackoff,
 ) -> Option<Duration> {
-    use std::io::ErrorKind;
-    if can_retry_method(method) {
-        if matches!(
-            error.kind(),
-            ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted
-        ) {
-            backoff.next_backoff()
-        } else {
-            None
-        }
+    if can_retry_method(method) && io_error_retry_cause(error).is_some() {
+        backoff.next_backoff()
     } else {
         None
     }
 }
+
+/// The cause of a retry after `error`, e.g. `connection_reset`, or `None` if the error is not
+/// one that makes sense to retry. See [`duration_from_io_error`].
+#[must_use]
+pub fn io_error_retry_cause(error: &std::io::Error) -> Option<&'static str> {
+    use std::io::ErrorKind;
+    match error.kind() {
+        ErrorKind::ConnectionReset => Some("connection_reset"),
+        ErrorKind::ConnectionAborted => Some("connection_aborted"),
+        _ => None,
+    }
+}
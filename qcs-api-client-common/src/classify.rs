@@ -0,0 +1,255 @@
+//! A coarse classification of the errors returned by the QCS API clients.
+//!
+//! The `OpenAPI` and gRPC clients report failures with different error types. Each of them, as well
+//! as [`TokenError`] and [`LoadError`], implements [`ClassifyError`], so callers can decide
+//! whether to retry, ask the user to log in again, or show a message without matching on every
+//! variant of every error type.
+
+use std::borrow::Cow;
+
+use qcs_dependencies_client::http::StatusCode;
+
+use crate::configuration::error::DiscoveryError;
+use crate::configuration::secrets::SECRETS_READ_ONLY_VAR;
+use crate::configuration::tokens::PkceFlowError;
+use crate::configuration::{LoadError, TokenError};
+
+/// The broad category of a QCS API client error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// The request lacked valid credentials; logging in again may resolve the error.
+    AuthRequired,
+    /// The credentials are valid, but do not grant access to the requested resource.
+    PermissionDenied,
+    /// The requested resource does not exist.
+    NotFound,
+    /// The request or the client configuration is invalid, and retrying it will not help.
+    InvalidInput,
+    /// The request failed due to a temporary condition and may succeed if retried.
+    Transient,
+    /// A quota or rate limit was exceeded.
+    Quota,
+    /// An unexpected error occurred on the client or the server.
+    Internal,
+}
+
+impl ErrorCategory {
+    /// Classify an HTTP response status code.
+    #[must_use]
+    pub fn from_http_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::AuthRequired,
+            StatusCode::FORBIDDEN => Self::PermissionDenied,
+            StatusCode::NOT_FOUND | StatusCode::GONE => Self::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => Self::Quota,
+            StatusCode::REQUEST_TIMEOUT
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => Self::Transient,
+            status if status.is_client_error() => Self::InvalidInput,
+            _ => Self::Internal,
+        }
+    }
+
+    /// Classify an error returned by [`reqwest`](qcs_dependencies_client::reqwest).
+    #[must_use]
+    pub fn from_reqwest_error(error: &qcs_dependencies_client::reqwest::Error) -> Self {
+        error.status().map_or_else(
+            || {
+                if error.is_timeout() || error.is_connect() || error.is_request() {
+                    Self::Transient
+                } else if error.is_builder() {
+                    Self::InvalidInput
+                } else {
+                    Self::Internal
+                }
+            },
+            Self::from_http_status,
+        )
+    }
+
+    /// Return `true` if a request which failed with this category of error may succeed if it is
+    /// retried later.
+    #[must_use]
+    pub const fn is_retryable(self) -> bool {
+        matches!(self, Self::Transient | Self::Quota)
+    }
+
+    /// A generic suggestion to the user for resolving an error of this category.
+    #[must_use]
+    pub const fn hint(self) -> &'static str {
+        match self {
+            Self::AuthRequired => "Log in to QCS again to refresh your credentials.",
+            Self::PermissionDenied => {
+                "Your QCS account does not have access to this resource. Check that you are using the intended profile, or contact support."
+            }
+            Self::NotFound => {
+                "Check that the requested resource exists and that its ID is correct."
+            }
+            Self::InvalidInput => "Check the request and your QCS configuration for errors.",
+            Self::Transient => "This is likely a temporary problem. Please try again shortly.",
+            Self::Quota => "A QCS quota or rate limit was exceeded. Please try again later.",
+            Self::Internal => {
+                "An unexpected error occurred. If the problem persists, contact support and include the request ID if there is one."
+            }
+        }
+    }
+}
+
+/// Classifies an error returned by the QCS API clients into an [`ErrorCategory`].
+pub trait ClassifyError {
+    /// The broad category of the error.
+    fn category(&self) -> ErrorCategory;
+
+    /// The ID of the failed request, if known, which identifies it in the QCS server logs.
+    /// Include this when reporting a problem to support.
+    fn server_request_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// A suggestion to the user for resolving the error.
+    fn hint(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.category().hint())
+    }
+
+    /// Return `true` if the failed request may succeed if it is retried later.
+    fn is_retryable(&self) -> bool {
+        self.category().is_retryable()
+    }
+}
+
+impl ClassifyError for TokenError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::NoRefreshToken
+            | Self::NoAccessToken
+            | Self::NoCredentials
+            | Self::InvalidAccessToken(_)
+            | Self::NoAuthServer
+            | Self::ExternallyManaged(_)
+            | Self::EnvVar { .. } => ErrorCategory::AuthRequired,
+            Self::Fetch(error) => match ErrorCategory::from_reqwest_error(error) {
+                // The auth server rejects an expired or revoked refresh token as a bad request.
+                ErrorCategory::InvalidInput | ErrorCategory::PermissionDenied => {
+                    ErrorCategory::AuthRequired
+                }
+                category => category,
+            },
+            Self::Override(error) => error
+                .downcast_ref::<Self>()
+                .map_or(ErrorCategory::AuthRequired, Self::category),
+            Self::Write { .. } => ErrorCategory::Internal,
+            Self::Discovery(error) => error.category(),
+        }
+    }
+
+    fn hint(&self) -> Cow<'static, str> {
+        match self {
+            Self::Write { .. } => Cow::Owned(format!(
+                "The access token could not be saved to your QCS secrets file. Check the file's permissions, or set `{SECRETS_READ_ONLY_VAR}=true` to skip saving tokens."
+            )),
+            Self::EnvVar { variable_name, .. } => Cow::Owned(format!(
+                "Set the environment variable {variable_name} to a valid QCS access token."
+            )),
+            _ => Cow::Borrowed(self.category().hint()),
+        }
+    }
+}
+
+impl ClassifyError for DiscoveryError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Fetch(error) => ErrorCategory::from_reqwest_error(error),
+            Self::Url(_)
+            | Self::InvalidIssuer { .. }
+            | Self::IssuerMismatch { .. }
+            | Self::InvalidScopes(_) => ErrorCategory::InvalidInput,
+            Self::Json(_) | Self::InvalidDocument { .. } => ErrorCategory::Internal,
+        }
+    }
+}
+
+impl ClassifyError for PkceFlowError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::PkceLogin(_) => ErrorCategory::AuthRequired,
+            Self::Discovery(error) => error.category(),
+            Self::Request(error) => ErrorCategory::from_reqwest_error(error),
+        }
+    }
+}
+
+impl ClassifyError for LoadError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Load(_)
+            | Self::EnvVar { .. }
+            | Self::Path { .. }
+            | Self::Build(_)
+            | Self::ProfileNotFound(_)
+            | Self::AuthServerNotFound(_) => ErrorCategory::InvalidInput,
+            Self::Io(_) => ErrorCategory::Internal,
+            Self::PkceFlow(error) => error.category(),
+            #[cfg(feature = "tracing-config")]
+            Self::TracingFilterParseError(_) => ErrorCategory::InvalidInput,
+        }
+    }
+
+    fn hint(&self) -> Cow<'static, str> {
+        match self {
+            Self::Load(_) | Self::Path { .. } | Self::Build(_) => {
+                Cow::Borrowed("Check that your QCS settings and secrets files are valid.")
+            }
+            Self::ProfileNotFound(profile) => Cow::Owned(format!(
+                "Add the profile {profile} to your QCS settings file, or select an existing profile."
+            )),
+            Self::AuthServerNotFound(auth_server) => Cow::Owned(format!(
+                "Add the auth server {auth_server} to your QCS settings file, or select an existing one."
+            )),
+            _ => Cow::Borrowed(self.category().hint()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_status_categories() {
+        for (status, category) in [
+            (StatusCode::UNAUTHORIZED, ErrorCategory::AuthRequired),
+            (StatusCode::FORBIDDEN, ErrorCategory::PermissionDenied),
+            (StatusCode::NOT_FOUND, ErrorCategory::NotFound),
+            (StatusCode::BAD_REQUEST, ErrorCategory::InvalidInput),
+            (StatusCode::CONFLICT, ErrorCategory::InvalidInput),
+            (StatusCode::TOO_MANY_REQUESTS, ErrorCategory::Quota),
+            (StatusCode::SERVICE_UNAVAILABLE, ErrorCategory::Transient),
+            (StatusCode::INTERNAL_SERVER_ERROR, ErrorCategory::Internal),
+        ] {
+            assert_eq!(
+                ErrorCategory::from_http_status(status),
+                category,
+                "{status}"
+            );
+        }
+        assert!(ErrorCategory::Quota.is_retryable());
+        assert!(!ErrorCategory::NotFound.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_configuration_errors() {
+        assert_eq!(
+            TokenError::NoCredentials.category(),
+            ErrorCategory::AuthRequired
+        );
+        let error = TokenError::Override(Box::new(TokenError::NoRefreshToken));
+        assert_eq!(error.category(), ErrorCategory::AuthRequired);
+
+        let error = LoadError::ProfileNotFound("missing".to_string());
+        assert_eq!(error.category(), ErrorCategory::InvalidInput);
+        assert!(error.hint().contains("missing"));
+        assert!(error.server_request_id().is_none());
+    }
+}
@@ -89,6 +89,21 @@ pub enum TokenError {
     /// Catch all for errors returned from an [`super::ExternallyManaged`] refresh function.
     #[error("Failed to request an externally managed access token: {0}")]
     ExternallyManaged(String),
+    /// Failed to read an access token from an environment variable, see
+    /// [`super::tokens::EnvToken`].
+    #[error(
+        "Failed to read an access token from the environment variable {variable_name}: {message}"
+    )]
+    EnvVar {
+        /// The name of the environment variable.
+        variable_name: String,
+        /// The error message.
+        message: String,
+    },
+    /// Catch all for errors returned from the [`super::tokens::TokenRefresher`] of a
+    /// [`super::tokens::TokenOverride`].
+    #[error("Failed to get an access token from the token override: {0}")]
+    Override(#[source] Box<dyn Error + Send + Sync + 'static>),
     /// Failure writing the new access token to the secrets file.
     #[error(
         "Failed to write the new access token to the secrets file. Setting `{SECRETS_READ_ONLY_VAR}=true` in the environment will skip persistence of newly acquired tokens. Error details: {error}"
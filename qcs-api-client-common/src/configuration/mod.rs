@@ -22,6 +22,14 @@
 //! * [`QVM_URL_VAR`]: Override the URL used for requests to the QVM server.
 //! * [`API_URL_VAR`]: Override the URL used for requests to the QCS REST API server.
 //! * [`GRPC_API_URL_VAR`]: Override the URL used for requests to the QCS gRPC API.
+//! * [`HTTPS_PROXY_VAR`](proxy::HTTPS_PROXY_VAR), [`HTTP_PROXY_VAR`](proxy::HTTP_PROXY_VAR) and
+//!   [`NO_PROXY_VAR`](proxy::NO_PROXY_VAR): Set any proxy settings not given by the profile. See
+//!   [`ProxyConfig`](proxy::ProxyConfig).
+//! * [`ACCESS_TOKEN_VAR`]: Provide an access token when there are no configuration files, see
+//!   [`ClientConfiguration::load_default`].
+//!
+//! Each profile may also give [TLS settings](tls::TlsSettings), such as additional CA certificates
+//! and a client certificate for mutual TLS.
 //!
 //! The [`ClientConfiguration`] exposes an API for loading and accessing your
 //! configuration.
@@ -31,32 +39,39 @@ use crate::configuration::{secrets::SecretAccessToken, tokens::insecure_validate
 use crate::tracing_configuration::TracingConfiguration;
 use derive_builder::Builder;
 use std::{env, path::PathBuf};
+use tokio::sync::broadcast;
 use tokio_util::sync::CancellationToken;
 
 #[cfg(feature = "stubs")]
 use rigetti_pyo3::pyo3_stub_gen::derive::gen_stub_pyclass;
 
 use self::{
-    secrets::{Credential, Secrets, TokenPayload},
-    settings::Settings,
+    proxy::ProxyConfig,
+    secrets::{Credential, SECRETS_PATH_VAR, Secrets, TokenPayload},
+    settings::{ChannelSettings, RetrySettings, SETTINGS_PATH_VAR, Settings},
+    tls::TlsConfig,
 };
 
 pub(crate) mod error;
 pub mod fs;
 mod oidc;
 mod pkce;
+pub mod proxy;
 mod secret_string;
 pub mod secrets;
 pub mod settings;
+pub mod tls;
 pub mod tokens;
 
 pub use error::{LoadError, TokenError};
+pub use pkce::{BrowserLoginPresenter, ListenerStatus, LoginPresenter};
 #[cfg(feature = "python")]
 pub(crate) mod py;
 
 use settings::AuthServer;
 use tokens::{
-    OAuthGrant, OAuthSession, PkceFlow, RefreshToken, TokenDispatcher, persist_oauth_session,
+    ConnectionConfig, OAuthGrant, OAuthSession, PkceFlow, RefreshToken, TokenDispatcher,
+    TokenEvent, persist_oauth_session,
 };
 
 /// Default profile name.
@@ -99,6 +114,10 @@ fn env_or_default_quilc_url() -> String {
     env::var(QUILC_URL_VAR).unwrap_or_else(|_| DEFAULT_QUILC_URL.to_string())
 }
 
+/// Setting this environment variable provides an access token to [`ClientConfiguration::from_env_token`]
+/// and [`tokens::EnvToken`].
+pub const ACCESS_TOKEN_VAR: &str = "QCS_ACCESS_TOKEN";
+
 /// A configuration suitable for use as a QCS API Client.
 ///
 /// This configuration can be constructed in a few ways.
@@ -182,6 +201,26 @@ pub struct ClientConfiguration {
     #[builder_field_attr(gen_stub(skip))]
     source: ConfigSource,
 
+    /// Settings that control how the QCS API clients retry failed requests.
+    #[builder(default)]
+    #[builder_field_attr(gen_stub(skip))]
+    retry_settings: RetrySettings,
+
+    /// Proxy settings used by the QCS API clients. Defaults to the proxy environment variables.
+    #[builder(default = "ProxyConfig::from_env()")]
+    #[builder_field_attr(gen_stub(skip))]
+    proxy_config: ProxyConfig,
+
+    /// TLS settings used by the QCS API clients.
+    #[builder(default)]
+    #[builder_field_attr(gen_stub(skip))]
+    tls_config: TlsConfig,
+
+    /// HTTP/2 connection settings used by the QCS gRPC API clients.
+    #[builder(default)]
+    #[builder_field_attr(gen_stub(skip))]
+    channel_settings: ChannelSettings,
+
     /// Configuration for tracing of network API calls. If `None`, tracing is disabled.
     #[cfg(feature = "tracing-config")]
     #[builder(default)]
@@ -214,6 +253,10 @@ struct ConfigurationContext {
     /// The credentials name the [`ClientConfigurationBuilder`] was configured with, i.e. the key
     /// the profile's tokens live under in `secrets.toml`. See [`Self::source`].
     credentials_name: String,
+    /// How to reach the auth server before the final [`ClientConfiguration`] is built, according
+    /// to the [`ProxyConfig`] and [`TlsConfig`] the [`ClientConfigurationBuilder`] was configured
+    /// with.
+    connection_config: ConnectionConfig,
 }
 
 impl ConfigurationContext {
@@ -278,6 +321,9 @@ impl ConfigurationContext {
             _ => ConfigSource::Default,
         };
 
+        let proxy_config = profile.proxy.or_env();
+        let tls_config = profile.tls.load()?;
+
         let mut builder = ClientConfiguration::builder();
         builder
             .profile(profile_name)
@@ -286,7 +332,11 @@ impl ConfigurationContext {
             .api_url(api_url)
             .quilc_url(quilc_url)
             .qvm_url(qvm_url)
-            .grpc_api_url(grpc_api_url);
+            .grpc_api_url(grpc_api_url)
+            .retry_settings(profile.retry)
+            .proxy_config(proxy_config.clone())
+            .tls_config(tls_config.clone())
+            .channel_settings(profile.channel);
 
         #[cfg(feature = "tracing-config")]
         {
@@ -299,24 +349,36 @@ impl ConfigurationContext {
             credential,
             source,
             credentials_name,
+            connection_config: ConnectionConfig {
+                proxy: proxy_config,
+                tls: tls_config,
+            },
         })
     }
 }
 
-/// Persists `oauth_session` via [`persist_oauth_session`], logging a warning on failure instead of
+/// Persists the `dispatcher`'s session via [`persist_oauth_session`], logging a warning on failure instead of
 /// returning an error. A session that was just successfully refreshed or logged in is still valid
 /// and usable even if it can't be persisted, so a persistence failure shouldn't prevent returning
 /// it to the caller (mirroring how [`TokenError::Write`] is handled elsewhere).
+///
+/// The failure is also emitted as a [`TokenEvent::PersistFailed`] on the `dispatcher`. Since the
+/// dispatcher was only just created, the event is held for its first subscriber (see
+/// [`TokenDispatcher::subscribe`]).
 async fn persist_or_warn(
-    oauth_session: &OAuthSession,
+    dispatcher: &TokenDispatcher,
     source: &ConfigSource,
     credentials_name: &str,
 ) {
-    if let Err(_error) = persist_oauth_session(oauth_session, source, credentials_name).await {
+    let oauth_session = dispatcher.tokens().await;
+    if let Err(error) = persist_oauth_session(&oauth_session, source, credentials_name).await {
         #[cfg(feature = "tracing")]
         tracing::warn!(
-            "Refreshed QCS credentials but failed to persist them to the secrets file: {_error}"
+            "Refreshed QCS credentials but failed to persist them to the secrets file: {error}"
         );
+        dispatcher.emit_or_hold(TokenEvent::PersistFailed {
+            message: error.to_string(),
+        });
     }
 }
 
@@ -360,12 +422,91 @@ impl ClientConfiguration {
 
     /// Attempts to load config files
     ///
+    /// If a configuration file does not exist at its default location, and [`ACCESS_TOKEN_VAR`]
+    /// is set, this falls back to [`ClientConfiguration::from_env_token`], so that e.g. container
+    /// jobs need no `~/.qcs` directory.
+    ///
     /// # Errors
     ///
     /// See [`LoadError`]
     pub fn load_default() -> Result<Self, LoadError> {
-        let base_config = Self::load(None)?;
-        Ok(base_config)
+        match Self::load(None) {
+            Err(LoadError::Path { .. })
+                if env::var_os(SETTINGS_PATH_VAR).is_none()
+                    && env::var_os(SECRETS_PATH_VAR).is_none()
+                    && env::var_os(ACCESS_TOKEN_VAR).is_some() =>
+            {
+                #[cfg(feature = "tracing-config")]
+                tracing::debug!(
+                    "no QCS configuration files found, using the access token from {ACCESS_TOKEN_VAR}"
+                );
+                Self::from_env_token()
+            }
+            result => result,
+        }
+    }
+
+    /// Create a configuration which needs no configuration files, authenticating with the access
+    /// token in [`ACCESS_TOKEN_VAR`] (see [`tokens::EnvToken`]).
+    ///
+    /// All other settings take their defaults, unless overridden by the environment variables
+    /// listed in the [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// [`LoadError::EnvVar`] if [`ACCESS_TOKEN_VAR`] is not set, or see [`LoadError`].
+    pub fn from_env_token() -> Result<Self, LoadError> {
+        let env_token = tokens::EnvToken::new();
+        let access_token = env_token.read().map_err(|error| LoadError::EnvVar {
+            variable_name: ACCESS_TOKEN_VAR.to_string(),
+            message: error.to_string(),
+        })?;
+        Self::without_files(env_token.into(), access_token)
+    }
+
+    /// Create a configuration which needs no configuration files, authenticating with a fixed
+    /// access token (see [`tokens::StaticToken`]).
+    ///
+    /// All other settings take their defaults, unless overridden by the environment variables
+    /// listed in the [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// See [`LoadError`].
+    pub fn from_static_token(
+        access_token: impl Into<SecretAccessToken>,
+    ) -> Result<Self, LoadError> {
+        let access_token = access_token.into();
+        Self::without_files(
+            tokens::StaticToken::new(access_token.clone()).into(),
+            access_token,
+        )
+    }
+
+    /// Build a configuration from defaults and environment variables only, whose tokens are
+    /// managed by `tokens`.
+    fn without_files(
+        tokens: tokens::ExternallyManaged,
+        access_token: SecretAccessToken,
+    ) -> Result<Self, LoadError> {
+        let oauth_session = OAuthSession::from_externally_managed(
+            tokens,
+            AuthServer::default(),
+            Some(access_token),
+        );
+        let mut builder = Self::builder();
+        builder
+            .source(ConfigSource::Default)
+            .oauth_session(Some(oauth_session));
+
+        #[cfg(feature = "tracing-config")]
+        {
+            builder.tracing_configuration(
+                TracingConfiguration::from_env().map_err(LoadError::TracingFilterParseError)?,
+            );
+        }
+
+        Ok(builder.build()?)
     }
 
     /// Attempts to load a QCS configuration and creates a [`ClientConfiguration`] using the
@@ -381,7 +522,8 @@ impl ClientConfiguration {
     /// Attempts to load a QCS configuration and creates a [`ClientConfiguration`] using the
     /// specified profile. If no `profile_name` is provided, then a default configuration is
     /// loaded. When stored OAuth credentials are unavailable, this method falls back to an
-    /// interactive PKCE login flow.
+    /// interactive PKCE login flow, which opens the login page in the default browser (see
+    /// [`BrowserLoginPresenter`]).
     ///
     /// # Errors
     ///
@@ -389,6 +531,21 @@ impl ClientConfiguration {
     pub async fn load_with_login(
         cancel_token: CancellationToken,
         profile_name: Option<String>,
+    ) -> Result<Self, LoadError> {
+        Self::load_with_login_and_presenter(cancel_token, profile_name, &BrowserLoginPresenter)
+            .await
+    }
+
+    /// Like [`ClientConfiguration::load_with_login`], but the PKCE login flow is shown to the user
+    /// by the given `presenter`.
+    ///
+    /// # Errors
+    ///
+    /// See [`LoadError`]
+    pub async fn load_with_login_and_presenter(
+        cancel_token: CancellationToken,
+        profile_name: Option<String>,
+        presenter: &dyn LoginPresenter,
     ) -> Result<Self, LoadError> {
         let ConfigurationContext {
             mut builder,
@@ -396,6 +553,7 @@ impl ClientConfiguration {
             credential,
             source,
             credentials_name,
+            connection_config,
         } = ConfigurationContext::from_profile(profile_name)?;
 
         // If the stored access or refresh tokens are valid, skip the login flow
@@ -429,34 +587,48 @@ impl ClientConfiguration {
                 let mut refresh_token = RefreshToken::new(refresh_token);
 
                 // If the refresh token is valid, use it
-                if let Ok(access_token) = refresh_token.request_access_token(&auth_server).await {
-                    let oauth_session = OAuthSession::new(
+                if let Ok(access_token) = refresh_token
+                    .request_access_token_with_connection_config(&auth_server, &connection_config)
+                    .await
+                {
+                    let dispatcher = TokenDispatcher::from(OAuthSession::new(
                         OAuthGrant::RefreshToken(refresh_token),
                         auth_server,
                         Some(access_token),
-                    );
+                    ));
 
                     // Requesting a new access token may have rotated the refresh token.
-                    persist_or_warn(&oauth_session, &source, &credentials_name).await;
+                    persist_or_warn(&dispatcher, &source, &credentials_name).await;
 
-                    return Ok(builder.oauth_session(Some(oauth_session)).build()?);
+                    builder.oauth_session = Some(Some(dispatcher));
+                    return Ok(builder.build()?);
                 }
             }
         }
 
         // At this point the stored credentials are known to be invalid, so a login is required
-        let pkce_flow = PkceFlow::new_login_flow(cancel_token, &auth_server).await?;
+        let pkce_flow = PkceFlow::new_login_flow_with_connection_config(
+            cancel_token,
+            &auth_server,
+            presenter,
+            &connection_config,
+        )
+        .await?;
         let access_token = pkce_flow.access_token.clone();
-        let oauth_session =
-            OAuthSession::from_pkce_flow(pkce_flow, auth_server, Some(access_token));
+        let dispatcher = TokenDispatcher::from(OAuthSession::from_pkce_flow(
+            pkce_flow,
+            auth_server,
+            Some(access_token),
+        ));
 
         // Persist eagerly: without this, the freshly logged-in tokens are only saved once
         // something later triggers a dispatcher-managed refresh (e.g. the access token expiring
         // during a later call). If this process exits before that happens, the login is lost and
         // the next process is forced through the login flow again.
-        persist_or_warn(&oauth_session, &source, &credentials_name).await;
+        persist_or_warn(&dispatcher, &source, &credentials_name).await;
 
-        Ok(builder.oauth_session(Some(oauth_session)).build()?)
+        builder.oauth_session = Some(Some(dispatcher));
+        Ok(builder.build()?)
     }
 
     /// Attempts to load a QCS configuration and creates a [`ClientConfiguration`] using the
@@ -473,6 +645,7 @@ impl ClientConfiguration {
             credential,
             source: _,
             credentials_name: _,
+            connection_config: _,
         } = ConfigurationContext::from_profile(profile_name)?;
         let oauth_session = credential_to_oauth_session(credential, auth_server);
         Ok(builder.oauth_session(oauth_session).build()?)
@@ -528,6 +701,38 @@ impl ClientConfiguration {
         self.tracing_configuration.as_ref()
     }
 
+    /// Get the [`RetrySettings`] used by the QCS API clients.
+    #[must_use]
+    pub const fn retry_settings(&self) -> &RetrySettings {
+        &self.retry_settings
+    }
+
+    /// Get the [`ProxyConfig`] used by the QCS API clients.
+    #[must_use]
+    pub const fn proxy_config(&self) -> &ProxyConfig {
+        &self.proxy_config
+    }
+
+    /// Get the [`TlsConfig`] used by the QCS API clients.
+    #[must_use]
+    pub const fn tls_config(&self) -> &TlsConfig {
+        &self.tls_config
+    }
+
+    /// Get the [`ChannelSettings`] used by the QCS gRPC API clients.
+    #[must_use]
+    pub const fn channel_settings(&self) -> &ChannelSettings {
+        &self.channel_settings
+    }
+
+    /// How HTTP clients connect to the auth server.
+    fn connection_config(&self) -> ConnectionConfig {
+        ConnectionConfig {
+            proxy: self.proxy_config.clone(),
+            tls: self.tls_config.clone(),
+        }
+    }
+
     /// Get the source of the configuration.
     #[must_use]
     pub const fn source(&self) -> &ConfigSource {
@@ -567,13 +772,52 @@ impl ClientConfiguration {
                 #[cfg(feature = "tracing-config")]
                 tracing::debug!("Refreshing access token because current one is invalid: {e}");
                 dispatcher
-                    .refresh(self.source(), self.credentials_name())
+                    .refresh_with_connection_config(
+                        &self.connection_config(),
+                        self.source(),
+                        self.credentials_name(),
+                    )
                     .await
                     .map(|e| e.access_token().cloned())?
             }
         }
     }
 
+    /// Subscribe to [`TokenEvent`]s, such as refreshes, refresh token rotations, and failures to
+    /// refresh or persist tokens.
+    ///
+    /// Events are shared by all clones of this configuration. Only events emitted after
+    /// subscribing are received, except that the first subscriber also receives a
+    /// [`TokenEvent::PersistFailed`] from loading the configuration with
+    /// [`ClientConfiguration::load_with_login`]. Returns [`None`] if the configuration has no
+    /// [`OAuthSession`].
+    #[must_use]
+    pub fn subscribe_token_events(&self) -> Option<broadcast::Receiver<TokenEvent>> {
+        self.oauth_session.as_ref().map(TokenDispatcher::subscribe)
+    }
+
+    /// Emit a [`TokenEvent`] to this configuration's subscribers, if it has an [`OAuthSession`].
+    ///
+    /// This is used by API clients to report events observed outside of a refresh, such as
+    /// [`TokenEvent::AccessTokenRejected`].
+    pub fn emit_token_event(&self, event: TokenEvent) {
+        if let Some(dispatcher) = &self.oauth_session {
+            dispatcher.emit(event);
+        }
+    }
+
+    /// Return `true` if this configuration shares its [`OAuthSession`] with `other`, i.e. one was
+    /// cloned from the other, so that requests made with either use the same tokens.
+    /// Configurations without an [`OAuthSession`] are considered to share one.
+    #[must_use]
+    pub fn shares_oauth_session_with(&self, other: &Self) -> bool {
+        match (&self.oauth_session, &other.oauth_session) {
+            (Some(dispatcher), Some(other)) => dispatcher.is_same_session(other),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
     /// Refreshes the [`Tokens`] in use and returns the new bearer access token.
     ///
     /// # Errors
@@ -583,7 +827,11 @@ impl ClientConfiguration {
         self.oauth_session
             .as_ref()
             .ok_or(TokenError::NoRefreshToken)?
-            .refresh(self.source(), self.credentials_name())
+            .refresh_with_connection_config(
+                &self.connection_config(),
+                self.source(),
+                self.credentials_name(),
+            )
             .await
     }
 }
@@ -653,18 +901,22 @@ mod test {
     use tokio_util::sync::CancellationToken;
 
     use crate::configuration::{
-        API_URL_VAR, AuthServer, ClientConfiguration, DEFAULT_QUILC_URL, GRPC_API_URL_VAR,
-        OAuthGrant, OAuthSession, QUILC_URL_VAR, QVM_URL_VAR, RefreshToken,
-        expand_path_from_env_or_default, oidc,
-        pkce::tests::PkceTestServerHarness,
+        ACCESS_TOKEN_VAR, API_URL_VAR, AuthServer, ClientConfiguration, ConfigSource,
+        DEFAULT_PROFILE_NAME, DEFAULT_QUILC_URL, GRPC_API_URL_VAR, LoadError, OAuthGrant,
+        OAuthSession, QUILC_URL_VAR, QVM_URL_VAR, RefreshToken, expand_path_from_env_or_default,
+        oidc,
+        pkce::tests::{PkceTestServerHarness, RecordingLoginPresenter},
+        proxy::ProxyConfig,
         secrets::{
             SECRETS_PATH_VAR, SECRETS_READ_ONLY_VAR, SecretAccessToken, SecretRefreshToken, Secrets,
         },
-        settings::{SETTINGS_PATH_VAR, Settings},
-        tokens::{RefreshTokenResponse, TokenRefresher},
+        settings::{RetrySettings, SETTINGS_PATH_VAR, Settings},
+        tokens::{RefreshTokenResponse, TokenDispatcher, TokenEvent, TokenRefresher},
     };
 
-    use super::{settings::QCS_DEFAULT_AUTH_ISSUER_PRODUCTION, tokens::ClientCredentials};
+    use super::{
+        persist_or_warn, settings::QCS_DEFAULT_AUTH_ISSUER_PRODUCTION, tokens::ClientCredentials,
+    };
 
     #[test]
     fn expands_env_var() {
@@ -731,6 +983,99 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_uses_profile_retry_settings() {
+        let retry = RetrySettings {
+            max_elapsed_time_ms: 1000,
+            retryable_http_status_codes: vec![503],
+            ..RetrySettings::default()
+        };
+        let mut settings = Settings::default();
+        settings
+            .profiles
+            .get_mut(DEFAULT_PROFILE_NAME)
+            .expect("default settings should have a default profile")
+            .retry = retry.clone();
+
+        let config = ClientConfiguration::new(
+            settings,
+            Secrets::default(),
+            Some(DEFAULT_PROFILE_NAME.to_string()),
+        )
+        .expect("Should be able to build config.");
+
+        assert_eq!(config.retry_settings(), &retry);
+    }
+
+    #[test]
+    fn test_uses_profile_proxy_config() {
+        let proxy = ProxyConfig {
+            https_proxy: Some("http://proxy.example.com:3128".to_string()),
+            http_proxy: Some("http://proxy.example.com:3128".to_string()),
+            no_proxy: Some(".internal.example.com".to_string()),
+        };
+        let mut settings = Settings::default();
+        settings
+            .profiles
+            .get_mut(DEFAULT_PROFILE_NAME)
+            .expect("default settings should have a default profile")
+            .proxy = proxy.clone();
+
+        let config = ClientConfiguration::new(
+            settings,
+            Secrets::default(),
+            Some(DEFAULT_PROFILE_NAME.to_string()),
+        )
+        .expect("Should be able to build config.");
+
+        assert_eq!(config.proxy_config(), &proxy);
+    }
+
+    #[test]
+    fn test_loads_profile_tls_settings() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("ca.pem", "ca certificate")?;
+            let mut settings = Settings::default();
+            let profile = settings
+                .profiles
+                .get_mut(DEFAULT_PROFILE_NAME)
+                .expect("default settings should have a default profile");
+            profile.tls.ca_certificates = vec!["ca.pem".into()];
+            profile.tls.server_name = Some("qcs.internal.example.com".to_string());
+
+            let config = ClientConfiguration::new(
+                settings.clone(),
+                Secrets::default(),
+                Some(DEFAULT_PROFILE_NAME.to_string()),
+            )
+            .expect("Should be able to build config.");
+            assert_eq!(
+                config.tls_config().ca_certificates,
+                vec!["ca certificate".to_string()]
+            );
+            assert_eq!(
+                config.tls_config().server_name.as_deref(),
+                Some("qcs.internal.example.com")
+            );
+
+            settings
+                .profiles
+                .get_mut(DEFAULT_PROFILE_NAME)
+                .expect("default settings should have a default profile")
+                .tls
+                .ca_certificates = vec!["missing.pem".into()];
+            assert!(matches!(
+                ClientConfiguration::new(
+                    settings,
+                    Secrets::default(),
+                    Some(DEFAULT_PROFILE_NAME.to_string()),
+                ),
+                Err(LoadError::Path { .. })
+            ));
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_default_loads_settings_with_partial_profile_applications() {
         figment::Jail::expect_with(|jail| {
@@ -898,6 +1243,39 @@ token_type = "Bearer"
         );
     }
 
+    #[tokio::test]
+    async fn test_default_falls_back_to_env_token() {
+        let mut config = ClientConfiguration::builder().build().unwrap();
+        figment::Jail::expect_with(|jail| {
+            // Without configuration files, the access token is required.
+            let home = jail.directory().display().to_string();
+            jail.set_env("HOME", home);
+            assert!(matches!(
+                ClientConfiguration::load_default(),
+                Err(LoadError::Path { .. })
+            ));
+
+            jail.set_env(ACCESS_TOKEN_VAR, "env_access_token");
+            config = ClientConfiguration::load_default().unwrap();
+            assert!(matches!(config.source(), ConfigSource::Default));
+
+            // An explicitly configured file path is not ignored.
+            jail.set_env(
+                SETTINGS_PATH_VAR,
+                jail.directory().join("settings.toml").display(),
+            );
+            assert!(matches!(
+                ClientConfiguration::load_default(),
+                Err(LoadError::Path { .. })
+            ));
+            Ok(())
+        });
+        assert_eq!(
+            config.get_access_token().await.unwrap().unwrap(),
+            SecretAccessToken::from("env_access_token")
+        );
+    }
+
     #[derive(Clone, Debug, Serialize)]
     struct Claims {
         exp: i64,
@@ -1122,9 +1500,13 @@ access_token = ""
             runtime.block_on(async {
                 let cancel_token = CancellationToken::new();
                 // should load the configuration and perform a login flow
-                let configuration = ClientConfiguration::load_with_login(cancel_token, None)
-                    .await
-                    .expect("should load configuration");
+                let configuration = ClientConfiguration::load_with_login_and_presenter(
+                    cancel_token,
+                    None,
+                    &RecordingLoginPresenter::default(),
+                )
+                .await
+                .expect("should load configuration");
                 let oauth_session = configuration.refresh().await.expect("should refresh");
                 let token = oauth_session.validate().expect("token should be valid");
 
@@ -1251,9 +1633,13 @@ access_token = ""
 
                 // Deliberately do NOT call `.refresh()` afterward: `load_with_login` itself
                 // should persist the freshly logged-in tokens.
-                let configuration = ClientConfiguration::load_with_login(cancel_token, None)
-                    .await
-                    .expect("should perform a login flow");
+                let configuration = ClientConfiguration::load_with_login_and_presenter(
+                    cancel_token,
+                    None,
+                    &RecordingLoginPresenter::default(),
+                )
+                .await
+                .expect("should perform a login flow");
 
                 let oauth_session = configuration
                     .oauth_session()
@@ -1386,9 +1772,13 @@ refresh_token = "{initial_refresh_token}"
 
                 // The expired access token should be refreshed using the stored refresh token,
                 // without falling back to an interactive login flow.
-                let configuration = ClientConfiguration::load_with_login(cancel_token, None)
-                    .await
-                    .expect("should refresh using the stored refresh token");
+                let configuration = ClientConfiguration::load_with_login_and_presenter(
+                    cancel_token,
+                    None,
+                    &RecordingLoginPresenter::default(),
+                )
+                .await
+                .expect("should refresh using the stored refresh token");
 
                 oidc_mock.assert_async().await;
                 issuer_mock.assert_async().await;
@@ -1441,6 +1831,55 @@ refresh_token = "{initial_refresh_token}"
         });
     }
 
+    /// A failure to persist the tokens while logging in happens before anyone could subscribe to
+    /// the new configuration's events, so it is held for the first subscriber.
+    #[test]
+    fn test_login_persist_failure_is_held_for_first_subscriber() {
+        let runtime = tokio::runtime::Runtime::new().expect("should create runtime");
+
+        figment::Jail::expect_with(|jail| {
+            jail.set_env(SECRETS_READ_ONLY_VAR, "false");
+
+            // The tokens cannot be written to a secrets file which is not valid TOML.
+            jail.create_file("secrets.toml", "[credentials")
+                .expect("should create test secrets.toml");
+            let source = ConfigSource::File {
+                settings_path: jail.directory().join("settings.toml"),
+                secrets_path: jail.directory().join("secrets.toml"),
+            };
+
+            runtime.block_on(async {
+                let dispatcher = TokenDispatcher::from(OAuthSession::new(
+                    OAuthGrant::RefreshToken(RefreshToken::new(SecretRefreshToken::from(
+                        "refresh_token",
+                    ))),
+                    AuthServer::default(),
+                    Some(SecretAccessToken::from(Claims::new_valid().to_encoded())),
+                ));
+                persist_or_warn(&dispatcher, &source, "default").await;
+
+                let mut builder = ClientConfiguration::builder();
+                builder.oauth_session = Some(Some(dispatcher));
+                let configuration = builder.build().expect("should build configuration");
+
+                let mut events = configuration
+                    .subscribe_token_events()
+                    .expect("configuration should have an OAuth session");
+                assert!(
+                    matches!(events.try_recv(), Ok(TokenEvent::PersistFailed { .. })),
+                    "the first subscriber should receive the held PersistFailed event"
+                );
+
+                let mut later_events = configuration
+                    .subscribe_token_events()
+                    .expect("configuration should have an OAuth session");
+                assert!(later_events.try_recv().is_err());
+            });
+
+            Ok(())
+        });
+    }
+
     /// A profile's `credentials_name` may differ from the profile's own name, and several profiles
     /// may point at the same credential. Tokens are *read* from `credentials.<credentials_name>`,
     /// so they must also be *written* there.
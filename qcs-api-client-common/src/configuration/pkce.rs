@@ -15,9 +15,10 @@ use qcs_dependencies_client::http::{Response, StatusCode};
 
 use tokio::net::TcpListener;
 use tokio_util::sync::CancellationToken;
-use url::form_urlencoded;
+use url::{Url, form_urlencoded};
 
 use crate::configuration::oidc::{DISCOVERY_REQUIRED_SCOPE, Discovery};
+use crate::configuration::tokens::ConnectionConfig;
 
 /// The scheme for the redirect URL.
 const PKCE_REDIRECT_URL_SCHEME: &str = "http";
@@ -39,6 +40,62 @@ fn format_redirect_url(port: u16) -> RedirectUrl {
     )
 }
 
+/// The state of the local redirect listener used during a PKCE login.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ListenerStatus {
+    /// The listener is bound and waiting for the authorization server to redirect to `redirect_url`.
+    Listening {
+        /// The URL the authorization server will redirect to once the user has authenticated.
+        redirect_url: Url,
+    },
+    /// The listener received the redirect, and the authorization code is being exchanged for tokens.
+    RedirectReceived,
+}
+
+/// Presents a PKCE login to the user and receives progress updates as the login proceeds.
+///
+/// Applications that want control over how the login is shown (e.g. rendering the link in a
+/// notebook or a desktop dialog) can implement this trait and pass it to
+/// [`ClientConfiguration::load_with_login_and_presenter`](super::ClientConfiguration::load_with_login_and_presenter)
+/// or [`PkceFlow::new_login_flow_with_presenter`](super::tokens::PkceFlow::new_login_flow_with_presenter).
+/// The [`BrowserLoginPresenter`] is used by default.
+///
+/// Methods are called from within the login flow and should return quickly.
+pub trait LoginPresenter: std::fmt::Debug + Send + Sync {
+    /// Called with the URL the user must visit to authenticate.
+    fn present_authorization_url(&self, auth_url: &Url);
+
+    /// Called when the state of the local redirect listener changes.
+    fn listener_status(&self, status: &ListenerStatus) {
+        let _ = status;
+    }
+
+    /// Called once the login has completed and tokens have been acquired.
+    fn login_completed(&self) {}
+
+    /// Called if the login is cancelled before the redirect is received.
+    fn login_cancelled(&self) {}
+}
+
+/// The default [`LoginPresenter`], which prints the authorization URL and attempts to open it in
+/// the default browser.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BrowserLoginPresenter;
+
+impl LoginPresenter for BrowserLoginPresenter {
+    fn present_authorization_url(&self, auth_url: &Url) {
+        println!("Login to QCS by going to: {auth_url}");
+
+        // Attempt to open the URL in the default browser, or notify the user to do so manually.
+        if let Err(error) = webbrowser::open(auth_url.as_ref()) {
+            eprintln!(
+                "Failed to open URL in the default browser, please open it manually: {error}",
+            );
+        }
+    }
+}
+
 /// Errors that can occur while trying to perform a PKCE login.
 #[derive(Debug, thiserror::Error)]
 pub enum PkceLoginError {
@@ -79,12 +136,15 @@ pub(crate) struct PkceLoginRequest {
     /// The scopes to request in the token authorization to request.
     /// If `None`, all scopes from [`Discovery::scopes_supported`] will be requested.
     pub(crate) scopes: Option<Vec<String>>,
+    /// How to connect to the authorization server to exchange the authorization code for tokens.
+    pub(crate) connection_config: ConnectionConfig,
 }
 
-/// Launch a PKCE login, requiring the user to authenticate via browser.
+/// Launch a PKCE login, requiring the user to authenticate via the URL given to the `presenter`.
 pub(crate) async fn pkce_login(
     cancel_token: CancellationToken,
     request: PkceLoginRequest,
+    presenter: &dyn LoginPresenter,
 ) -> Result<PkceLoginResponse, PkceLoginError> {
     let redirect_port = request
         .redirect_port
@@ -95,6 +155,10 @@ pub(crate) async fn pkce_login(
         join_handle,
     } = RedirectListener::spawn(cancel_token, redirect_port).await?;
 
+    presenter.listener_status(&ListenerStatus::Listening {
+        redirect_url: redirect_url.url().clone(),
+    });
+
     let client = BasicClient::new(ClientId::new(request.client_id))
         .set_auth_uri(AuthUrl::from_url(request.discovery.authorization_endpoint))
         .set_token_uri(TokenUrl::from_url(request.discovery.token_endpoint))
@@ -118,23 +182,25 @@ pub(crate) async fn pkce_login(
         .add_scopes(scopes.into_iter().map(Scope::new))
         .url();
 
+    presenter.present_authorization_url(&auth_url);
+
     if cfg!(test) {
         // Tests are headless, and should use an oauth2 request that does not require entering credentials.
         let client = oauth2::reqwest::Client::new();
         println!("Requesting auth URL: {auth_url}");
         client.get(auth_url).send().await?.error_for_status()?;
-    } else {
-        println!("Login to QCS by going to: {auth_url}");
+    }
 
-        // Attempt to open the URL in the default browser, or notify the user to do so manually.
-        if let Err(error) = webbrowser::open(auth_url.as_ref()) {
-            eprintln!(
-                "Failed to open URL in the default browser, please open it manually: {error}",
-            );
+    let CodeStatePair { code, state } = match join_handle.await? {
+        Ok(pair) => pair,
+        Err(RedirectListenerError::Cancelled) => {
+            presenter.login_cancelled();
+            return Err(RedirectListenerError::Cancelled.into());
         }
-    }
+        Err(error) => return Err(error.into()),
+    };
 
-    let CodeStatePair { code, state } = join_handle.await??;
+    presenter.listener_status(&ListenerStatus::RedirectReceived);
 
     if state.secret() != csrf_token.secret() {
         return Err(PkceLoginError::CodeChallengeMismatch);
@@ -143,6 +209,11 @@ pub(crate) async fn pkce_login(
     let http_client = oauth2::reqwest::ClientBuilder::new()
         // Following redirects opens the client up to SSRF vulnerabilities.
         .redirect(oauth2::reqwest::redirect::Policy::none())
+        .proxy(request.connection_config.proxy.oauth2_reqwest_proxy()?);
+    let http_client = request
+        .connection_config
+        .tls
+        .configure_oauth2_reqwest(http_client)?
         .build()?;
 
     let token_result = client
@@ -151,6 +222,8 @@ pub(crate) async fn pkce_login(
         .request_async(&http_client)
         .await?;
 
+    presenter.login_completed();
+
     Ok(token_result)
 }
 
@@ -374,6 +447,55 @@ pub(in crate::configuration) mod tests {
         }
     }
 
+    /// The events a [`RecordingLoginPresenter`] has observed, in order.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub(in crate::configuration) enum LoginEvent {
+        AuthorizationUrl,
+        Listener(ListenerStatus),
+        Completed,
+        Cancelled,
+    }
+
+    /// A headless [`LoginPresenter`] that records the events it receives.
+    #[derive(Debug, Default)]
+    pub(in crate::configuration) struct RecordingLoginPresenter {
+        pub events: std::sync::Mutex<Vec<LoginEvent>>,
+    }
+
+    impl RecordingLoginPresenter {
+        fn record(&self, event: LoginEvent) {
+            self.events
+                .lock()
+                .expect("events lock should not be poisoned")
+                .push(event);
+        }
+
+        pub(in crate::configuration) fn events(&self) -> Vec<LoginEvent> {
+            self.events
+                .lock()
+                .expect("events lock should not be poisoned")
+                .clone()
+        }
+    }
+
+    impl LoginPresenter for RecordingLoginPresenter {
+        fn present_authorization_url(&self, _auth_url: &Url) {
+            self.record(LoginEvent::AuthorizationUrl);
+        }
+
+        fn listener_status(&self, status: &ListenerStatus) {
+            self.record(LoginEvent::Listener(status.clone()));
+        }
+
+        fn login_completed(&self) {
+            self.record(LoginEvent::Completed);
+        }
+
+        fn login_cancelled(&self) {
+            self.record(LoginEvent::Cancelled);
+        }
+    }
+
     #[tokio::test]
     #[serial_test::serial(oauth2_test_server)]
     async fn test_pkce_login() {
@@ -389,9 +511,11 @@ pub(in crate::configuration) mod tests {
             redirect_port: Some(redirect_port),
             discovery,
             scopes: None,
+            connection_config: ConnectionConfig::default(),
         };
 
-        let token_result = pkce_login(CancellationToken::new(), request)
+        let presenter = RecordingLoginPresenter::default();
+        let token_result = pkce_login(CancellationToken::new(), request, &presenter)
             .await
             .expect("pkce_login should succeed");
 
@@ -399,6 +523,63 @@ pub(in crate::configuration) mod tests {
 
         insecure_validate_token_exp(&access_token).expect("token should be valid");
 
+        assert_eq!(
+            presenter.events(),
+            vec![
+                LoginEvent::Listener(ListenerStatus::Listening {
+                    redirect_url: format_redirect_url(redirect_port).url().clone(),
+                }),
+                LoginEvent::AuthorizationUrl,
+                LoginEvent::Listener(ListenerStatus::RedirectReceived),
+                LoginEvent::Completed,
+            ]
+        );
+
         drop(server);
     }
+
+    #[tokio::test]
+    async fn test_pkce_login_cancelled() {
+        let server = httpmock::MockServer::start_async().await;
+        // The headless test login requests the authorization URL, but never redirects.
+        server
+            .mock_async(|when, then| {
+                when.method(httpmock::Method::GET).path("/authorize");
+                then.status(200);
+            })
+            .await;
+
+        let discovery = Discovery {
+            issuer: server.base_url().parse().unwrap(),
+            authorization_endpoint: server.url("/authorize").parse().unwrap(),
+            token_endpoint: server.url("/token").parse().unwrap(),
+            jwks_uri: server.url("/jwks").parse().unwrap(),
+            scopes_supported: vec![],
+        };
+        let request = PkceLoginRequest {
+            client_id: "client".to_string(),
+            // Bind to any available port, no redirect is expected.
+            redirect_port: Some(0),
+            discovery,
+            scopes: None,
+            connection_config: ConnectionConfig::default(),
+        };
+
+        let cancel_token = CancellationToken::new();
+        cancel_token.cancel();
+
+        let presenter = RecordingLoginPresenter::default();
+        let error = pkce_login(cancel_token, request, &presenter)
+            .await
+            .expect_err("pkce_login should be cancelled");
+
+        assert!(
+            matches!(
+                error,
+                PkceLoginError::RedirectListenerError(RedirectListenerError::Cancelled)
+            ),
+            "unexpected error: {error:?}"
+        );
+        assert_eq!(presenter.events().last(), Some(&LoginEvent::Cancelled));
+    }
 }
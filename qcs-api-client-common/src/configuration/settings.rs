@@ -1,17 +1,22 @@
 //! Models and utilities for managing QCS settings.
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use figment::providers::Format;
 use figment::{Figment, providers::Toml};
+use qcs_dependencies_client::http::StatusCode;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "stubs")]
 use pyo3_stub_gen::derive::gen_stub_pyclass;
 
+use crate::backoff::{ExponentialBackoff, ExponentialBackoffBuilder};
 use crate::configuration::error::DiscoveryError;
 use crate::configuration::oidc::{DISCOVERY_REQUIRED_SCOPE, fetch_discovery};
-use crate::configuration::tokens::default_http_client;
+use crate::configuration::proxy::ProxyConfig;
+use crate::configuration::tls::TlsSettings;
+use crate::configuration::tokens::{ConnectionConfig, default_http_client};
 
 use super::{
     DEFAULT_API_URL, DEFAULT_GRPC_API_URL, DEFAULT_PROFILE_NAME, DEFAULT_QUILC_URL,
@@ -113,6 +118,18 @@ pub struct Profile {
     /// Application specific settings.
     #[serde(default)]
     pub applications: Applications,
+    /// Retry and backoff settings used by the QCS API clients.
+    #[serde(default)]
+    pub retry: RetrySettings,
+    /// Proxy settings used by the QCS API clients.
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    /// TLS settings used by the QCS API clients.
+    #[serde(default)]
+    pub tls: TlsSettings,
+    /// HTTP/2 connection settings used by the QCS gRPC API clients.
+    #[serde(default)]
+    pub channel: ChannelSettings,
 }
 
 impl Default for Profile {
@@ -123,6 +140,10 @@ impl Default for Profile {
             auth_server_name: DEFAULT_PROFILE_NAME.to_string(),
             credentials_name: DEFAULT_PROFILE_NAME.to_string(),
             applications: Applications::default(),
+            retry: RetrySettings::default(),
+            proxy: ProxyConfig::default(),
+            tls: TlsSettings::default(),
+            channel: ChannelSettings::default(),
         }
     }
 }
@@ -199,7 +220,7 @@ impl AuthServer {
         client_id: String,
         issuer: String,
     ) -> Result<Self, DiscoveryError> {
-        let client = default_http_client()?;
+        let client = default_http_client(&ConnectionConfig::from_env())?;
         let discovery = fetch_discovery(&client, &issuer).await?;
         Ok(Self {
             client_id,
@@ -209,6 +230,210 @@ impl AuthServer {
     }
 }
 
+/// Settings that control how the QCS API clients retry failed requests.
+///
+/// In `settings.toml`, these are configured per profile:
+///
+/// ```toml
+/// [profiles.default.retry]
+/// initial_interval_ms = 100
+/// max_interval_ms = 1000
+/// max_elapsed_time_ms = 5000
+/// retryable_http_status_codes = [502, 503, 429]
+/// retryable_grpc_codes = ["UNAVAILABLE"]
+/// ```
+///
+/// The defaults match [`default_backoff`](crate::backoff::default_backoff).
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RetrySettings {
+    /// The delay before the first retry, in milliseconds.
+    #[serde(default = "default_initial_interval_ms")]
+    pub initial_interval_ms: u64,
+
+    /// The maximum delay between retries, in milliseconds.
+    #[serde(default = "default_max_interval_ms")]
+    pub max_interval_ms: u64,
+
+    /// The total time after which a request is no longer retried, in milliseconds.
+    #[serde(default = "default_max_elapsed_time_ms")]
+    pub max_elapsed_time_ms: u64,
+
+    /// HTTP status codes for which a request is retried.
+    #[serde(default = "default_retryable_http_status_codes")]
+    pub retryable_http_status_codes: Vec<u16>,
+
+    /// gRPC status codes for which a request is retried, by name (e.g. `"UNAVAILABLE"`).
+    #[serde(default = "default_retryable_grpc_codes")]
+    pub retryable_grpc_codes: Vec<String>,
+}
+
+impl RetrySettings {
+    /// Build the [`ExponentialBackoff`] described by these settings.
+    #[must_use]
+    pub fn backoff(&self) -> ExponentialBackoff {
+        ExponentialBackoffBuilder::new()
+            .with_initial_interval(Duration::from_millis(self.initial_interval_ms))
+            .with_max_interval(Duration::from_millis(self.max_interval_ms))
+            .with_max_elapsed_time(Some(Duration::from_millis(self.max_elapsed_time_ms)))
+            .build()
+    }
+
+    /// Return `true` if a request that failed with the given HTTP `status` should be retried.
+    #[must_use]
+    pub fn is_retryable_http_status(&self, status: StatusCode) -> bool {
+        self.retryable_http_status_codes.contains(&status.as_u16())
+    }
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            initial_interval_ms: default_initial_interval_ms(),
+            max_interval_ms: default_max_interval_ms(),
+            max_elapsed_time_ms: default_max_elapsed_time_ms(),
+            retryable_http_status_codes: default_retryable_http_status_codes(),
+            retryable_grpc_codes: default_retryable_grpc_codes(),
+        }
+    }
+}
+
+const fn default_initial_interval_ms() -> u64 {
+    crate::backoff::default::INITIAL_INTERVAL_MILLIS
+}
+
+const fn default_max_interval_ms() -> u64 {
+    30_000
+}
+
+const fn default_max_elapsed_time_ms() -> u64 {
+    300_000
+}
+
+fn default_retryable_http_status_codes() -> Vec<u16> {
+    vec![502, 503, 429]
+}
+
+fn default_retryable_grpc_codes() -> Vec<String> {
+    vec!["UNAVAILABLE".to_string()]
+}
+
+/// Settings for the HTTP/2 connections of the QCS gRPC API clients.
+///
+/// In `settings.toml`, these are configured per profile:
+///
+/// ```toml
+/// [profiles.default.channel]
+/// keepalive_interval_ms = 30000
+/// keepalive_timeout_ms = 10000
+/// connect_timeout_ms = 5000
+/// concurrency_limit = 64
+/// ```
+///
+/// The defaults send keepalive pings well within the idle timeout of the load balancers in front
+/// of the QCS gateway, so that long-running requests such as waiting on job results are not cut
+/// off, and detect dead connections without waiting on the operating system.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChannelSettings {
+    /// How often to send HTTP/2 keepalive pings, in milliseconds, or `0` to not send any.
+    #[serde(default = "default_keepalive_interval_ms")]
+    pub keepalive_interval_ms: u64,
+
+    /// How long to wait for a keepalive ping to be acknowledged before closing the connection,
+    /// in milliseconds.
+    #[serde(default = "default_keepalive_timeout_ms")]
+    pub keepalive_timeout_ms: u64,
+
+    /// Whether to send keepalive pings while no requests are in flight.
+    #[serde(default)]
+    pub keepalive_while_idle: bool,
+
+    /// How long to wait for a connection to be established, in milliseconds, or `0` to wait
+    /// indefinitely.
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+
+    /// Whether to set `TCP_NODELAY`, sending small messages without waiting to fill a packet.
+    #[serde(default = "default_tcp_nodelay")]
+    pub tcp_nodelay: bool,
+
+    /// The initial HTTP/2 flow control window of each stream, in bytes. If neither window size
+    /// is set, windows are sized adaptively based on the measured bandwidth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_stream_window_size: Option<u32>,
+
+    /// The initial HTTP/2 flow control window of each connection, in bytes. If neither window
+    /// size is set, windows are sized adaptively based on the measured bandwidth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_connection_window_size: Option<u32>,
+
+    /// The maximum number of requests in flight on a connection at once. Unlimited if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency_limit: Option<usize>,
+}
+
+impl ChannelSettings {
+    /// The interval between keepalive pings, if they are enabled.
+    #[must_use]
+    pub const fn keepalive_interval(&self) -> Option<Duration> {
+        match self.keepalive_interval_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    /// The timeout for keepalive pings to be acknowledged.
+    #[must_use]
+    pub const fn keepalive_timeout(&self) -> Duration {
+        Duration::from_millis(self.keepalive_timeout_ms)
+    }
+
+    /// The timeout for establishing a connection, if any.
+    #[must_use]
+    pub const fn connect_timeout(&self) -> Option<Duration> {
+        match self.connect_timeout_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    /// Whether HTTP/2 flow control windows should be sized adaptively.
+    #[must_use]
+    pub const fn adaptive_window(&self) -> bool {
+        self.initial_stream_window_size.is_none() && self.initial_connection_window_size.is_none()
+    }
+}
+
+impl Default for ChannelSettings {
+    fn default() -> Self {
+        Self {
+            keepalive_interval_ms: default_keepalive_interval_ms(),
+            keepalive_timeout_ms: default_keepalive_timeout_ms(),
+            keepalive_while_idle: false,
+            connect_timeout_ms: default_connect_timeout_ms(),
+            tcp_nodelay: default_tcp_nodelay(),
+            initial_stream_window_size: None,
+            initial_connection_window_size: None,
+            concurrency_limit: None,
+        }
+    }
+}
+
+const fn default_keepalive_interval_ms() -> u64 {
+    45_000
+}
+
+const fn default_keepalive_timeout_ms() -> u64 {
+    20_000
+}
+
+const fn default_connect_timeout_ms() -> u64 {
+    10_000
+}
+
+const fn default_tcp_nodelay() -> bool {
+    true
+}
+
 /// Settings for secondary applications used by QCS SDKs.
 #[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct Applications {
@@ -243,8 +468,12 @@ mod test {
     #![allow(clippy::result_large_err, reason = "happens in figment tests")]
 
     use std::path::PathBuf;
+    use std::time::Duration;
 
-    use super::{SETTINGS_PATH_VAR, Settings};
+    use super::{
+        ChannelSettings, DEFAULT_PROFILE_NAME, RetrySettings, SETTINGS_PATH_VAR, Settings,
+    };
+    use crate::backoff::default_backoff;
 
     #[test]
     fn returns_err_if_invalid_path_env() {
@@ -292,4 +521,80 @@ mod test {
             Ok(())
         });
     }
+
+    #[test]
+    fn test_loads_partial_retry_settings() {
+        figment::Jail::expect_with(|jail| {
+            let _ = jail.create_file(
+                "settings.toml",
+                r#"
+[profiles.default.retry]
+max_elapsed_time_ms = 5000
+retryable_grpc_codes = ["UNAVAILABLE", "RESOURCE_EXHAUSTED"]
+"#,
+            )?;
+            jail.set_env(SETTINGS_PATH_VAR, "settings.toml");
+            let loaded = Settings::load().expect("should load settings");
+            let expected = RetrySettings {
+                max_elapsed_time_ms: 5000,
+                retryable_grpc_codes: vec![
+                    "UNAVAILABLE".to_string(),
+                    "RESOURCE_EXHAUSTED".to_string(),
+                ],
+                ..RetrySettings::default()
+            };
+
+            assert_eq!(loaded.profiles[DEFAULT_PROFILE_NAME].retry, expected);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_loads_partial_channel_settings() {
+        figment::Jail::expect_with(|jail| {
+            let _ = jail.create_file(
+                "settings.toml",
+                r"
+[profiles.default.channel]
+keepalive_interval_ms = 0
+connect_timeout_ms = 5000
+initial_stream_window_size = 1048576
+concurrency_limit = 64
+",
+            )?;
+            jail.set_env(SETTINGS_PATH_VAR, "settings.toml");
+            let loaded = Settings::load().expect("should load settings");
+            let channel = &loaded.profiles[DEFAULT_PROFILE_NAME].channel;
+            let expected = ChannelSettings {
+                keepalive_interval_ms: 0,
+                connect_timeout_ms: 5000,
+                initial_stream_window_size: Some(1_048_576),
+                concurrency_limit: Some(64),
+                ..ChannelSettings::default()
+            };
+
+            assert_eq!(channel, &expected);
+            assert_eq!(channel.keepalive_interval(), None);
+            assert_eq!(channel.connect_timeout(), Some(Duration::from_secs(5)));
+            assert!(!channel.adaptive_window());
+            assert!(ChannelSettings::default().adaptive_window());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_default_retry_settings_match_default_backoff() {
+        let backoff = RetrySettings::default().backoff();
+        let expected = default_backoff();
+
+        assert_eq!(backoff.initial_interval, expected.initial_interval);
+        assert_eq!(backoff.max_interval, expected.max_interval);
+        assert_eq!(backoff.max_elapsed_time, expected.max_elapsed_time);
+        assert!((backoff.multiplier - expected.multiplier).abs() < f64::EPSILON);
+        assert!(
+            (backoff.randomization_factor - expected.randomization_factor).abs() < f64::EPSILON
+        );
+    }
 }
@@ -6,19 +6,24 @@ use jsonwebtoken::{Algorithm, DecodingKey, Validation};
 use oauth2::TokenResponse;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
-use tokio::sync::{Mutex, Notify, RwLock};
+use tokio::sync::{Mutex, Notify, RwLock, broadcast};
 use tokio_util::sync::CancellationToken;
 
 #[cfg(feature = "stubs")]
 use pyo3_stub_gen::derive::gen_stub_pyclass;
 
+#[cfg(feature = "tracing")]
+use super::DEFAULT_GRPC_API_URL;
 use super::{
-    ClientConfiguration, ConfigSource, TokenError, oidc, secrets::Secrets, settings::AuthServer,
+    ACCESS_TOKEN_VAR, ClientConfiguration, ConfigSource, TokenError, oidc, secrets::Secrets,
+    settings::AuthServer,
 };
 use crate::configuration::{
     error::{DiscoveryError, WriteError},
-    pkce::{PkceLoginError, PkceLoginRequest, pkce_login},
+    pkce::{BrowserLoginPresenter, LoginPresenter, PkceLoginError, PkceLoginRequest, pkce_login},
+    proxy::ProxyConfig,
     secrets::{Credential, SecretAccessToken, SecretRefreshToken, TokenPayload},
+    tls::TlsConfig,
 };
 #[cfg(feature = "tracing-config")]
 use crate::tracing_configuration::TracingConfiguration;
@@ -52,15 +57,27 @@ impl RefreshToken {
     /// # Errors
     ///
     /// See [`TokenError`]
+    ///
+    /// Connections to the authorization server use the proxy environment variables; see
+    /// [`ProxyConfig::from_env`].
     pub async fn request_access_token(
         &mut self,
         auth_server: &AuthServer,
+    ) -> Result<SecretAccessToken, TokenError> {
+        self.request_access_token_with_connection_config(auth_server, &ConnectionConfig::from_env())
+            .await
+    }
+
+    pub(crate) async fn request_access_token_with_connection_config(
+        &mut self,
+        auth_server: &AuthServer,
+        connection_config: &ConnectionConfig,
     ) -> Result<SecretAccessToken, TokenError> {
         if self.refresh_token.is_empty() {
             return Err(TokenError::NoRefreshToken);
         }
 
-        let client = default_http_client()?;
+        let client = default_http_client(connection_config)?;
         let token_url = oidc::fetch_discovery(&client, &auth_server.issuer)
             .await?
             .token_endpoint;
@@ -144,12 +161,24 @@ impl ClientCredentials {
     /// # Errors
     ///
     /// See [`TokenError`]
+    ///
+    /// Connections to the authorization server use the proxy environment variables; see
+    /// [`ProxyConfig::from_env`].
     pub async fn request_access_token(
         &self,
         auth_server: &AuthServer,
+    ) -> Result<SecretAccessToken, TokenError> {
+        self.request_access_token_with_connection_config(auth_server, &ConnectionConfig::from_env())
+            .await
+    }
+
+    pub(crate) async fn request_access_token_with_connection_config(
+        &self,
+        auth_server: &AuthServer,
+        connection_config: &ConnectionConfig,
     ) -> Result<SecretAccessToken, TokenError> {
         let request = ClientCredentialsRequest::new(None);
-        let client = default_http_client()?;
+        let client = default_http_client(connection_config)?;
 
         let url = oidc::fetch_discovery(&client, &auth_server.issuer)
             .await?
@@ -196,7 +225,11 @@ pub enum PkceFlowError {
 }
 
 impl PkceFlow {
-    /// Starts a new PKCE login flow to acquire a new set of tokens.
+    /// Starts a new PKCE login flow to acquire a new set of tokens, opening the authorization URL
+    /// in the default browser (see [`BrowserLoginPresenter`]).
+    ///
+    /// Connections to the authorization server use the proxy environment variables; see
+    /// [`ProxyConfig::from_env`].
     ///
     /// # Errors
     ///
@@ -204,10 +237,39 @@ impl PkceFlow {
     pub async fn new_login_flow(
         cancel_token: CancellationToken,
         auth_server: &AuthServer,
+    ) -> Result<Self, PkceFlowError> {
+        Self::new_login_flow_with_presenter(cancel_token, auth_server, &BrowserLoginPresenter).await
+    }
+
+    /// Like [`PkceFlow::new_login_flow`], but the `presenter` is responsible for showing the
+    /// authorization URL to the user and is notified as the login progresses.
+    ///
+    /// # Errors
+    ///
+    /// See [`PkceFlowError`]
+    pub async fn new_login_flow_with_presenter(
+        cancel_token: CancellationToken,
+        auth_server: &AuthServer,
+        presenter: &dyn LoginPresenter,
+    ) -> Result<Self, PkceFlowError> {
+        Self::new_login_flow_with_connection_config(
+            cancel_token,
+            auth_server,
+            presenter,
+            &ConnectionConfig::from_env(),
+        )
+        .await
+    }
+
+    pub(crate) async fn new_login_flow_with_connection_config(
+        cancel_token: CancellationToken,
+        auth_server: &AuthServer,
+        presenter: &dyn LoginPresenter,
+        connection_config: &ConnectionConfig,
     ) -> Result<Self, PkceFlowError> {
         let issuer = auth_server.issuer.clone();
 
-        let client = default_http_client()?;
+        let client = default_http_client(connection_config)?;
         let discovery = oidc::fetch_discovery(&client, &issuer).await?;
 
         let response = pkce_login(
@@ -217,7 +279,9 @@ impl PkceFlow {
                 redirect_port: None,
                 discovery,
                 scopes: auth_server.scopes.clone(),
+                connection_config: connection_config.clone(),
             },
+            presenter,
         )
         .await?;
 
@@ -237,13 +301,24 @@ impl PkceFlow {
     pub async fn request_access_token(
         &mut self,
         auth_server: &AuthServer,
+    ) -> Result<SecretAccessToken, TokenError> {
+        self.request_access_token_with_connection_config(auth_server, &ConnectionConfig::from_env())
+            .await
+    }
+
+    pub(crate) async fn request_access_token_with_connection_config(
+        &mut self,
+        auth_server: &AuthServer,
+        connection_config: &ConnectionConfig,
     ) -> Result<SecretAccessToken, TokenError> {
         if insecure_validate_token_exp(&self.access_token).is_ok() {
             return Ok(self.access_token.clone());
         }
 
         if let Some(refresh_token) = &mut self.refresh_token {
-            let access_token = refresh_token.request_access_token(auth_server).await?;
+            let access_token = refresh_token
+                .request_access_token_with_connection_config(auth_server, connection_config)
+                .await?;
             self.access_token.clone_from(&access_token);
             return Ok(access_token);
         }
@@ -308,15 +383,28 @@ impl OAuthGrant {
     async fn request_access_token(
         &mut self,
         auth_server: &AuthServer,
+        connection_config: &ConnectionConfig,
     ) -> Result<SecretAccessToken, TokenError> {
         match self {
-            Self::RefreshToken(tokens) => tokens.request_access_token(auth_server).await,
-            Self::ClientCredentials(tokens) => tokens.request_access_token(auth_server).await,
+            Self::RefreshToken(tokens) => {
+                tokens
+                    .request_access_token_with_connection_config(auth_server, connection_config)
+                    .await
+            }
+            Self::ClientCredentials(tokens) => {
+                tokens
+                    .request_access_token_with_connection_config(auth_server, connection_config)
+                    .await
+            }
             Self::ExternallyManaged(tokens) => tokens
                 .request_access_token(auth_server)
                 .await
                 .map_err(|e| TokenError::ExternallyManaged(e.to_string())),
-            Self::PkceFlow(tokens) => tokens.request_access_token(auth_server).await,
+            Self::PkceFlow(tokens) => {
+                tokens
+                    .request_access_token_with_connection_config(auth_server, connection_config)
+                    .await
+            }
         }
     }
 }
@@ -448,6 +536,20 @@ impl OAuthSession {
         self.access_token.as_ref().ok_or(TokenError::NoAccessToken)
     }
 
+    /// Get the refresh token held by the grant, if any.
+    ///
+    /// Both the PKCE and refresh-token grants can hold a refresh token that the auth server may
+    /// rotate on refresh.
+    pub(crate) fn refresh_token(&self) -> Option<&SecretRefreshToken> {
+        match &self.payload {
+            OAuthGrant::PkceFlow(payload) => {
+                payload.refresh_token.as_ref().map(|rt| &rt.refresh_token)
+            }
+            OAuthGrant::RefreshToken(payload) => Some(&payload.refresh_token),
+            OAuthGrant::ExternallyManaged(_) | OAuthGrant::ClientCredentials(_) => None,
+        }
+    }
+
     /// Get the payload used to request an access token.
     #[must_use]
     pub const fn payload(&self) -> &OAuthGrant {
@@ -456,12 +558,26 @@ impl OAuthSession {
 
     /// Request and return an updated access token using these credentials.
     ///
+    /// Connections to the authorization server use the proxy environment variables; see
+    /// [`ProxyConfig::from_env`].
+    ///
     /// # Errors
     ///
     /// See [`TokenError`]
     #[allow(clippy::missing_panics_doc)]
     pub async fn request_access_token(&mut self) -> Result<&SecretAccessToken, TokenError> {
-        let access_token = self.payload.request_access_token(&self.auth_server).await?;
+        self.request_access_token_with_connection_config(&ConnectionConfig::from_env())
+            .await
+    }
+
+    pub(crate) async fn request_access_token_with_connection_config(
+        &mut self,
+        connection_config: &ConnectionConfig,
+    ) -> Result<&SecretAccessToken, TokenError> {
+        let access_token = self
+            .payload
+            .request_access_token(&self.auth_server, connection_config)
+            .await?;
         Ok(self.access_token.insert(access_token))
     }
 
@@ -552,11 +668,7 @@ pub(crate) async fn persist_oauth_session(
     // Persist the fresh refresh token if the grant carries one, so that a rotated
     // refresh token isn't lost on the next load. Both the PKCE and refresh-token
     // grants can hold a refresh token that the auth server may have rotated.
-    let refresh_token = match &oauth_session.payload {
-        OAuthGrant::PkceFlow(payload) => payload.refresh_token.as_ref().map(|rt| &rt.refresh_token),
-        OAuthGrant::RefreshToken(payload) => Some(&payload.refresh_token),
-        OAuthGrant::ExternallyManaged(_) | OAuthGrant::ClientCredentials(_) => None,
-    };
+    let refresh_token = oauth_session.refresh_token();
 
     // Nothing to persist without an access token; this shouldn't happen for a session that was
     // just successfully refreshed or logged in, but there's nothing useful to write otherwise.
@@ -575,6 +687,35 @@ pub(crate) async fn persist_oauth_session(
     .await
 }
 
+/// The number of [`TokenEvent`]s buffered for each subscriber before the oldest are dropped.
+const TOKEN_EVENT_CAPACITY: usize = 16;
+
+/// An event in the lifecycle of the tokens managed by a [`TokenDispatcher`].
+///
+/// Subscribe to these with [`ClientConfiguration::subscribe_token_events`] or
+/// [`TokenDispatcher::subscribe`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TokenEvent {
+    /// A new access token was acquired.
+    Refreshed,
+    /// The auth server issued a new refresh token while refreshing the access token.
+    RefreshTokenRotated,
+    /// The access token could not be refreshed.
+    RefreshFailed {
+        /// A description of the failure.
+        message: String,
+    },
+    /// Refreshed tokens could not be written to the secrets file. The tokens are still usable,
+    /// but will be lost when the process exits.
+    PersistFailed {
+        /// A description of the failure.
+        message: String,
+    },
+    /// A QCS API rejected the access token as unauthenticated or unauthorized.
+    AccessTokenRejected,
+}
+
 /// A wrapper for [`OAuthSession`] that provides thread-safe access to the inner tokens.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "stubs", gen_stub_pyclass)]
@@ -586,6 +727,9 @@ pub struct TokenDispatcher {
     lock: Arc<RwLock<OAuthSession>>,
     refreshing: Arc<Mutex<bool>>,
     notify_refreshed: Arc<Notify>,
+    events: broadcast::Sender<TokenEvent>,
+    /// Events emitted before anyone subscribed, which are delivered to the first subscriber.
+    held_events: Arc<std::sync::Mutex<Vec<TokenEvent>>>,
 }
 
 impl From<OAuthSession> for TokenDispatcher {
@@ -594,6 +738,8 @@ impl From<OAuthSession> for TokenDispatcher {
             lock: Arc::new(RwLock::new(value)),
             refreshing: Arc::new(Mutex::new(false)),
             notify_refreshed: Arc::new(Notify::new()),
+            events: broadcast::channel(TOKEN_EVENT_CAPACITY).0,
+            held_events: Arc::default(),
         }
     }
 }
@@ -622,8 +768,16 @@ impl TokenDispatcher {
         self.use_tokens(Clone::clone).await
     }
 
+    /// Return `true` if `other` is a clone of this dispatcher, sharing the same tokens.
+    pub(crate) fn is_same_session(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.lock, &other.lock)
+    }
+
     /// Refreshes the tokens. Readers will be blocked until the refresh is complete.
     ///
+    /// Connections to the authorization server use the proxy environment variables; see
+    /// [`ProxyConfig::from_env`].
+    ///
     /// # Errors
     ///
     /// See [`TokenError`]
@@ -632,10 +786,64 @@ impl TokenDispatcher {
         source: &ConfigSource,
         credentials_name: &str,
     ) -> Result<OAuthSession, TokenError> {
-        self.managed_refresh(Self::perform_refresh, source, credentials_name)
+        self.refresh_with_connection_config(&ConnectionConfig::from_env(), source, credentials_name)
             .await
     }
 
+    /// Refreshes the tokens, connecting to the authorization server according to `connection_config`.
+    pub(crate) async fn refresh_with_connection_config(
+        &self,
+        connection_config: &ConnectionConfig,
+        source: &ConfigSource,
+        credentials_name: &str,
+    ) -> Result<OAuthSession, TokenError> {
+        let connection_config = connection_config.clone();
+        self.managed_refresh(
+            |lock| Self::perform_refresh(lock, connection_config),
+            source,
+            credentials_name,
+        )
+        .await
+    }
+
+    /// Subscribe to [`TokenEvent`]s emitted by this dispatcher and its clones.
+    ///
+    /// Only events emitted after subscribing are received, except that the first subscriber also
+    /// receives events from while the dispatcher was being created, such as a
+    /// [`TokenEvent::PersistFailed`] while logging in. A receiver that falls too far behind will
+    /// skip the oldest events; see [`broadcast::Receiver::recv`].
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<TokenEvent> {
+        let receiver = self.events.subscribe();
+        let held_events = std::mem::take(
+            &mut *self
+                .held_events
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        );
+        for event in held_events {
+            self.emit(event);
+        }
+        receiver
+    }
+
+    /// Emit a [`TokenEvent`] to all current subscribers.
+    pub(crate) fn emit(&self, event: TokenEvent) {
+        // An error only means there are no subscribers.
+        let _ = self.events.send(event);
+    }
+
+    /// Emit a [`TokenEvent`] to all current subscribers, or hold it for the first subscriber if
+    /// there are none yet.
+    pub(crate) fn emit_or_hold(&self, event: TokenEvent) {
+        if let Err(broadcast::error::SendError(event)) = self.events.send(event) {
+            self.held_events
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .push(event);
+        }
+    }
+
     /// Validate the access token, returning it if it is valid, or an error describing why it is
     /// invalid.
     ///
@@ -670,7 +878,23 @@ impl TokenDispatcher {
         *is_refreshing = true;
         drop(is_refreshing);
 
-        let oauth_session = refresh_fn(self.lock.clone()).await?;
+        let previous_refresh_token = self
+            .use_tokens(|tokens| tokens.refresh_token().cloned())
+            .await;
+
+        let refresh_result = refresh_fn(self.lock.clone()).await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_token_refresh(refresh_result.is_ok());
+        let oauth_session = refresh_result.inspect_err(|error| {
+            self.emit(TokenEvent::RefreshFailed {
+                message: error.to_string(),
+            });
+        })?;
+
+        self.emit(TokenEvent::Refreshed);
+        if oauth_session.refresh_token() != previous_refresh_token.as_ref() {
+            self.emit(TokenEvent::RefreshTokenRotated);
+        }
 
         let write_result = persist_oauth_session(&oauth_session, source, credentials_name).await;
 
@@ -680,6 +904,9 @@ impl TokenDispatcher {
 
         // If write failed, return error with the valid oauth_session
         if let Err(error) = write_result {
+            self.emit(TokenEvent::PersistFailed {
+                message: error.to_string(),
+            });
             return Err(TokenError::Write {
                 error,
                 oauth_session: Box::new(oauth_session),
@@ -695,9 +922,14 @@ impl TokenDispatcher {
     /// # Errors
     ///
     /// See [`TokenError`]
-    async fn perform_refresh(lock: Arc<RwLock<OAuthSession>>) -> Result<OAuthSession, TokenError> {
+    async fn perform_refresh(
+        lock: Arc<RwLock<OAuthSession>>,
+        connection_config: ConnectionConfig,
+    ) -> Result<OAuthSession, TokenError> {
         let mut credentials = lock.write().await;
-        credentials.request_access_token().await?;
+        credentials
+            .request_access_token_with_connection_config(&connection_config)
+            .await?;
         Ok(credentials.clone())
     }
 }
@@ -927,6 +1159,12 @@ pub trait TokenRefresher: Clone + std::fmt::Debug + Send {
     /// Get a fresh access token
     async fn refresh_access_token(&self) -> Result<SecretAccessToken, Self::Error>;
 
+    /// Notify subscribers of a [`TokenEvent`] observed outside of the refresher, such as an API
+    /// rejecting the access token. By default, the event is discarded.
+    fn emit_token_event(&self, event: TokenEvent) {
+        let _ = event;
+    }
+
     /// Get the base URL for requests
     #[cfg(feature = "tracing")]
     fn base_url(&self) -> &str;
@@ -983,6 +1221,10 @@ impl TokenRefresher for ClientConfiguration {
         Ok(Some(self.oauth_session().await?.access_token()?.clone()))
     }
 
+    fn emit_token_event(&self, event: TokenEvent) {
+        Self::emit_token_event(self, event);
+    }
+
     #[cfg(feature = "tracing")]
     fn base_url(&self) -> &str {
         &self.grpc_api_url
@@ -994,12 +1236,293 @@ impl TokenRefresher for ClientConfiguration {
     }
 }
 
-/// Get a default http client.
-pub(super) fn default_http_client()
--> Result<qcs_dependencies_client::reqwest::Client, qcs_dependencies_client::reqwest::Error> {
-    qcs_dependencies_client::reqwest::Client::builder()
+/// A [`TokenRefresher`] for a fixed access token, e.g. in integration tests.
+///
+/// The token is never refreshed; once it expires, requests are rejected.
+#[derive(Clone, Debug)]
+pub struct StaticToken {
+    access_token: SecretAccessToken,
+}
+
+impl StaticToken {
+    /// Create a refresher which always provides the given access token.
+    #[must_use]
+    pub fn new(access_token: impl Into<SecretAccessToken>) -> Self {
+        Self {
+            access_token: access_token.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenRefresher for StaticToken {
+    type Error = TokenError;
+
+    async fn validated_access_token(&self) -> Result<SecretAccessToken, Self::Error> {
+        Ok(self.access_token.clone())
+    }
+
+    async fn get_access_token(&self) -> Result<Option<SecretAccessToken>, Self::Error> {
+        Ok(Some(self.access_token.clone()))
+    }
+
+    async fn refresh_access_token(&self) -> Result<SecretAccessToken, Self::Error> {
+        Err(TokenError::NoRefreshToken)
+    }
+
+    #[cfg(feature = "tracing")]
+    fn base_url(&self) -> &str {
+        DEFAULT_GRPC_API_URL
+    }
+
+    #[cfg(feature = "tracing-config")]
+    fn tracing_configuration(&self) -> Option<&TracingConfiguration> {
+        None
+    }
+}
+
+impl From<StaticToken> for ExternallyManaged {
+    fn from(value: StaticToken) -> Self {
+        Self::from_sync(move |_| Ok(value.access_token.secret().to_string()))
+    }
+}
+
+/// A [`TokenRefresher`] which reads the access token from an environment variable, by default
+/// [`ACCESS_TOKEN_VAR`], e.g. in CI jobs.
+///
+/// The variable is read again whenever the token is requested, so that a token rotated by the
+/// surrounding process is picked up.
+#[derive(Clone, Debug)]
+pub struct EnvToken {
+    variable_name: String,
+}
+
+impl Default for EnvToken {
+    fn default() -> Self {
+        Self::from_var(ACCESS_TOKEN_VAR)
+    }
+}
+
+impl EnvToken {
+    /// Create a refresher which reads the access token from [`ACCESS_TOKEN_VAR`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a refresher which reads the access token from the given environment variable.
+    #[must_use]
+    pub fn from_var(variable_name: impl Into<String>) -> Self {
+        Self {
+            variable_name: variable_name.into(),
+        }
+    }
+
+    /// The name of the environment variable the access token is read from.
+    #[must_use]
+    pub fn variable_name(&self) -> &str {
+        &self.variable_name
+    }
+
+    /// Read the access token from the environment variable.
+    ///
+    /// # Errors
+    ///
+    /// [`TokenError::EnvVar`] if the variable is unset, empty or not valid unicode.
+    pub fn read(&self) -> Result<SecretAccessToken, TokenError> {
+        match std::env::var(&self.variable_name) {
+            Ok(access_token) if !access_token.is_empty() => Ok(access_token.into()),
+            Ok(_) => Err(TokenError::EnvVar {
+                variable_name: self.variable_name.clone(),
+                message: "the variable is empty".to_string(),
+            }),
+            Err(error) => Err(TokenError::EnvVar {
+                variable_name: self.variable_name.clone(),
+                message: error.to_string(),
+            }),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenRefresher for EnvToken {
+    type Error = TokenError;
+
+    async fn validated_access_token(&self) -> Result<SecretAccessToken, Self::Error> {
+        self.read()
+    }
+
+    async fn get_access_token(&self) -> Result<Option<SecretAccessToken>, Self::Error> {
+        self.read().map(Some)
+    }
+
+    async fn refresh_access_token(&self) -> Result<SecretAccessToken, Self::Error> {
+        self.read()
+    }
+
+    #[cfg(feature = "tracing")]
+    fn base_url(&self) -> &str {
+        DEFAULT_GRPC_API_URL
+    }
+
+    #[cfg(feature = "tracing-config")]
+    fn tracing_configuration(&self) -> Option<&TracingConfiguration> {
+        None
+    }
+}
+
+impl From<EnvToken> for ExternallyManaged {
+    fn from(value: EnvToken) -> Self {
+        Self::from_sync(move |_| Ok(value.read()?.secret().to_string()))
+    }
+}
+
+/// Credentials that take the place of a client's own tokens for a single request.
+///
+/// This allows one client (and its connections) to be shared by many users: each request carries
+/// the credentials of the user it is made on behalf of. For gRPC, insert it into the extensions
+/// of a `tonic::Request`; for the `OpenAPI` client, pass it to
+/// `Configuration::with_token_override`.
+///
+/// If a QCS API rejects the access token, a [`TokenOverride::refresher`] is asked for a fresh
+/// one and the request is sent once more, just as with the client's own tokens. A fixed
+/// [`TokenOverride::access_token`] cannot be refreshed, so the rejection is returned as is.
+#[derive(Clone, Debug)]
+pub struct TokenOverride(TokenOverrideInner);
+
+#[derive(Clone, Debug)]
+enum TokenOverrideInner {
+    AccessToken(SecretAccessToken),
+    Refresher(Arc<dyn DynTokenRefresher>),
+}
+
+impl TokenOverride {
+    /// Authenticate with the given access token, which is never refreshed.
+    #[must_use]
+    pub fn access_token(access_token: impl Into<SecretAccessToken>) -> Self {
+        Self(TokenOverrideInner::AccessToken(access_token.into()))
+    }
+
+    /// Authenticate with the access tokens of the given [`TokenRefresher`].
+    #[must_use]
+    pub fn refresher<T>(refresher: T) -> Self
+    where
+        T: TokenRefresher + Sync + 'static,
+        T::Error: std::error::Error + Send + Sync + 'static,
+    {
+        Self(TokenOverrideInner::Refresher(Arc::new(refresher)))
+    }
+
+    /// Get the access token to authenticate a request with.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the refresher fails to provide a valid access token.
+    pub async fn validated_access_token(&self) -> Result<SecretAccessToken, TokenError> {
+        match &self.0 {
+            TokenOverrideInner::AccessToken(access_token) => Ok(access_token.clone()),
+            TokenOverrideInner::Refresher(refresher) => refresher.validated_access_token().await,
+        }
+    }
+
+    /// Returns whether a fresh access token can be requested with
+    /// [`TokenOverride::refresh_access_token`].
+    #[must_use]
+    pub const fn is_refreshable(&self) -> bool {
+        matches!(self.0, TokenOverrideInner::Refresher(_))
+    }
+
+    /// Get a fresh access token after the current one was rejected.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TokenError::NoRefreshToken`] if the override is a fixed access token, or if
+    /// the refresher fails to refresh the access token.
+    pub async fn refresh_access_token(&self) -> Result<SecretAccessToken, TokenError> {
+        match &self.0 {
+            TokenOverrideInner::AccessToken(_) => Err(TokenError::NoRefreshToken),
+            TokenOverrideInner::Refresher(refresher) => refresher.refresh_access_token().await,
+        }
+    }
+
+    /// Notify the refresher's subscribers of a [`TokenEvent`], if the override has a refresher.
+    pub fn emit_token_event(&self, event: TokenEvent) {
+        if let TokenOverrideInner::Refresher(refresher) = &self.0 {
+            refresher.emit_token_event(event);
+        }
+    }
+}
+
+/// An object-safe view of a [`TokenRefresher`], with its errors converted into [`TokenError`]s.
+#[async_trait::async_trait]
+trait DynTokenRefresher: std::fmt::Debug + Send + Sync {
+    async fn validated_access_token(&self) -> Result<SecretAccessToken, TokenError>;
+
+    async fn refresh_access_token(&self) -> Result<SecretAccessToken, TokenError>;
+
+    fn emit_token_event(&self, event: TokenEvent);
+}
+
+#[async_trait::async_trait]
+impl<T> DynTokenRefresher for T
+where
+    T: TokenRefresher + Sync + 'static,
+    T::Error: std::error::Error + Send + Sync + 'static,
+{
+    async fn validated_access_token(&self) -> Result<SecretAccessToken, TokenError> {
+        TokenRefresher::validated_access_token(self)
+            .await
+            .map_err(into_token_error)
+    }
+
+    async fn refresh_access_token(&self) -> Result<SecretAccessToken, TokenError> {
+        TokenRefresher::refresh_access_token(self)
+            .await
+            .map_err(into_token_error)
+    }
+
+    fn emit_token_event(&self, event: TokenEvent) {
+        TokenRefresher::emit_token_event(self, event);
+    }
+}
+
+/// Convert the error of a [`TokenRefresher`], keeping it as is if it already is a [`TokenError`].
+fn into_token_error<E>(error: E) -> TokenError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let error: Box<dyn std::error::Error + Send + Sync> = Box::new(error);
+    match error.downcast::<TokenError>() {
+        Ok(error) => *error,
+        Err(error) => TokenError::Override(error),
+    }
+}
+
+/// How HTTP clients connect to the authorization server.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ConnectionConfig {
+    pub(crate) proxy: ProxyConfig,
+    pub(crate) tls: TlsConfig,
+}
+
+impl ConnectionConfig {
+    /// Connect according to the proxy environment variables, with the default TLS settings.
+    pub(crate) fn from_env() -> Self {
+        Self {
+            proxy: ProxyConfig::from_env(),
+            tls: TlsConfig::default(),
+        }
+    }
+}
+
+/// Get a default http client, which connects according to the given [`ConnectionConfig`].
+pub(super) fn default_http_client(
+    connection_config: &ConnectionConfig,
+) -> Result<qcs_dependencies_client::reqwest::Client, qcs_dependencies_client::reqwest::Error> {
+    let builder = qcs_dependencies_client::reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
-        .build()
+        .proxy(connection_config.proxy.reqwest_proxy()?);
+    connection_config.tls.configure_reqwest(builder)?.build()
 }
 
 #[cfg(test)]
@@ -1147,6 +1670,77 @@ mod test {
         );
     }
 
+    /// Refreshes a [`TokenDispatcher`] against an auth server that responds to token requests
+    /// with `status`, returning the refresh result and every [`TokenEvent`] emitted.
+    #[allow(clippy::future_not_send, reason = "httpmock mocks are not Send")]
+    async fn refresh_and_collect_events(
+        status: u16,
+    ) -> (Result<OAuthSession, TokenError>, Vec<TokenEvent>) {
+        let mock_server = MockServer::start_async().await;
+
+        mock_server
+            .mock_async(|when, then| {
+                when.method(GET).path("/.well-known/openid-configuration");
+                then.status(200)
+                    .json_body_obj(&oidc::Discovery::new_for_test(
+                        mock_server.base_url().parse().unwrap(),
+                    ));
+            })
+            .await;
+
+        mock_server
+            .mock_async(|when, then| {
+                when.method(POST).path("/v1/token");
+                then.status(status).json_body_obj(&RefreshTokenResponse {
+                    access_token: SecretAccessToken::from("new_access"),
+                    refresh_token: Some(SecretRefreshToken::from("new_refresh")),
+                });
+            })
+            .await;
+
+        let dispatcher = TokenDispatcher::from(OAuthSession::from_refresh_token(
+            RefreshToken::new(SecretRefreshToken::from("refresh")),
+            AuthServer {
+                client_id: "client_id".to_string(),
+                issuer: mock_server.base_url(),
+                scopes: None,
+            },
+            None,
+        ));
+        let mut receiver = dispatcher.subscribe();
+
+        let result = dispatcher.refresh(&ConfigSource::Default, "").await;
+
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+
+        (result, events)
+    }
+
+    #[tokio::test]
+    async fn test_token_events_emitted_on_refresh() {
+        let (result, events) = refresh_and_collect_events(200).await;
+
+        result.expect("refresh should succeed");
+        assert_eq!(
+            events,
+            vec![TokenEvent::Refreshed, TokenEvent::RefreshTokenRotated]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_events_emitted_on_refresh_failure() {
+        let (result, events) = refresh_and_collect_events(400).await;
+
+        result.expect_err("refresh should fail");
+        assert!(
+            matches!(events.as_slice(), [TokenEvent::RefreshFailed { .. }]),
+            "expected a single RefreshFailed event, got {events:?}"
+        );
+    }
+
     #[rstest]
     fn test_qcs_secrets_readonly(
         #[values(
@@ -1424,4 +2018,47 @@ updated_at = "2024-01-01T00:00:00Z"
             &format!("{session:?}")
         );
     }
+
+    #[tokio::test]
+    async fn test_static_and_env_tokens() {
+        let static_token = StaticToken::new("static_token");
+        assert_eq!(
+            TokenRefresher::validated_access_token(&static_token)
+                .await
+                .unwrap(),
+            SecretAccessToken::from("static_token")
+        );
+
+        let env_token = EnvToken::from_var("TEST_QCS_ACCESS_TOKEN");
+        figment::Jail::expect_with(|jail| {
+            assert!(matches!(env_token.read(), Err(TokenError::EnvVar { .. })));
+            jail.set_env("TEST_QCS_ACCESS_TOKEN", "env_token");
+            assert_eq!(
+                env_token.read().unwrap(),
+                SecretAccessToken::from("env_token")
+            );
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    async fn test_token_override() {
+        let fixed = TokenOverride::access_token("fixed_token");
+        assert_eq!(
+            fixed.validated_access_token().await.unwrap(),
+            SecretAccessToken::from("fixed_token")
+        );
+        assert!(!fixed.is_refreshable());
+        assert!(matches!(
+            fixed.refresh_access_token().await,
+            Err(TokenError::NoRefreshToken)
+        ));
+
+        // A refresher's TokenErrors are passed through as is.
+        let refresher = TokenOverride::refresher(ClientConfiguration::builder().build().unwrap());
+        assert!(matches!(
+            refresher.validated_access_token().await,
+            Err(TokenError::NoCredentials)
+        ));
+    }
 }
@@ -19,17 +19,23 @@
 //!
 //! # Features
 //!
+//! - `metrics`: records request, retry, and token refresh metrics through the `metrics` facade.
+//!   See the `metrics` module.
 //! - `tracing`: enables `tracing` support in [`ClientConfiguration`].
 //! - `tracing-config`: enables [`TracingConfiguration`] support for enabling/disabling traces per-URL.
 //!   Requires the `tracing` feature.
 //! - `python`: enables Python bindings for the client.
 pub mod backoff;
+pub mod classify;
 pub mod configuration;
 pub use configuration::ClientConfiguration;
 
 #[cfg(feature = "clap")]
 pub mod clap_utils;
 
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
 #[cfg(feature = "tracing-config")]
 pub mod tracing_configuration;
 
@@ -0,0 +1,124 @@
+// Copyright 2026 Rigetti Computing
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metrics emitted by the QCS API clients.
+//!
+//! Metrics are recorded through the [`metrics`] facade, so nothing is collected until the
+//! application installs a recorder (for example, `metrics-exporter-prometheus`, or a bridge to
+//! OpenTelemetry meters).
+//!
+//! | Name                       | Type      | Labels                           |
+//! |----------------------------|-----------|----------------------------------|
+//! | [`REQUEST_DURATION`]       | histogram | `client`, `operation`, `status`  |
+//! | [`REQUESTS`]               | counter   | `client`, `operation`, `status`  |
+//! | [`RETRIES`]                | counter   | `client`, `operation`, `cause`   |
+//! | [`TOKEN_REFRESHES`]        | counter   | `outcome`                        |
+//! | [`BYTES_SENT`]             | counter   | `client`, `operation`            |
+//! | [`BYTES_RECEIVED`]         | counter   | `client`, `operation`            |
+//!
+//! The `client` label is either `openapi` or `grpc`. The `operation` label is the `OpenAPI`
+//! operation name (e.g. `get_endpoint`) or the gRPC method path (e.g.
+//! `/services.controller.Controller/ExecuteControllerJob`). The `status` label is the HTTP status
+//! code for `OpenAPI` requests and the gRPC status code name for gRPC requests, or `error` if no
+//! response was received.
+
+use std::time::Duration;
+
+use ::metrics::{SharedString, counter, histogram};
+
+/// Histogram of the time taken by a single request attempt, in seconds.
+pub const REQUEST_DURATION: &str = "qcs_api_client_request_duration_seconds";
+/// Counter of completed request attempts.
+pub const REQUESTS: &str = "qcs_api_client_requests_total";
+/// Counter of retries scheduled by the clients' backoff logic.
+pub const RETRIES: &str = "qcs_api_client_retries_total";
+/// Counter of access token refreshes.
+pub const TOKEN_REFRESHES: &str = "qcs_api_client_token_refreshes_total";
+/// Counter of request body bytes sent.
+pub const BYTES_SENT: &str = "qcs_api_client_bytes_sent_total";
+/// Counter of response body bytes received.
+pub const BYTES_RECEIVED: &str = "qcs_api_client_bytes_received_total";
+
+/// The `status` label used when a request failed without a response.
+pub const STATUS_ERROR: &str = "error";
+
+/// The client that made a request, used as the `client` label.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Client {
+    /// The `OpenAPI` (REST) client.
+    OpenApi,
+    /// The gRPC client.
+    Grpc,
+}
+
+impl Client {
+    /// The label value for this client.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::OpenApi => "openapi",
+            Self::Grpc => "grpc",
+        }
+    }
+}
+
+/// Record a completed request attempt.
+pub fn record_request(
+    client: Client,
+    operation: impl Into<SharedString>,
+    status: impl Into<SharedString>,
+    duration: Duration,
+) {
+    let labels = [
+        ("client", SharedString::from(client.as_str())),
+        ("operation", operation.into()),
+        ("status", status.into()),
+    ];
+    histogram!(REQUEST_DURATION, &labels).record(duration.as_secs_f64());
+    counter!(REQUESTS, &labels).increment(1);
+}
+
+/// Record the number of bytes sent in a request body.
+pub fn record_bytes_sent(client: Client, operation: impl Into<SharedString>, bytes: u64) {
+    counter!(BYTES_SENT, "client" => client.as_str(), "operation" => operation.into())
+        .increment(bytes);
+}
+
+/// Record the number of bytes received in a response body.
+pub fn record_bytes_received(client: Client, operation: impl Into<SharedString>, bytes: u64) {
+    counter!(BYTES_RECEIVED, "client" => client.as_str(), "operation" => operation.into())
+        .increment(bytes);
+}
+
+/// Record that a request will be retried because of `cause`, e.g. an HTTP status code such as
+/// `503`, a gRPC status such as `Unavailable`, or a transport error such as `timeout`.
+pub fn record_retry(
+    client: Client,
+    operation: impl Into<SharedString>,
+    cause: impl Into<SharedString>,
+) {
+    counter!(
+        RETRIES,
+        "client" => client.as_str(),
+        "operation" => operation.into(),
+        "cause" => cause.into()
+    )
+    .increment(1);
+}
+
+/// Record the outcome of an access token refresh.
+pub fn record_token_refresh(succeeded: bool) {
+    let outcome = if succeeded { "success" } else { "failure" };
+    counter!(TOKEN_REFRESHES, "outcome" => outcome).increment(1);
+}
@@ -45,8 +45,17 @@
 //! * `server`: include the generated server code for both Controller Service
 //!   and Translation Service
 //! * `regen`: regenerate the protobuf code and store it in `./src/gen`
+//! * `compression`: compress request messages and decompress response messages with gzip or
+//!   zstd; see `ChannelBuilder::with_compression`
+//! * `metrics`: record request metrics through the [`metrics`](https://docs.rs/metrics) facade;
+//!   see `ChannelBuilder::with_metrics`
+//! * `resolver`: resolve the gRPC address of a quantum processor's controller through the QCS
+//!   OpenAPI; see `ProcessorResolver`
+//! * `grpc-web`: send gRPC requests as gRPC-web; see `wrap_channel_with_grpc_web`
+//! * `grpc-web-fallback`: fall back to gRPC-web over HTTP/1.1 for targets which cannot be reached
+//!   over HTTP/2; see `FallbackChannel`
 //!
-//! By default, both features are disabled.
+//! By default, all features are disabled.
 
 /// Utilities for creating and working with [`Channel`]s.
 ///
@@ -0,0 +1,348 @@
+//! Client-side load balancing across several gRPC addresses.
+
+use std::{
+    future::{Future, poll_fn},
+    pin::Pin,
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use qcs_dependencies_client::http::{Request, Response, Uri};
+use qcs_dependencies_client::tonic::{
+    body::Body,
+    transport::{Channel, Endpoint, Error as TransportError},
+};
+use qcs_dependencies_client::tonic_health::pb::{
+    HealthCheckRequest, health_check_response::ServingStatus, health_client::HealthClient,
+};
+use qcs_dependencies_client::tower::Service;
+
+use super::{
+    ChannelBuilder, ChannelError, get_channel_with_endpoint, get_endpoint, health::server_responded,
+};
+
+/// How often a [`BalancedChannel`] checks the health of its backends by default.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A [`Channel`] to one of the addresses of a [`BalancedChannel`].
+#[derive(Debug)]
+struct Backend {
+    uri: Uri,
+    channel: Channel,
+    healthy: AtomicBool,
+}
+
+/// A channel which balances requests across several gRPC addresses of the same service.
+///
+/// Requests are sent to the healthy backends in turn. A backend is ejected when a request to it
+/// fails to be sent, or when it does not report itself as serving to a
+/// [health check](https://github.com/grpc/grpc/blob/master/doc/health-checking.md); a server which
+/// rejects the health check, e.g. because it does not implement the health service, is assumed to
+/// be serving. The health of every backend,
+/// including ejected ones, is checked in the background, so that backends rejoin once they
+/// recover. If no backend is healthy, requests are sent to all of them in turn.
+///
+/// Each backend has its own [`Channel`], which reconnects as needed. Backends created from
+/// [`Endpoint`]s use any proxy in the same way as [`get_channel`](super::get_channel), while
+/// those created from [`ChannelBuilder`]s use the builder's proxy, TLS and channel settings.
+///
+/// Like a [`Channel`], a [`BalancedChannel`] can be wrapped with the layers in this module, e.g.
+/// with [`wrap_channel`](super::wrap_channel).
+#[derive(Clone, Debug)]
+pub struct BalancedChannel {
+    backends: Arc<[Backend]>,
+    next: Arc<AtomicUsize>,
+}
+
+impl BalancedChannel {
+    /// Create a [`BalancedChannel`] across the given endpoints, checking the health of each at
+    /// the given interval.
+    ///
+    /// This must be called from within a Tokio runtime, which runs the health checks until the
+    /// channel and all of its clones are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChannelError`] if there are no endpoints, if there is no current Tokio
+    /// runtime, or if a channel cannot be created for one of the endpoints.
+    #[allow(clippy::result_large_err)]
+    pub fn from_endpoints(
+        endpoints: impl IntoIterator<Item = Endpoint>,
+        health_check_interval: Duration,
+    ) -> Result<Self, ChannelError> {
+        Self::from_backends(endpoints, health_check_interval, |endpoint| {
+            Ok((
+                endpoint.uri().clone(),
+                get_channel_with_endpoint(&endpoint)?,
+            ))
+        })
+    }
+
+    /// Create a [`BalancedChannel`] across the addresses of the given builders, checking the
+    /// health of each at the given interval.
+    ///
+    /// Each backend uses the proxy, TLS and channel settings of its builder, but not its layers;
+    /// wrap the [`BalancedChannel`] itself to add those.
+    ///
+    /// This must be called from within a Tokio runtime, which runs the health checks until the
+    /// channel and all of its clones are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChannelError`] if there are no builders, if there is no current Tokio runtime,
+    /// or if a channel cannot be built from one of the builders.
+    #[allow(clippy::result_large_err)]
+    pub fn from_builders<O>(
+        builders: impl IntoIterator<Item = ChannelBuilder<O>>,
+        health_check_interval: Duration,
+    ) -> Result<Self, ChannelError> {
+        Self::from_backends(builders, health_check_interval, |builder| {
+            Ok((builder.uri().clone(), builder.build_channel()?))
+        })
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::result_large_err)]
+    fn from_channels(
+        channels: Vec<(Uri, Channel)>,
+        health_check_interval: Duration,
+    ) -> Result<Self, ChannelError> {
+        Self::from_backends(channels, health_check_interval, Ok)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn from_backends<T>(
+        backends: impl IntoIterator<Item = T>,
+        health_check_interval: Duration,
+        connect: impl Fn(T) -> Result<(Uri, Channel), ChannelError>,
+    ) -> Result<Self, ChannelError> {
+        let backends = backends.into_iter().collect::<Vec<_>>();
+        if backends.is_empty() {
+            return Err(ChannelError::NoEndpoints);
+        }
+        // Channels also spawn their connection tasks, so check for a runtime before creating any.
+        let runtime = tokio::runtime::Handle::try_current().map_err(|_| ChannelError::NoRuntime)?;
+        let backends = backends
+            .into_iter()
+            .map(|backend| {
+                let (uri, channel) = connect(backend)?;
+                Ok(Backend {
+                    uri,
+                    channel,
+                    healthy: AtomicBool::new(true),
+                })
+            })
+            .collect::<Result<Arc<[_]>, ChannelError>>()?;
+        runtime.spawn(check_health_periodically(
+            Arc::downgrade(&backends),
+            health_check_interval,
+        ));
+        Ok(Self {
+            backends,
+            next: Arc::default(),
+        })
+    }
+
+    /// The addresses of the backends which are currently considered healthy.
+    #[must_use]
+    pub fn healthy_uris(&self) -> Vec<Uri> {
+        self.backends
+            .iter()
+            .filter(|backend| backend.healthy.load(Ordering::Relaxed))
+            .map(|backend| backend.uri.clone())
+            .collect()
+    }
+
+    /// Choose the backend for the next request.
+    fn next_backend(&self) -> usize {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let count = self.backends.len();
+        (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|index| self.backends[*index].healthy.load(Ordering::Relaxed))
+            .unwrap_or(start % count)
+    }
+}
+
+/// Get a [`BalancedChannel`] across the given [`Uri`]s, configured like [`get_channel`](super::get_channel).
+///
+/// # Errors
+///
+/// See [`BalancedChannel::from_endpoints`].
+#[allow(clippy::result_large_err)]
+pub fn get_balanced_channel(
+    uris: impl IntoIterator<Item = Uri>,
+) -> Result<BalancedChannel, ChannelError> {
+    BalancedChannel::from_endpoints(
+        uris.into_iter().map(get_endpoint),
+        DEFAULT_HEALTH_CHECK_INTERVAL,
+    )
+}
+
+impl Service<Request<Body>> for BalancedChannel {
+    type Response = Response<Body>;
+    type Error = TransportError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Readiness depends on the backend, which is only chosen when the request is made.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let backends = self.backends.clone();
+        let index = self.next_backend();
+        let mut channel = backends[index].channel.clone();
+        Box::pin(async move {
+            // Ensure that the service is ready before trying to use it.
+            // Failure to do this *will* cause a panic.
+            let result = match poll_fn(|cx| channel.poll_ready(cx)).await {
+                Ok(()) => channel.call(req).await,
+                Err(error) => Err(error),
+            };
+            if result.is_err() {
+                backends[index].healthy.store(false, Ordering::Relaxed);
+                #[cfg(feature = "tracing")]
+                tracing::warn!("ejecting gRPC backend {}", backends[index].uri);
+            }
+            result
+        })
+    }
+}
+
+/// Check the health of each backend at the given interval, until the channel is dropped.
+async fn check_health_periodically(backends: Weak<[Backend]>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let Some(backends) = backends.upgrade() else {
+            return;
+        };
+        let checks = backends.iter().map(|backend| async {
+            let healthy = is_serving(backend.channel.clone(), interval.period()).await;
+            backend.healthy.store(healthy, Ordering::Relaxed);
+        });
+        futures_util::future::join_all(checks).await;
+    }
+}
+
+/// Whether the server behind `channel` reports itself as serving within `timeout`.
+async fn is_serving(channel: Channel, timeout: Duration) -> bool {
+    let mut client = HealthClient::new(channel);
+    let check = client.check(HealthCheckRequest::default());
+    match tokio::time::timeout(timeout, check).await {
+        Ok(Ok(response)) => response.into_inner().status() == ServingStatus::Serving,
+        Ok(Err(status)) => server_responded(&status),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use qcs_dependencies_client::tonic_health::pb::health_server::HealthServer;
+    use qcs_dependencies_client::tonic_health::server::{HealthReporter, HealthService};
+
+    use super::*;
+    use crate::tonic::uds_grpc_stream;
+
+    async fn health_server(
+        status: qcs_dependencies_client::tonic_health::ServingStatus,
+    ) -> HealthServer<HealthService> {
+        let reporter = HealthReporter::new();
+        reporter.set_service_status("", status).await;
+        HealthServer::new(HealthService::from_health_reporter(reporter))
+    }
+
+    async fn check(channel: BalancedChannel) -> ServingStatus {
+        HealthClient::new(channel)
+            .check(HealthCheckRequest::default())
+            .await
+            .unwrap()
+            .into_inner()
+            .status()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ejects_unhealthy_backend() {
+        let serving =
+            health_server(qcs_dependencies_client::tonic_health::ServingStatus::Serving).await;
+        let not_serving =
+            health_server(qcs_dependencies_client::tonic_health::ServingStatus::NotServing).await;
+
+        uds_grpc_stream::serve(serving, |serving| async move {
+            uds_grpc_stream::serve(not_serving, |not_serving| async move {
+                let channel = BalancedChannel::from_channels(
+                    vec![
+                        (Uri::from_static("http://serving"), serving),
+                        (Uri::from_static("http://not-serving"), not_serving),
+                    ],
+                    Duration::from_millis(50),
+                )
+                .unwrap();
+
+                // Give the health checks time to eject the backend which is not serving.
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                assert_eq!(
+                    channel.healthy_uris(),
+                    vec![Uri::from_static("http://serving")]
+                );
+                for _ in 0..4 {
+                    assert_eq!(check(channel.clone()).await, ServingStatus::Serving);
+                }
+            })
+            .await
+            .unwrap();
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sends_to_all_backends_when_none_are_healthy() {
+        let channel = BalancedChannel::from_channels(
+            vec![
+                (
+                    Uri::from_static("http://a"),
+                    Endpoint::from_static("http://a").connect_lazy(),
+                ),
+                (
+                    Uri::from_static("http://b"),
+                    Endpoint::from_static("http://b").connect_lazy(),
+                ),
+            ],
+            Duration::from_secs(60),
+        )
+        .unwrap();
+        for backend in channel.backends.iter() {
+            backend.healthy.store(false, Ordering::Relaxed);
+        }
+
+        let first = channel.next_backend();
+        let second = channel.next_backend();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_requires_a_runtime() {
+        assert!(matches!(
+            BalancedChannel::from_endpoints(
+                [Endpoint::from_static("http://a")],
+                DEFAULT_HEALTH_CHECK_INTERVAL
+            ),
+            Err(ChannelError::NoRuntime)
+        ));
+    }
+
+    #[test]
+    fn test_requires_an_endpoint() {
+        assert!(matches!(
+            BalancedChannel::from_endpoints([], DEFAULT_HEALTH_CHECK_INTERVAL),
+            Err(ChannelError::NoEndpoints)
+        ));
+    }
+}
@@ -10,14 +10,20 @@ use qcs_dependencies_client::http::{Uri, uri::InvalidUri};
 use qcs_dependencies_client::tonic::{
     body::Body,
     client::GrpcService,
-    transport::{Channel, ClientTlsConfig, Endpoint},
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity},
 };
 use qcs_dependencies_client::tower::{Layer, ServiceBuilder};
 use url::Url;
 
 use qcs_api_client_common::{
-    backoff::{self, default_backoff},
-    configuration::{ClientConfiguration, LoadError, TokenError, tokens::TokenRefresher},
+    backoff,
+    configuration::{
+        ClientConfiguration, LoadError, TokenError,
+        proxy::ProxyConfig,
+        settings::{ChannelSettings, RetrySettings},
+        tls::TlsConfig,
+        tokens::TokenRefresher,
+    },
 };
 
 #[cfg(feature = "tracing")]
@@ -27,7 +33,14 @@ use rigetti_hyper_proxy::{Intercept, Proxy, ProxyConnector};
 
 #[cfg(feature = "tracing")]
 use super::trace::{CustomTraceLayer, CustomTraceService, build_trace_layer};
-use super::{Error, RefreshLayer, RefreshService, RetryLayer, RetryService};
+use super::{
+    ChannelPool, Error, HealthError, HealthWatcher, HedgeLayer, HedgeService, RefreshLayer,
+    RefreshService, RetryLayer, RetryService, pool::ChannelKey,
+};
+#[cfg(feature = "compression")]
+use super::{CompressionConfig, CompressionLayer, CompressionService};
+#[cfg(feature = "metrics")]
+use super::{MetricsLayer, MetricsService};
 
 /// Errors that may occur when configuring a channel connection
 #[derive(Debug, thiserror::Error)]
@@ -46,6 +59,9 @@ pub enum ChannelError {
     #[error("HTTP proxy ssl verification failed: {0}")]
     SslFailure(#[from] std::io::Error),
     /// Proxy targets do not agree
+    #[deprecated(
+        note = "separate https and socks5 proxies are supported, so this is never returned"
+    )]
     #[error("Cannot set separate https and http proxies if one of them is socks5")]
     Mismatch {
         /// The URI of the HTTPS proxy.
@@ -53,6 +69,27 @@ pub enum ChannelError {
         /// The URI of the HTTP proxy.
         http_proxy: Uri,
     },
+    /// The TLS settings could not be applied, e.g. because a certificate is invalid.
+    #[error("Invalid TLS configuration: {0}")]
+    InvalidTlsConfig(#[source] qcs_dependencies_client::tonic::transport::Error),
+    /// A balanced channel needs at least one endpoint.
+    #[error("Cannot balance requests across zero endpoints")]
+    NoEndpoints,
+    /// A balanced channel checks the health of its backends in the background, so it must be
+    /// created within a Tokio runtime.
+    #[error("Cannot create a balanced channel outside of a Tokio runtime")]
+    NoRuntime,
+    /// The HTTP/1.1 client used for gRPC-web could not be built.
+    #[cfg(feature = "grpc-web-fallback")]
+    #[error("Failed to build the gRPC-web HTTP client: {0}")]
+    GrpcWebClient(#[source] qcs_dependencies_client::reqwest::Error),
+    /// The server did not report itself as serving. See [`ChannelBuilder::wait_until_serving`].
+    #[error("The gRPC server is not ready: {0}")]
+    NotServing(#[from] HealthError),
+    /// A service which authenticates with a custom [`TokenRefresher`] cannot be pooled without a
+    /// key identifying its credentials. See [`ChannelBuilder::build_pooled_with_key`].
+    #[error("Cannot pool a channel with a custom token refresher without a credentials key")]
+    UnkeyedRefresher,
 }
 
 /// Defines a logic for turning some object into a [`GrpcService`].
@@ -121,7 +158,10 @@ pub struct RetryOptions<O = ()> {
 impl From<ExponentialBackoff> for RetryOptions<()> {
     fn from(backoff: ExponentialBackoff) -> Self {
         Self {
-            layer: RetryLayer { backoff },
+            layer: RetryLayer {
+                backoff,
+                ..RetryLayer::default()
+            },
             other: (),
         }
     }
@@ -141,15 +181,93 @@ where
     }
 }
 
+/// Options for hedging requests.
+#[derive(Clone, Debug)]
+pub struct HedgeOptions<O = ()> {
+    layer: HedgeLayer,
+    other: O,
+}
+
+impl<C, O> IntoService<C> for HedgeOptions<O>
+where
+    C: GrpcService<Body>,
+    O: IntoService<C>,
+    O::Service: GrpcService<Body>,
+    HedgeService<O::Service>: GrpcService<Body>,
+{
+    type Service = HedgeService<O::Service>;
+    fn into_service(self, channel: C) -> Self::Service {
+        let service = self.other.into_service(channel);
+        self.layer.layer(service)
+    }
+}
+
+/// Options for compressing messages.
+#[cfg(feature = "compression")]
+#[derive(Clone, Debug)]
+pub struct CompressionOptions<O = ()> {
+    layer: CompressionLayer,
+    other: O,
+}
+
+#[cfg(feature = "compression")]
+impl<C, O> IntoService<C> for CompressionOptions<O>
+where
+    C: GrpcService<Body>,
+    O: IntoService<C>,
+    O::Service: GrpcService<Body>,
+    CompressionService<O::Service>: GrpcService<Body>,
+{
+    type Service = CompressionService<O::Service>;
+    fn into_service(self, channel: C) -> Self::Service {
+        let service = self.other.into_service(channel);
+        self.layer.layer(service)
+    }
+}
+
+/// Options for recording request metrics.
+#[cfg(feature = "metrics")]
+#[derive(Clone, Debug)]
+pub struct MetricsOptions<O = ()> {
+    layer: MetricsLayer,
+    other: O,
+}
+
+#[cfg(feature = "metrics")]
+impl<C, O> IntoService<C> for MetricsOptions<O>
+where
+    C: GrpcService<Body>,
+    O: IntoService<C>,
+    O::Service: GrpcService<Body>,
+    MetricsService<O::Service>: GrpcService<Body>,
+{
+    type Service = MetricsService<O::Service>;
+    fn into_service(self, channel: C) -> Self::Service {
+        let service = self.other.into_service(channel);
+        self.layer.layer(service)
+    }
+}
+
 /// Builder for configuring a [`Channel`].
 #[derive(Clone, Debug)]
 pub struct ChannelBuilder<O = ()> {
     endpoint: Endpoint,
+    retry_settings: RetrySettings,
+    proxy_config: Option<ProxyConfig>,
+    tls_config: Option<TlsConfig>,
+    channel_settings: Option<ChannelSettings>,
+    timeout: Option<Duration>,
+    qcs_config: Option<ClientConfiguration>,
+    /// Whether a [`TokenRefresher`] other than [`ChannelBuilder::with_qcs_config`]'s was given.
+    custom_refresher: bool,
     #[cfg(feature = "tracing")]
     trace_layer: CustomTraceLayer,
     options: O,
 }
 
+/// The endpoint's own HTTP/2 and TCP settings are kept unless [`ChannelSettings`] are given with
+/// [`ChannelBuilder::with_channel_settings`] (or a QCS configuration), in which case those are
+/// applied on top of them when the channel is built (see [`apply_channel_settings`]).
 impl From<Endpoint> for ChannelBuilder<()> {
     fn from(endpoint: Endpoint) -> Self {
         #[cfg(feature = "tracing")]
@@ -157,6 +275,13 @@ impl From<Endpoint> for ChannelBuilder<()> {
             let base_url = endpoint.uri().to_string();
             Self {
                 endpoint,
+                retry_settings: RetrySettings::default(),
+                proxy_config: None,
+                tls_config: None,
+                channel_settings: None,
+                timeout: None,
+                qcs_config: None,
+                custom_refresher: false,
                 trace_layer: build_trace_layer(base_url, None),
                 options: (),
             }
@@ -165,19 +290,34 @@ impl From<Endpoint> for ChannelBuilder<()> {
         #[cfg(not(feature = "tracing"))]
         return Self {
             endpoint,
+            retry_settings: RetrySettings::default(),
+            proxy_config: None,
+            tls_config: None,
+            channel_settings: None,
+            timeout: None,
+            qcs_config: None,
+            custom_refresher: false,
             options: (),
         };
     }
 }
 
 impl ChannelBuilder<()> {
-    /// Create a [`ChannelBuilder`] using the given [`Uri`]
+    /// Create a [`ChannelBuilder`] using the given [`Uri`], with an endpoint configured by
+    /// [`get_endpoint`].
     pub fn from_uri(uri: Uri) -> Self {
         #[cfg(feature = "tracing")]
         {
             let base_url = uri.to_string();
             Self {
                 endpoint: get_endpoint(uri),
+                retry_settings: RetrySettings::default(),
+                proxy_config: None,
+                tls_config: None,
+                channel_settings: None,
+                timeout: None,
+                qcs_config: None,
+                custom_refresher: false,
                 trace_layer: build_trace_layer(base_url, None),
                 options: (),
             }
@@ -186,6 +326,13 @@ impl ChannelBuilder<()> {
         #[cfg(not(feature = "tracing"))]
         return Self {
             endpoint: get_endpoint(uri),
+            retry_settings: RetrySettings::default(),
+            proxy_config: None,
+            tls_config: None,
+            channel_settings: None,
+            timeout: None,
+            qcs_config: None,
+            custom_refresher: false,
             options: (),
         };
     }
@@ -204,6 +351,33 @@ where
     #[must_use]
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.endpoint = self.endpoint.timeout(timeout);
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Route the channel's connections according to the given [`ProxyConfig`], rather than that
+    /// of the [`ClientConfiguration`] passed to [`ChannelBuilder::with_qcs_config`] or the proxy
+    /// environment variables.
+    #[must_use]
+    pub fn with_proxy_config(mut self, proxy_config: ProxyConfig) -> Self {
+        self.proxy_config = Some(proxy_config);
+        self
+    }
+
+    /// Secure the channel's connections according to the given [`TlsConfig`], rather than that of
+    /// the [`ClientConfiguration`] passed to [`ChannelBuilder::with_qcs_config`].
+    #[must_use]
+    pub fn with_tls_config(mut self, tls_config: TlsConfig) -> Self {
+        self.tls_config = Some(tls_config);
+        self
+    }
+
+    /// Configure the channel's connections with the given [`ChannelSettings`], rather than those
+    /// of the [`ClientConfiguration`] passed to [`ChannelBuilder::with_qcs_config`] or the
+    /// defaults.
+    #[must_use]
+    pub fn with_channel_settings(mut self, channel_settings: ChannelSettings) -> Self {
+        self.channel_settings = Some(channel_settings);
         self
     }
 
@@ -215,23 +389,9 @@ where
     where
         T: TokenRefresher + Clone + Send + Sync,
     {
-        #[cfg(feature = "tracing")]
-        return ChannelBuilder {
-            endpoint: self.endpoint,
-            trace_layer: self.trace_layer,
-            options: RefreshOptions {
-                layer,
-                other: self.options,
-            },
-        };
-        #[cfg(not(feature = "tracing"))]
-        return ChannelBuilder {
-            endpoint: self.endpoint,
-            options: RefreshOptions {
-                layer,
-                other: self.options,
-            },
-        };
+        let mut builder = self.map_options(|other| RefreshOptions { layer, other });
+        builder.custom_refresher = true;
+        builder
     }
 
     /// Wrap the channel with QCS authentication using the given [`TokenRefresher`].
@@ -243,22 +403,36 @@ where
     }
 
     /// Wrap the channel with QCS authentication for the given [`ClientConfiguration`].
+    ///
+    /// Retry logic configured after this call with [`ChannelBuilder::with_default_retry`] or
+    /// [`ChannelBuilder::with_retry_backoff`] uses the configuration's [`RetrySettings`].
+    /// The channel uses the configuration's [`ProxyConfig`], [`TlsConfig`] and
+    /// [`ChannelSettings`], unless they were given with [`ChannelBuilder::with_proxy_config`],
+    /// [`ChannelBuilder::with_tls_config`] and [`ChannelBuilder::with_channel_settings`].
     pub fn with_qcs_config(
         self,
         config: ClientConfiguration,
     ) -> ChannelBuilder<RefreshOptions<O, ClientConfiguration>> {
+        let custom_refresher = self.custom_refresher;
+        let mut builder = self.with_token_refresher(config.clone());
+        builder.custom_refresher = custom_refresher;
         #[cfg(feature = "tracing")]
         {
-            let base_url = self.endpoint.uri().to_string();
-            let trace_layer = build_trace_layer(base_url, config.tracing_configuration());
-            let mut builder = self.with_token_refresher(config);
-            builder.trace_layer = trace_layer;
-            builder
-        }
-        #[cfg(not(feature = "tracing"))]
-        {
-            self.with_token_refresher(config)
+            let base_url = builder.endpoint.uri().to_string();
+            builder.trace_layer = build_trace_layer(base_url, config.tracing_configuration());
         }
+        builder.retry_settings = config.retry_settings().clone();
+        builder
+            .proxy_config
+            .get_or_insert_with(|| config.proxy_config().clone());
+        builder
+            .tls_config
+            .get_or_insert_with(|| config.tls_config().clone());
+        builder
+            .channel_settings
+            .get_or_insert_with(|| config.channel_settings().clone());
+        builder.qcs_config = Some(config);
+        builder
     }
 
     /// Wrap the channel with QCS authentication for the given QCS profile.
@@ -280,36 +454,60 @@ where
 
     /// Wrap the channel with the given [`RetryLayer`].
     pub fn with_retry_layer(self, layer: RetryLayer) -> ChannelBuilder<RetryOptions<O>> {
-        #[cfg(feature = "tracing")]
-        return ChannelBuilder {
-            endpoint: self.endpoint,
-            trace_layer: self.trace_layer,
-            options: RetryOptions {
-                layer,
-                other: self.options,
-            },
-        };
-        #[cfg(not(feature = "tracing"))]
-        return ChannelBuilder {
-            endpoint: self.endpoint,
-            options: RetryOptions {
-                layer,
-                other: self.options,
-            },
-        };
+        self.map_options(|other| RetryOptions { layer, other })
     }
 
     /// Wrap the channel with the given [`ExponentialBackoff`] configuration.
+    ///
+    /// The retryable status codes are those of the [`RetrySettings`] of the
+    /// [`ClientConfiguration`] passed to [`ChannelBuilder::with_qcs_config`], if any.
     pub fn with_retry_backoff(
         self,
         backoff: ExponentialBackoff,
     ) -> ChannelBuilder<RetryOptions<O>> {
-        self.with_retry_layer(RetryLayer { backoff })
+        let layer = RetryLayer {
+            backoff,
+            ..RetryLayer::from_retry_settings(self.retry_settings.clone())
+        };
+        self.with_retry_layer(layer)
     }
 
-    /// Wrap the channel with the default retry logic. See [`default_backoff`].
+    /// Wrap the channel with the default retry logic.
+    ///
+    /// This uses the [`RetrySettings`] of the [`ClientConfiguration`] passed to
+    /// [`ChannelBuilder::with_qcs_config`], or [`backoff::default_backoff`] if there is none.
     pub fn with_default_retry(self) -> ChannelBuilder<RetryOptions<O>> {
-        self.with_retry_backoff(default_backoff())
+        let layer = RetryLayer::from_retry_settings(self.retry_settings.clone());
+        self.with_retry_layer(layer)
+    }
+
+    /// Wrap the channel with the given [`HedgeLayer`]. To hedge each retry attempt, call this
+    /// before configuring retries.
+    pub fn with_hedge_layer(self, layer: HedgeLayer) -> ChannelBuilder<HedgeOptions<O>> {
+        self.map_options(|other| HedgeOptions { layer, other })
+    }
+
+    /// Wrap the channel with a [`CompressionLayer`], compressing the messages of requests made
+    /// through the layers configured so far according to the given [`CompressionConfig`]. To
+    /// retain compressed request bodies for retries, call this after configuring retries.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(
+        self,
+        config: CompressionConfig,
+    ) -> ChannelBuilder<CompressionOptions<O>> {
+        let layer = CompressionLayer::new(config);
+        self.map_options(|other| CompressionOptions { layer, other })
+    }
+
+    /// Wrap the channel with a [`MetricsLayer`], recording metrics for each request made through
+    /// the layers configured so far. To include retries in each request's duration, call this
+    /// after configuring retries.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self) -> ChannelBuilder<MetricsOptions<O>> {
+        self.map_options(|other| MetricsOptions {
+            layer: MetricsLayer,
+            other,
+        })
     }
 
     /// Build the [`Channel`]
@@ -319,7 +517,7 @@ where
     /// Returns a [`ChannelError`] if the service cannot be built.
     #[allow(clippy::result_large_err)]
     pub fn build(self) -> Result<O::Service, ChannelError> {
-        let channel = get_channel_with_endpoint(&self.endpoint)?;
+        let channel = self.build_channel()?;
         #[cfg(feature = "tracing")]
         {
             let traced_channel = self.trace_layer.layer(channel);
@@ -329,32 +527,298 @@ where
         #[cfg(not(feature = "tracing"))]
         Ok(self.options.into_service(channel))
     }
+
+    /// Wait until the server reports the given service as serving, for at most `timeout`, e.g.
+    /// after restarting an endpoint. Use an empty service name for the server as a whole.
+    ///
+    /// The server is checked with the standard gRPC health protocol, over a channel with the
+    /// builder's proxy, TLS and channel settings but without any of its layers, such as
+    /// authentication. The builder is returned so that the channel can be built once the server is
+    /// ready.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChannelError::NotServing`] if the server does not implement the health service or
+    /// the service is not serving within `timeout`, or another [`ChannelError`] if the channel
+    /// cannot be built.
+    #[allow(clippy::result_large_err)]
+    pub async fn wait_until_serving(
+        self,
+        service_name: &str,
+        timeout: Duration,
+    ) -> Result<Self, ChannelError> {
+        let watcher = HealthWatcher::spawn(self.build_channel()?, service_name);
+        watcher.wait_until_serving(timeout).await?;
+        Ok(self)
+    }
+
+    /// Watch the health of the given service in the background, over a channel configured like
+    /// the one checked by [`ChannelBuilder::wait_until_serving`].
+    ///
+    /// This must be called from within a Tokio runtime; see [`HealthWatcher::spawn`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChannelError`] if the channel cannot be built.
+    #[allow(clippy::result_large_err)]
+    pub fn watch_health(&self, service_name: &str) -> Result<HealthWatcher, ChannelError> {
+        Ok(HealthWatcher::spawn(self.build_channel()?, service_name))
+    }
+
+    /// Get a clone of the service from the given [`ChannelPool`] which matches this builder's
+    /// address and configuration, building and pooling a new one if there is none. See
+    /// [`ChannelPool`] for which parts of the configuration must match.
+    ///
+    /// A pooled service is shared by everyone who builds a matching one, so a service which
+    /// authenticates with a [`TokenRefresher`] given to [`ChannelBuilder::with_token_refresher`]
+    /// or [`ChannelBuilder::with_refresh_layer`] can only be pooled with
+    /// [`ChannelBuilder::build_pooled_with_key`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChannelError`] if the service cannot be built, or
+    /// [`ChannelError::UnkeyedRefresher`] if it uses such a [`TokenRefresher`].
+    #[allow(clippy::result_large_err)]
+    pub fn build_pooled(self, pool: &ChannelPool) -> Result<O::Service, ChannelError>
+    where
+        O::Service: Clone + Send + Sync + 'static,
+    {
+        if self.custom_refresher {
+            return Err(ChannelError::UnkeyedRefresher);
+        }
+        let key = self.pool_key(None);
+        pool.get_or_try_insert_with(key, || self.build())
+    }
+
+    /// Like [`ChannelBuilder::build_pooled`], but only share the service with builders which give
+    /// the same `credentials_key`. The key must identify the credentials of any
+    /// [`TokenRefresher`] this builder was given, e.g. the user they belong to, since services
+    /// with the same key are assumed to authenticate as the same user.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChannelError`] if the service cannot be built.
+    #[allow(clippy::result_large_err)]
+    pub fn build_pooled_with_key(
+        self,
+        pool: &ChannelPool,
+        credentials_key: impl Into<String>,
+    ) -> Result<O::Service, ChannelError>
+    where
+        O::Service: Clone + Send + Sync + 'static,
+    {
+        let key = self.pool_key(Some(credentials_key.into()));
+        pool.get_or_try_insert_with(key, || self.build())
+    }
+
+    fn pool_key(&self, credentials_key: Option<String>) -> ChannelKey {
+        ChannelKey {
+            uri: self.endpoint.uri().clone(),
+            timeout: self.timeout,
+            retry_settings: self.retry_settings.clone(),
+            proxy_config: self.proxy_config.clone(),
+            tls_config: self.tls_config.clone(),
+            channel_settings: self.channel_settings.clone(),
+            qcs_config: self.qcs_config.clone(),
+            credentials_key,
+        }
+    }
+}
+
+impl<O> ChannelBuilder<O> {
+    /// Replace the layers configured so far with those returned by `f`, keeping the rest of the
+    /// builder's configuration.
+    fn map_options<P>(self, f: impl FnOnce(O) -> P) -> ChannelBuilder<P> {
+        ChannelBuilder {
+            endpoint: self.endpoint,
+            retry_settings: self.retry_settings,
+            proxy_config: self.proxy_config,
+            tls_config: self.tls_config,
+            channel_settings: self.channel_settings,
+            timeout: self.timeout,
+            qcs_config: self.qcs_config,
+            custom_refresher: self.custom_refresher,
+            #[cfg(feature = "tracing")]
+            trace_layer: self.trace_layer,
+            options: f(self.options),
+        }
+    }
+
+    /// The address of the channel.
+    pub(super) fn uri(&self) -> &Uri {
+        self.endpoint.uri()
+    }
+
+    /// Build the underlying [`Channel`], without any of the configured layers.
+    #[allow(clippy::result_large_err)]
+    pub(super) fn build_channel(&self) -> Result<Channel, ChannelError> {
+        let proxy_config = self
+            .proxy_config
+            .clone()
+            .unwrap_or_else(ProxyConfig::from_env);
+        let endpoint = self.build_endpoint()?;
+        get_channel_with_proxy_config(&endpoint, &proxy_config)
+    }
+
+    /// Apply the configured [`ChannelSettings`] and [`TlsConfig`], if any, to the endpoint.
+    #[allow(clippy::result_large_err)]
+    fn build_endpoint(&self) -> Result<Endpoint, ChannelError> {
+        let endpoint = match &self.channel_settings {
+            Some(channel_settings) => {
+                apply_channel_settings(self.endpoint.clone(), channel_settings)
+            }
+            None => self.endpoint.clone(),
+        };
+        match &self.tls_config {
+            Some(tls_config) => endpoint
+                .tls_config(get_client_tls_config(tls_config))
+                .map_err(ChannelError::InvalidTlsConfig),
+            None => Ok(endpoint),
+        }
+    }
 }
 
 /// Parse a string as a URI.
 ///
 /// This serves as a helper to avoid consumers needing to create a new error just to include this.
 ///
+/// A Unix domain socket may be given as `unix:///path/to/socket`; see [`get_unix_socket_uri`].
+///
 /// # Errors
 ///
 /// [`Error::InvalidUri`] if the string is an invalid URI.
 #[allow(clippy::result_large_err)]
 pub fn parse_uri(s: &str) -> Result<Uri, Error<TokenError>> {
-    s.parse().map_err(Error::from)
+    let unix_socket_path = match s.strip_prefix("unix://") {
+        // e.g. `unix://localhost/path/to/socket`, which is already a valid `Uri`.
+        Some(rest) if !rest.starts_with('/') => None,
+        Some(path) => Some(path),
+        None => s.strip_prefix("unix:"),
+    };
+    match unix_socket_path {
+        Some(path) => get_unix_socket_uri(path),
+        None => s.parse(),
+    }
+    .map_err(Error::from)
+}
+
+/// The URI scheme of Unix domain sockets.
+const UNIX_SCHEME: &str = "unix";
+
+/// The origin of requests sent over a Unix domain socket.
+const UNIX_ORIGIN: &str = "http://localhost";
+
+/// Get a [`Uri`] for the Unix domain socket at the given absolute `path`.
+///
+/// Since a [`Uri`] with a scheme must have an authority, the socket at `/path/to/socket` is
+/// represented as `unix://localhost/path/to/socket`. Channels to such a [`Uri`] connect to the
+/// socket directly, bypassing any proxy.
+///
+/// # Errors
+///
+/// Returns an [`InvalidUri`] if `path` is not an absolute path which is valid in a [`Uri`].
+pub fn get_unix_socket_uri(path: &str) -> Result<Uri, InvalidUri> {
+    if path.starts_with('/') {
+        format!("{UNIX_SCHEME}://localhost{path}").parse()
+    } else {
+        // A `Uri` cannot have a scheme without an authority, so this fails to parse.
+        format!("{UNIX_SCHEME}:///{path}").parse()
+    }
+}
+
+/// The path of the Unix domain socket that `uri` refers to, if any. See [`get_unix_socket_uri`].
+#[must_use]
+pub fn get_unix_socket_path(uri: &Uri) -> Option<&str> {
+    (uri.scheme_str() == Some(UNIX_SCHEME)).then(|| uri.path())
+}
+
+/// Connect lazily to the Unix domain socket at `path`, sending requests with an `http` origin.
+#[cfg(unix)]
+#[allow(clippy::result_large_err, clippy::unnecessary_wraps)]
+fn connect_unix_socket_lazy(endpoint: &Endpoint, path: &str) -> Result<Channel, ChannelError> {
+    let path = path.to_string();
+    let connector = qcs_dependencies_client::tower::service_fn(move |_: Uri| {
+        let path = path.clone();
+        async move {
+            let stream = tokio::net::UnixStream::connect(path).await?;
+            Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+        }
+    });
+    Ok(endpoint
+        .clone()
+        .origin(Uri::from_static(UNIX_ORIGIN))
+        .connect_with_connector_lazy(connector))
+}
+
+/// Unix domain sockets are not supported on this platform.
+#[cfg(not(unix))]
+#[allow(clippy::result_large_err)]
+fn connect_unix_socket_lazy(_endpoint: &Endpoint, _path: &str) -> Result<Channel, ChannelError> {
+    Err(ChannelError::UnsupportedProtocol(Some(
+        UNIX_SCHEME.to_string(),
+    )))
 }
 
-/// Get an [`Endpoint`] for the given [`Uri`]
+/// Get an [`Endpoint`] for the given [`Uri`], configured with the default [`ChannelSettings`].
 #[allow(clippy::missing_panics_doc)]
 pub fn get_endpoint(uri: Uri) -> Endpoint {
-    Channel::builder(uri)
-        .user_agent(concat!(
-            "QCS gRPC Client (Rust)/",
-            env!("CARGO_PKG_VERSION")
-        ))
+    let endpoint = Channel::builder(uri)
+        .user_agent(GRPC_USER_AGENT)
         .expect("user agent string should be valid")
-        .http2_adaptive_window(true)
         .tls_config(ClientTlsConfig::new().with_enabled_roots())
-        .expect("tls setup should succeed")
+        .expect("tls setup should succeed");
+    apply_channel_settings(endpoint, &ChannelSettings::default())
+}
+
+/// The user agent of gRPC requests.
+pub(super) const GRPC_USER_AGENT: &str =
+    concat!("QCS gRPC Client (Rust)/", env!("CARGO_PKG_VERSION"));
+
+/// Configure the HTTP/2 and TCP settings of an [`Endpoint`] according to the given
+/// [`ChannelSettings`].
+///
+/// A `keepalive_interval_ms` or `connect_timeout_ms` of `0`, or an unset `concurrency_limit`,
+/// leaves the endpoint's existing setting in place.
+#[must_use]
+pub fn apply_channel_settings(mut endpoint: Endpoint, settings: &ChannelSettings) -> Endpoint {
+    if let Some(interval) = settings.keepalive_interval() {
+        endpoint = endpoint
+            .http2_keep_alive_interval(interval)
+            .keep_alive_timeout(settings.keepalive_timeout())
+            .keep_alive_while_idle(settings.keepalive_while_idle);
+    }
+    if let Some(timeout) = settings.connect_timeout() {
+        endpoint = endpoint.connect_timeout(timeout);
+    }
+    if let Some(limit) = settings.concurrency_limit {
+        endpoint = endpoint.concurrency_limit(limit);
+    }
+    if settings.adaptive_window() {
+        endpoint = endpoint.http2_adaptive_window(true);
+    } else {
+        endpoint = endpoint
+            .http2_adaptive_window(false)
+            .initial_stream_window_size(settings.initial_stream_window_size)
+            .initial_connection_window_size(settings.initial_connection_window_size);
+    }
+    endpoint.tcp_nodelay(settings.tcp_nodelay)
+}
+
+/// Get a [`ClientTlsConfig`] which trusts the system's root certificates along with those of
+/// the given [`TlsConfig`], and applies the rest of its settings.
+#[must_use]
+pub fn get_client_tls_config(tls_config: &TlsConfig) -> ClientTlsConfig {
+    let mut client_tls_config = ClientTlsConfig::new()
+        .with_enabled_roots()
+        .ca_certificates(tls_config.ca_certificates.iter().map(Certificate::from_pem));
+    if let Some(identity) = &tls_config.client_identity {
+        client_tls_config =
+            client_tls_config.identity(Identity::from_pem(identity.certificate(), identity.key()));
+    }
+    if let Some(server_name) = &tls_config.server_name {
+        client_tls_config = client_tls_config.domain_name(server_name);
+    }
+    client_tls_config
 }
 
 /// Get an [`Endpoint`] for the given [`Uri`] and timeout.
@@ -366,16 +830,6 @@ pub fn get_endpoint_with_timeout(uri: Uri, timeout: Option<Duration>) -> Endpoin
     }
 }
 
-/// Fetch the env var named for `key` and parse as a `Uri`.
-/// Tries the original casing, then the full lowercasing of `key`.
-fn get_env_uri(key: &str) -> Result<Option<Uri>, InvalidUri> {
-    std::env::var(key)
-        .or_else(|_| std::env::var(key.to_lowercase()))
-        .ok()
-        .map(Uri::try_from)
-        .transpose()
-}
-
 /// Parse the authentication from `uri` into proxy `Auth`, if present.
 fn get_uri_socks_auth(uri: &Uri) -> Result<Option<Auth>, url::ParseError> {
     let full_url = uri.to_string().parse::<Url>()?;
@@ -393,13 +847,9 @@ fn get_uri_socks_auth(uri: &Uri) -> Result<Option<Auth>, url::ParseError> {
 /// Sets up things like user agent without setting up QCS credentials.
 ///
 /// This channel will be configured to route requests through proxies defined by
-/// `HTTPS_PROXY` and/or `HTTP_PROXY` environment variables, if they are defined.
-/// The variable names can be all-uppercase or all-lowercase, but the all-uppercase
-/// variants will take precedence. Supported proxy schemes are `http`, `https`, and `socks5`.
-///
-/// Proxy configuration caveats:
-/// - If both variables are defined, neither can be a `socks5` proxy, unless they are both the same value.
-/// - If only one variable is defined, and it is a `socks5` proxy, *all* traffic will be routed through it.
+/// `HTTPS_PROXY` and/or `HTTP_PROXY` environment variables, if they are defined, unless the
+/// destination matches the `NO_PROXY` environment variable. See [`get_channel_with_proxy_config`]
+/// for details.
 ///
 /// # Errors
 ///
@@ -415,13 +865,9 @@ pub fn get_channel(uri: Uri) -> Result<Channel, ChannelError> {
 /// Sets up things like user agent without setting up QCS credentials.
 ///
 /// This channel will be configured to route requests through proxies defined by
-/// `HTTPS_PROXY` and/or `HTTP_PROXY` environment variables, if they are defined.
-/// The variable names can be all-uppercase or all-lowercase, but the all-uppercase
-/// variants will take precedence. Supported proxy schemes are `http`, `https`, and `socks5`.
-///
-/// Proxy configuration caveats:
-/// - If both variables are defined, neither can be a `socks5` proxy, unless they are both the same value.
-/// - If only one variable is defined, and it is a `socks5` proxy, *all* traffic will be routed through it.
+/// `HTTPS_PROXY` and/or `HTTP_PROXY` environment variables, if they are defined, unless the
+/// destination matches the `NO_PROXY` environment variable. See [`get_channel_with_proxy_config`]
+/// for details.
 ///
 /// # Errors
 ///
@@ -442,89 +888,65 @@ pub fn get_channel_with_timeout(
 /// [`Endpoint`].
 ///
 /// This channel will be configured to route requests through proxies defined by
-/// `HTTPS_PROXY` and/or `HTTP_PROXY` environment variables, if they are defined.
-/// The variable names can be all-uppercase or all-lowercase, but the all-uppercase
-/// variants will take precedence. Supported proxy schemes are `http`, `https`, and `socks5`.
-///
-/// Proxy configuration caveats:
-/// - If both variables are defined, neither can be a `socks5` proxy, unless they are both the same value.
-/// - If only one variable is defined, and it is a `socks5` proxy, *all* traffic will be routed through it.
+/// `HTTPS_PROXY` and/or `HTTP_PROXY` environment variables, if they are defined, unless the
+/// destination matches the `NO_PROXY` environment variable. See [`get_channel_with_proxy_config`]
+/// for details.
 ///
 /// # Errors
 ///
 /// Returns a [`ChannelError`] if the channel cannot be constructed.
-#[allow(
-    clippy::similar_names,
-    reason = "http(s)_proxy are similar but precise in this case"
-)]
 #[allow(clippy::result_large_err)]
 pub fn get_channel_with_endpoint(endpoint: &Endpoint) -> Result<Channel, ChannelError> {
-    let https_proxy = get_env_uri("HTTPS_PROXY")?;
-    let http_proxy = get_env_uri("HTTP_PROXY")?;
+    get_channel_with_proxy_config(endpoint, &ProxyConfig::from_env())
+}
+
+/// Get a [`Channel`] to the given [`Endpoint`], routing requests according to the given
+/// [`ProxyConfig`].
+///
+/// Requests are sent through the proxy chosen by [`ProxyConfig::proxy_for`], or directly if there
+/// is none. Supported proxy schemes are `http`, `https`, and `socks5`. Requests to a Unix domain
+/// socket (see [`get_unix_socket_uri`]) are always sent directly.
+///
+/// # Errors
+///
+/// Returns a [`ChannelError`] if the channel cannot be constructed.
+#[allow(clippy::result_large_err)]
+pub fn get_channel_with_proxy_config(
+    endpoint: &Endpoint,
+    proxy_config: &ProxyConfig,
+) -> Result<Channel, ChannelError> {
+    if let Some(path) = get_unix_socket_path(endpoint.uri()) {
+        return connect_unix_socket_lazy(endpoint, path);
+    }
+
+    let Some(proxy) = proxy_config.proxy_for(endpoint.uri())? else {
+        return Ok(endpoint.connect_lazy());
+    };
 
     let mut connector = HttpConnector::new();
     connector.enforce_http(false);
+    connector.set_nodelay(endpoint.get_tcp_nodelay());
 
-    let connect_to = |uri: qcs_dependencies_client::http::Uri, intercept: Intercept| {
-        let connector = connector.clone();
-        match uri.scheme_str() {
-            Some("socks5") => {
-                let socks_connector = SocksConnector {
-                    auth: get_uri_socks_auth(&uri)?,
-                    proxy_addr: uri,
-                    connector,
-                };
-                Ok(endpoint.connect_with_connector_lazy(socks_connector))
-            }
-            Some("https" | "http") => {
-                let is_http =
-                    uri.scheme() == Some(&qcs_dependencies_client::http::uri::Scheme::HTTP);
-                let proxy = Proxy::new(intercept, uri);
-                let mut proxy_connector = ProxyConnector::from_proxy(connector, proxy)?;
-                if is_http {
-                    proxy_connector.set_tls(None);
-                }
-                Ok(endpoint.connect_with_connector_lazy(proxy_connector))
-            }
-            scheme => Err(ChannelError::UnsupportedProtocol(scheme.map(String::from))),
+    match proxy.scheme_str() {
+        Some("socks5") => {
+            let socks_connector = SocksConnector {
+                auth: get_uri_socks_auth(&proxy)?,
+                proxy_addr: proxy,
+                connector,
+            };
+            Ok(endpoint.connect_with_connector_lazy(socks_connector))
         }
-    };
-
-    let channel = match (https_proxy, http_proxy) {
-        // no proxies, default behavior
-        (None, None) => endpoint.connect_lazy(),
-
-        // either proxy may use https/http, or socks.
-        (Some(https_proxy), None) => connect_to(https_proxy, Intercept::Https)?,
-        (None, Some(http_proxy)) => connect_to(http_proxy, Intercept::Http)?,
-
-        // both proxies are set. If they are the same, they can be socks5. If there are different, they
-        // must both be `https?` URIs in order to use the same `ProxyConnector`.
-        (Some(https_proxy), Some(http_proxy)) => {
-            if https_proxy == http_proxy {
-                connect_to(https_proxy, Intercept::All)?
-            } else {
-                let accepted = [https_proxy.scheme_str(), http_proxy.scheme_str()]
-                    .into_iter()
-                    .all(|scheme| matches!(scheme, Some("https" | "http")));
-                if accepted {
-                    let mut proxy_connector = ProxyConnector::new(connector)?;
-                    proxy_connector.extend_proxies(vec![
-                        Proxy::new(Intercept::Https, https_proxy),
-                        Proxy::new(Intercept::Http, http_proxy),
-                    ]);
-                    endpoint.connect_with_connector_lazy(proxy_connector)
-                } else {
-                    return Err(ChannelError::Mismatch {
-                        https_proxy,
-                        http_proxy,
-                    });
-                }
+        Some("https" | "http") => {
+            let is_http = proxy.scheme() == Some(&qcs_dependencies_client::http::uri::Scheme::HTTP);
+            let proxy = Proxy::new(Intercept::All, proxy);
+            let mut proxy_connector = ProxyConnector::from_proxy(connector, proxy)?;
+            if is_http {
+                proxy_connector.set_tls(None);
             }
+            Ok(endpoint.connect_with_connector_lazy(proxy_connector))
         }
-    };
-
-    Ok(channel)
+        scheme => Err(ChannelError::UnsupportedProtocol(scheme.map(String::from))),
+    }
 }
 
 /// Get a [`Channel`] to the given [`Uri`] with QCS authentication set up already.
@@ -615,6 +1037,30 @@ where
         .service(channel)
 }
 
+/// Compress the messages of requests made through the `channel`, and decompress those of their
+/// responses, according to the given [`CompressionConfig`]. See [`CompressionService`].
+#[cfg(feature = "compression")]
+pub fn wrap_channel_with_compression<C>(
+    channel: C,
+    config: CompressionConfig,
+) -> CompressionService<C>
+where
+    C: GrpcService<Body>,
+{
+    ServiceBuilder::new()
+        .layer(CompressionLayer::new(config))
+        .service(channel)
+}
+
+/// Record request metrics for the `channel`. See [`MetricsService`].
+#[cfg(feature = "metrics")]
+pub fn wrap_channel_with_metrics<C>(channel: C) -> MetricsService<C>
+where
+    C: GrpcService<Body>,
+{
+    ServiceBuilder::new().layer(MetricsLayer).service(channel)
+}
+
 #[cfg(feature = "tracing")]
 /// Add a tracing layer with OpenTelemetry semantics to the `channel`.
 pub fn wrap_channel_with_tracing(
@@ -626,3 +1072,285 @@ pub fn wrap_channel_with_tracing(
         .layer(build_trace_layer(base_url, Some(&configuration)))
         .service(channel)
 }
+
+#[cfg(test)]
+mod tests {
+    use qcs_api_client_common::configuration::tls::ClientIdentity;
+    use qcs_dependencies_client::tonic::transport::Server;
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, health_check_response::ServingStatus, health_client::HealthClient,
+        health_server::HealthServer,
+    };
+    use qcs_dependencies_client::tonic_health::server::{HealthReporter, HealthService};
+    use tokio_stream::wrappers::UnixListenerStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_no_proxy_bypasses_proxy() {
+        let proxy_config = ProxyConfig {
+            https_proxy: Some("not a proxy uri".to_string()),
+            http_proxy: None,
+            no_proxy: Some("10.0.0.0/8,.internal.test".to_string()),
+        };
+
+        for uri in ["https://10.1.2.3:50051", "https://controller.internal.test"] {
+            let endpoint = get_endpoint(Uri::from_static(uri));
+            assert!(get_channel_with_proxy_config(&endpoint, &proxy_config).is_ok());
+        }
+
+        let endpoint = get_endpoint(Uri::from_static("https://grpc.example.com"));
+        assert!(matches!(
+            get_channel_with_proxy_config(&endpoint, &proxy_config),
+            Err(ChannelError::InvalidUri(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_explicit_proxy_config_takes_precedence() {
+        let proxy_config = ProxyConfig {
+            no_proxy: Some("*".to_string()),
+            ..ProxyConfig::default()
+        };
+        let config = ClientConfiguration::builder()
+            .proxy_config(ProxyConfig {
+                https_proxy: Some("not a proxy uri".to_string()),
+                ..ProxyConfig::default()
+            })
+            .build()
+            .expect("should be able to build client config");
+
+        let builder = ChannelBuilder::from_uri(Uri::from_static("https://grpc.example.com"))
+            .with_proxy_config(proxy_config.clone())
+            .with_qcs_config(config);
+        assert_eq!(builder.proxy_config.as_ref(), Some(&proxy_config));
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_parse_unix_socket_uri() {
+        for s in [
+            "unix:///tmp/qcs.sock",
+            "unix:/tmp/qcs.sock",
+            "unix://localhost/tmp/qcs.sock",
+        ] {
+            let uri = parse_uri(s).expect("should parse unix socket URI");
+            assert_eq!(get_unix_socket_path(&uri), Some("/tmp/qcs.sock"));
+        }
+        assert!(parse_uri("unix:qcs.sock").is_err());
+        assert_eq!(
+            get_unix_socket_path(&Uri::from_static("https://grpc.example.com")),
+            None
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_connects_to_unix_socket() {
+        let reporter = HealthReporter::new();
+        reporter
+            .set_service_status(
+                "",
+                qcs_dependencies_client::tonic_health::ServingStatus::Serving,
+            )
+            .await;
+        let directory = tempfile::TempDir::new().unwrap();
+        let path = directory.path().join("qcs.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(HealthServer::new(HealthService::from_health_reporter(
+                    reporter,
+                )))
+                .serve_with_incoming(UnixListenerStream::new(listener)),
+        );
+
+        // The proxy is never used, so it does not matter that it is invalid.
+        let proxy_config = ProxyConfig {
+            https_proxy: Some("not a proxy uri".to_string()),
+            http_proxy: Some("not a proxy uri".to_string()),
+            no_proxy: None,
+        };
+        let channel =
+            ChannelBuilder::from_uri(parse_uri(&format!("unix://{}", path.display())).unwrap())
+                .with_proxy_config(proxy_config)
+                .with_default_retry()
+                .build()
+                .unwrap();
+
+        let response = HealthClient::new(channel)
+            .check(HealthCheckRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(response.into_inner().status(), ServingStatus::Serving);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_wait_until_serving() {
+        let (reporter, health_server) =
+            qcs_dependencies_client::tonic_health::server::health_reporter();
+        reporter
+            .set_service_status(
+                "",
+                qcs_dependencies_client::tonic_health::ServingStatus::Serving,
+            )
+            .await;
+        let directory = tempfile::TempDir::new().unwrap();
+        let path = directory.path().join("qcs.sock");
+        let uri = parse_uri(&format!("unix://{}", path.display())).unwrap();
+
+        // Nothing is listening on the socket yet.
+        let error = ChannelBuilder::from_uri(uri.clone())
+            .wait_until_serving("", Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ChannelError::NotServing(HealthError::Timeout { .. })
+        ));
+
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(health_server)
+                .serve_with_incoming(UnixListenerStream::new(listener)),
+        );
+        let channel = ChannelBuilder::from_uri(uri)
+            .wait_until_serving("", Duration::from_secs(5))
+            .await
+            .unwrap()
+            .build()
+            .unwrap();
+        let response = HealthClient::new(channel)
+            .check(HealthCheckRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(response.into_inner().status(), ServingStatus::Serving);
+    }
+
+    #[tokio::test]
+    async fn test_uses_qcs_config_tls_settings() {
+        let tls_config = TlsConfig {
+            server_name: Some("qcs.internal.test".to_string()),
+            ..TlsConfig::default()
+        };
+        let config = ClientConfiguration::builder()
+            .proxy_config(ProxyConfig::default())
+            .tls_config(tls_config.clone())
+            .build()
+            .expect("should be able to build client config");
+
+        let builder = ChannelBuilder::from_uri(Uri::from_static("https://10.1.2.3:50051"))
+            .with_qcs_config(config);
+        assert_eq!(builder.tls_config.as_ref(), Some(&tls_config));
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_applies_channel_settings() {
+        let endpoint = get_endpoint(Uri::from_static("https://grpc.example.com"));
+        assert_eq!(
+            endpoint.get_connect_timeout(),
+            ChannelSettings::default().connect_timeout()
+        );
+        assert!(endpoint.get_tcp_nodelay());
+
+        let settings = ChannelSettings {
+            connect_timeout_ms: 1500,
+            tcp_nodelay: false,
+            ..ChannelSettings::default()
+        };
+        let endpoint = apply_channel_settings(endpoint, &settings);
+        assert_eq!(
+            endpoint.get_connect_timeout(),
+            Some(Duration::from_millis(1500))
+        );
+        assert!(!endpoint.get_tcp_nodelay());
+
+        let disabled = ChannelSettings {
+            connect_timeout_ms: 0,
+            ..settings
+        };
+        let endpoint = apply_channel_settings(endpoint, &disabled);
+        assert_eq!(
+            endpoint.get_connect_timeout(),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_channel_settings_precedence() {
+        let profile_settings = ChannelSettings {
+            keepalive_while_idle: true,
+            concurrency_limit: Some(16),
+            ..ChannelSettings::default()
+        };
+        let config = ClientConfiguration::builder()
+            .proxy_config(ProxyConfig::default())
+            .channel_settings(profile_settings.clone())
+            .build()
+            .expect("should be able to build client config");
+        let uri = Uri::from_static("https://10.1.2.3:50051");
+
+        let builder = ChannelBuilder::from_uri(uri.clone()).with_qcs_config(config.clone());
+        assert_eq!(builder.channel_settings.as_ref(), Some(&profile_settings));
+        assert!(builder.build().is_ok());
+
+        let explicit_settings = ChannelSettings {
+            initial_stream_window_size: Some(1 << 20),
+            initial_connection_window_size: Some(1 << 22),
+            ..ChannelSettings::default()
+        };
+        let builder = ChannelBuilder::from_uri(uri)
+            .with_channel_settings(explicit_settings.clone())
+            .with_qcs_config(config);
+        assert_eq!(builder.channel_settings.as_ref(), Some(&explicit_settings));
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_from_uri_uses_default_channel_settings() {
+        let builder = ChannelBuilder::from_uri(Uri::from_static("https://grpc.example.com"));
+        assert!(builder.channel_settings.is_none());
+
+        let endpoint = builder.build_endpoint().unwrap();
+        assert_eq!(
+            endpoint.get_connect_timeout(),
+            ChannelSettings::default().connect_timeout()
+        );
+        assert!(endpoint.get_tcp_nodelay());
+    }
+
+    #[test]
+    fn test_keeps_endpoint_settings_without_channel_settings() {
+        let endpoint = Endpoint::from_static("https://grpc.example.com")
+            .connect_timeout(Duration::from_secs(7));
+        let builder = ChannelBuilder::from(endpoint);
+        assert_eq!(
+            builder.build_endpoint().unwrap().get_connect_timeout(),
+            Some(Duration::from_secs(7))
+        );
+
+        let builder = builder.with_channel_settings(ChannelSettings {
+            connect_timeout_ms: 1500,
+            ..ChannelSettings::default()
+        });
+        assert_eq!(
+            builder.build_endpoint().unwrap().get_connect_timeout(),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tls_config() {
+        let builder = ChannelBuilder::from_uri(Uri::from_static("https://grpc.example.com"))
+            .with_proxy_config(ProxyConfig::default())
+            .with_tls_config(TlsConfig {
+                client_identity: Some(ClientIdentity::new("not a certificate", "not a key")),
+                ..TlsConfig::default()
+            });
+        assert!(matches!(
+            builder.build(),
+            Err(ChannelError::InvalidTlsConfig(_))
+        ));
+    }
+}
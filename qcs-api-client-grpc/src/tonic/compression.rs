@@ -0,0 +1,690 @@
+//! Compression of gRPC messages.
+//!
+//! Generated clients such as `ControllerClient` and `TranslationClient` can only compress
+//! messages when configured individually. A [`CompressionLayer`] instead compresses the messages
+//! of every request sent through a channel, and decompresses those of every response, so that
+//! large job payloads and results are compressed for any client built on the channel.
+
+use std::{
+    future::Future,
+    io::{self, Read, Write},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, ready},
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use qcs_dependencies_client::http::{HeaderMap, HeaderValue, Request, Response};
+use qcs_dependencies_client::prost::bytes::{Buf, BufMut, Bytes, BytesMut};
+use qcs_dependencies_client::tonic::{Status, client::GrpcService};
+use qcs_dependencies_client::tower::Layer;
+
+use super::Body;
+
+/// The header naming the encoding used to compress the messages of a request or response.
+const GRPC_ENCODING: &str = "grpc-encoding";
+
+/// The header listing the encodings a client accepts for the messages of a response.
+const GRPC_ACCEPT_ENCODING: &str = "grpc-accept-encoding";
+
+/// The length of the prefix of each gRPC message: a compressed flag and a 4-byte length.
+const MESSAGE_PREFIX_LEN: usize = 5;
+
+/// The default limit on the size of a decompressed response message, which matches the default
+/// limit tonic applies when decoding messages.
+pub const DEFAULT_MAX_DECODING_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// An encoding used to compress gRPC messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CompressionEncoding {
+    /// The `gzip` encoding.
+    Gzip,
+    /// The `zstd` encoding.
+    Zstd,
+}
+
+impl CompressionEncoding {
+    /// The name of the encoding, as used in the `grpc-encoding` header.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    fn from_header(value: &HeaderValue) -> Option<Self> {
+        match value.as_bytes() {
+            b"gzip" => Some(Self::Gzip),
+            b"zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    fn compress(self, message: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(message)?;
+                encoder.finish()
+            }
+            Self::Zstd => zstd::encode_all(message, zstd::DEFAULT_COMPRESSION_LEVEL),
+        }
+    }
+
+    /// Decompress at most `limit` bytes of the message, and one more, so that a message which
+    /// exceeds the limit is detected without decompressing it whole.
+    fn decompress(self, message: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Self::Gzip => Box::new(GzDecoder::new(message)),
+            Self::Zstd => Box::new(zstd::Decoder::new(message)?),
+        };
+        let mut decompressed = Vec::new();
+        decoder
+            .take(u64::try_from(limit).unwrap_or(u64::MAX).saturating_add(1))
+            .read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+}
+
+/// Which encodings a [`CompressionLayer`] uses for requests and accepts for responses.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// The encoding used to compress request messages. If `None`, requests are not compressed.
+    pub send: Option<CompressionEncoding>,
+    /// The encodings the server may use to compress response messages, in order of preference.
+    /// If empty, the server is asked not to compress responses.
+    pub accept: Vec<CompressionEncoding>,
+    /// The largest size of a decompressed response message, in bytes. If `None`,
+    /// [`DEFAULT_MAX_DECODING_MESSAGE_SIZE`] is used. A client which raises its own
+    /// `max_decoding_message_size` must raise this limit to match.
+    pub max_decoding_message_size: Option<usize>,
+}
+
+impl CompressionConfig {
+    /// Compress requests with, and accept responses compressed with, the given `encoding`.
+    #[must_use]
+    pub fn new(encoding: CompressionEncoding) -> Self {
+        Self {
+            send: Some(encoding),
+            accept: vec![encoding],
+            max_decoding_message_size: None,
+        }
+    }
+
+    /// Compress requests with, and accept responses compressed with, `gzip`.
+    #[must_use]
+    pub fn gzip() -> Self {
+        Self::new(CompressionEncoding::Gzip)
+    }
+
+    /// Compress requests with, and accept responses compressed with, `zstd`.
+    #[must_use]
+    pub fn zstd() -> Self {
+        Self::new(CompressionEncoding::Zstd)
+    }
+
+    /// Limit decompressed response messages to the given size, in bytes.
+    #[must_use]
+    pub const fn with_max_decoding_message_size(mut self, limit: usize) -> Self {
+        self.max_decoding_message_size = Some(limit);
+        self
+    }
+
+    fn max_decoding_message_size(&self) -> usize {
+        self.max_decoding_message_size
+            .unwrap_or(DEFAULT_MAX_DECODING_MESSAGE_SIZE)
+    }
+
+    fn accept_header(&self) -> Option<HeaderValue> {
+        let encodings = self
+            .accept
+            .iter()
+            .map(|encoding| encoding.as_str())
+            .collect::<Vec<_>>();
+        if encodings.is_empty() {
+            None
+        } else {
+            Some(HeaderValue::from_str(&encodings.join(",")).expect("encodings are valid headers"))
+        }
+    }
+}
+
+/// The [`Layer`] used to compress request messages and decompress response messages.
+///
+/// Requests which already carry a `grpc-encoding` header, such as those of a generated client
+/// configured with `send_compressed`, are sent as they are. Likewise, responses to requests
+/// which already carry a `grpc-accept-encoding` header are left for the client to decompress.
+///
+/// A [`super::RetryService`] retains the request body in order to replay it. When this layer
+/// wraps the retry layer, the compressed body is retained, which allows larger requests to be
+/// retried within the same replay buffer limit.
+///
+/// See also: [`CompressionService`].
+#[derive(Clone, Debug, Default)]
+pub struct CompressionLayer {
+    config: Arc<CompressionConfig>,
+}
+
+impl CompressionLayer {
+    /// Create a [`CompressionLayer`] with the given [`CompressionConfig`].
+    #[must_use]
+    pub fn new(config: CompressionConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<S: GrpcService<Body>> Layer<S> for CompressionLayer {
+    type Service = CompressionService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        CompressionService {
+            config: self.config.clone(),
+            service,
+        }
+    }
+}
+
+/// The [`GrpcService`] that compresses request messages and decompresses response messages.
+///
+/// See also: [`CompressionLayer`].
+#[derive(Clone, Debug)]
+pub struct CompressionService<S> {
+    config: Arc<CompressionConfig>,
+    service: S,
+}
+
+impl<S> GrpcService<Body> for CompressionService<S>
+where
+    S: GrpcService<Body>,
+    S::Future: Send + 'static,
+    <S::ResponseBody as HttpBody>::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type ResponseBody = CompressionBody<S::ResponseBody>;
+    type Error = S::Error;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Response<Self::ResponseBody>, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        if let Some(encoding) = self.config.send {
+            if !req.headers().contains_key(GRPC_ENCODING) {
+                req.headers_mut()
+                    .insert(GRPC_ENCODING, HeaderValue::from_static(encoding.as_str()));
+                req = req.map(|body| Body::new(CompressionBody::compress(body, encoding)));
+            }
+        }
+
+        let mut accept = Vec::new();
+        if !req.headers().contains_key(GRPC_ACCEPT_ENCODING) {
+            if let Some(value) = self.config.accept_header() {
+                req.headers_mut().insert(GRPC_ACCEPT_ENCODING, value);
+                accept.clone_from(&self.config.accept);
+            }
+        }
+
+        let limit = self.config.max_decoding_message_size();
+        let future = self.service.call(req);
+        super::common::pin_future_with_otel_context_if_available(async move {
+            let mut response = future.await?;
+            let encoding = accepted_encoding(response.headers(), &accept);
+            if encoding.is_some() {
+                response.headers_mut().remove(GRPC_ENCODING);
+            }
+            Ok(response.map(|body| match encoding {
+                Some(encoding) => CompressionBody::decompress(body, encoding, limit),
+                None => CompressionBody::pass_through(body),
+            }))
+        })
+    }
+}
+
+/// The encoding of the response messages, if it is one this layer accepted on the client's behalf.
+fn accepted_encoding(
+    headers: &HeaderMap,
+    accept: &[CompressionEncoding],
+) -> Option<CompressionEncoding> {
+    headers
+        .get(GRPC_ENCODING)
+        .and_then(CompressionEncoding::from_header)
+        .filter(|encoding| accept.contains(encoding))
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Mode {
+    Compress(CompressionEncoding),
+    /// Decompress messages, up to the given size.
+    Decompress(CompressionEncoding, usize),
+    PassThrough,
+}
+
+/// A body whose gRPC messages are compressed or decompressed as they are read.
+///
+/// See [`CompressionService`].
+pub struct CompressionBody<B> {
+    inner: Pin<Box<B>>,
+    mode: Mode,
+    buffer: BytesMut,
+}
+
+impl<B> std::fmt::Debug for CompressionBody<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompressionBody")
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B> CompressionBody<B> {
+    fn new(inner: B, mode: Mode) -> Self {
+        Self {
+            inner: Box::pin(inner),
+            mode,
+            buffer: BytesMut::new(),
+        }
+    }
+
+    fn compress(inner: B, encoding: CompressionEncoding) -> Self {
+        Self::new(inner, Mode::Compress(encoding))
+    }
+
+    fn decompress(inner: B, encoding: CompressionEncoding, limit: usize) -> Self {
+        Self::new(inner, Mode::Decompress(encoding, limit))
+    }
+
+    fn pass_through(inner: B) -> Self {
+        Self::new(inner, Mode::PassThrough)
+    }
+
+    /// Split the next complete message off the buffer, and re-encode it according to the mode.
+    fn next_message(&mut self) -> Option<Result<Bytes, Status>> {
+        if self.buffer.len() < MESSAGE_PREFIX_LEN {
+            return None;
+        }
+        let compressed = self.buffer[0] != 0;
+        let len = u32::from_be_bytes([
+            self.buffer[1],
+            self.buffer[2],
+            self.buffer[3],
+            self.buffer[4],
+        ]) as usize;
+        if self.buffer.len() < MESSAGE_PREFIX_LEN + len {
+            return None;
+        }
+        let mut message = self.buffer.split_to(MESSAGE_PREFIX_LEN + len);
+        let payload = message.split_off(MESSAGE_PREFIX_LEN);
+
+        Some(reencode(self.mode, compressed, &payload))
+    }
+}
+
+/// Compress or decompress a message payload according to the `mode`, and prefix the result.
+fn reencode(mode: Mode, compressed: bool, payload: &[u8]) -> Result<Bytes, Status> {
+    let (compressed, payload) = match mode {
+        Mode::Compress(encoding) if !compressed => {
+            let payload = encoding.compress(payload).map_err(|error| {
+                Status::internal(format!("failed to compress request message: {error}"))
+            })?;
+            (true, payload)
+        }
+        Mode::Decompress(encoding, limit) if compressed => {
+            let payload = encoding.decompress(payload, limit).map_err(|error| {
+                Status::internal(format!("failed to decompress response message: {error}"))
+            })?;
+            if payload.len() > limit {
+                return Err(Status::resource_exhausted(format!(
+                    "decompressed response message is larger than the limit of {limit} bytes"
+                )));
+            }
+            (false, payload)
+        }
+        _ => (compressed, payload.to_vec()),
+    };
+    let len = u32::try_from(payload.len())
+        .map_err(|_| Status::resource_exhausted("message is too large to encode"))?;
+    let mut message = BytesMut::with_capacity(MESSAGE_PREFIX_LEN + payload.len());
+    message.put_u8(u8::from(compressed));
+    message.put_u32(len);
+    message.put_slice(&payload);
+    Ok(message.freeze())
+}
+
+impl<B> HttpBody for CompressionBody<B>
+where
+    B: HttpBody,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        loop {
+            if !matches!(self.mode, Mode::PassThrough) {
+                if let Some(message) = self.next_message() {
+                    return Poll::Ready(Some(message.map(Frame::data)));
+                }
+            }
+
+            let frame = match ready!(self.inner.as_mut().poll_frame(cx)) {
+                Some(Ok(frame)) => frame,
+                Some(Err(error)) => {
+                    return Poll::Ready(Some(Err(Status::from_error(error.into()))));
+                }
+                // Pass on a truncated message as is, for the client to report.
+                None if !self.buffer.is_empty() => {
+                    let rest = self.buffer.split().freeze();
+                    return Poll::Ready(Some(Ok(Frame::data(rest))));
+                }
+                None => return Poll::Ready(None),
+            };
+            match frame.into_data() {
+                Ok(mut data) => {
+                    if matches!(self.mode, Mode::PassThrough) {
+                        return Poll::Ready(Some(Ok(Frame::data(
+                            data.copy_to_bytes(data.remaining()),
+                        ))));
+                    }
+                    self.buffer.put(data);
+                }
+                Err(frame) => {
+                    let Ok(trailers) = frame.into_trailers() else {
+                        continue;
+                    };
+                    return Poll::Ready(Some(Ok(Frame::trailers(trailers))));
+                }
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.buffer.is_empty() && self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match self.mode {
+            Mode::PassThrough => self.inner.size_hint(),
+            Mode::Compress(_) | Mode::Decompress(..) => SizeHint::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use http_body_util::BodyExt;
+    use qcs_dependencies_client::tonic::{Code, Request as GrpcRequest};
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, HealthCheckResponse,
+        health_check_response::ServingStatus,
+        health_client::HealthClient,
+        health_server::{Health, HealthServer},
+    };
+    use qcs_dependencies_client::tonic_health::server::HealthService;
+
+    use super::*;
+    use crate::tonic::{RetryLayer, uds_grpc_stream, wrap_channel_with_compression};
+
+    /// A service long enough to be worth compressing.
+    fn long_service_name() -> String {
+        "services.controller.Controller".repeat(64)
+    }
+
+    /// Fails the first `failures` checks with `UNAVAILABLE`, and records the services checked.
+    #[derive(Default)]
+    struct FlakyHealthService {
+        failures: AtomicUsize,
+        services: Mutex<Vec<String>>,
+    }
+
+    #[qcs_dependencies_client::tonic::async_trait]
+    impl Health for FlakyHealthService {
+        type WatchStream = <HealthService as Health>::WatchStream;
+
+        async fn check(
+            &self,
+            request: GrpcRequest<HealthCheckRequest>,
+        ) -> Result<qcs_dependencies_client::tonic::Response<HealthCheckResponse>, Status> {
+            self.services
+                .lock()
+                .unwrap()
+                .push(request.into_inner().service);
+            if self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Err(Status::unavailable("unavailable"));
+            }
+            Ok(qcs_dependencies_client::tonic::Response::new(
+                HealthCheckResponse {
+                    status: ServingStatus::Serving as i32,
+                },
+            ))
+        }
+
+        async fn watch(
+            &self,
+            _request: GrpcRequest<HealthCheckRequest>,
+        ) -> Result<qcs_dependencies_client::tonic::Response<Self::WatchStream>, Status> {
+            Err(Status::unimplemented("watch"))
+        }
+    }
+
+    /// The `grpc-encoding` headers of each request and its response.
+    type Encodings = Arc<Mutex<Vec<(Option<HeaderValue>, Option<HeaderValue>)>>>;
+
+    /// Records the encodings of the requests and responses passing through it.
+    #[derive(Clone)]
+    struct EncodingSpy<S> {
+        service: S,
+        encodings: Encodings,
+    }
+
+    impl<S> GrpcService<Body> for EncodingSpy<S>
+    where
+        S: GrpcService<Body>,
+        S::Future: Send + 'static,
+    {
+        type ResponseBody = S::ResponseBody;
+        type Error = S::Error;
+        type Future =
+            Pin<Box<dyn Future<Output = Result<Response<Self::ResponseBody>, Self::Error>> + Send>>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.service.poll_ready(cx)
+        }
+
+        fn call(&mut self, req: Request<Body>) -> Self::Future {
+            let request_encoding = req.headers().get(GRPC_ENCODING).cloned();
+            let encodings = self.encodings.clone();
+            let future = self.service.call(req);
+            Box::pin(async move {
+                let response = future.await?;
+                let response_encoding = response.headers().get(GRPC_ENCODING).cloned();
+                encodings
+                    .lock()
+                    .unwrap()
+                    .push((request_encoding, response_encoding));
+                Ok(response)
+            })
+        }
+    }
+
+    fn health_server(service: &Arc<FlakyHealthService>) -> HealthServer<FlakyHealthService> {
+        HealthServer::from_arc(service.clone())
+            .accept_compressed(qcs_dependencies_client::tonic::codec::CompressionEncoding::Gzip)
+            .send_compressed(qcs_dependencies_client::tonic::codec::CompressionEncoding::Gzip)
+    }
+
+    #[rstest::rstest]
+    #[case::gzip(CompressionEncoding::Gzip)]
+    #[case::zstd(CompressionEncoding::Zstd)]
+    fn test_round_trips_messages(#[case] encoding: CompressionEncoding) {
+        let message = long_service_name().into_bytes();
+        let compressed = encoding.compress(&message).unwrap();
+        assert!(compressed.len() < message.len());
+        assert_eq!(
+            encoding
+                .decompress(&compressed, DEFAULT_MAX_DECODING_MESSAGE_SIZE)
+                .unwrap(),
+            message
+        );
+        assert!(
+            encoding
+                .decompress(b"not compressed", DEFAULT_MAX_DECODING_MESSAGE_SIZE)
+                .is_err()
+        );
+        assert_eq!(
+            CompressionEncoding::from_header(&HeaderValue::from_static(encoding.as_str())),
+            Some(encoding)
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::gzip(CompressionEncoding::Gzip)]
+    #[case::zstd(CompressionEncoding::Zstd)]
+    #[tokio::test]
+    async fn test_limits_decompressed_messages(#[case] encoding: CompressionEncoding) {
+        let message = long_service_name().into_bytes();
+        let compressed = reencode(Mode::Compress(encoding), false, &message).unwrap();
+        let decompress = |limit| {
+            CompressionBody::decompress(
+                http_body_util::Full::new(compressed.clone()),
+                encoding,
+                limit,
+            )
+            .collect()
+        };
+
+        let decompressed = decompress(message.len()).await.unwrap().to_bytes();
+        assert_eq!(decompressed[MESSAGE_PREFIX_LEN..], message[..]);
+
+        let status = decompress(message.len() - 1).await.unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+    }
+
+    #[tokio::test]
+    async fn test_compresses_requests_and_responses() {
+        let service = Arc::new(FlakyHealthService::default());
+        let encodings = Arc::default();
+
+        uds_grpc_stream::serve(health_server(&service), |channel| {
+            let encodings = Arc::clone(&encodings);
+            async move {
+                let spy = EncodingSpy {
+                    service: channel,
+                    encodings,
+                };
+                let mut client = HealthClient::new(wrap_channel_with_compression(
+                    spy,
+                    CompressionConfig::gzip(),
+                ));
+                let response = client
+                    .check(HealthCheckRequest {
+                        service: long_service_name(),
+                    })
+                    .await
+                    .unwrap();
+                assert_eq!(response.into_inner().status(), ServingStatus::Serving);
+            }
+        })
+        .await
+        .unwrap();
+
+        let gzip = Some(HeaderValue::from_static("gzip"));
+        assert_eq!(*encodings.lock().unwrap(), vec![(gzip.clone(), gzip)]);
+        assert_eq!(*service.services.lock().unwrap(), vec![long_service_name()]);
+    }
+
+    #[tokio::test]
+    async fn test_leaves_uncompressed_responses_alone() {
+        let service = Arc::new(FlakyHealthService::default());
+        let config = CompressionConfig {
+            send: Some(CompressionEncoding::Gzip),
+            accept: Vec::new(),
+            max_decoding_message_size: None,
+        };
+
+        uds_grpc_stream::serve(health_server(&service), |channel| async move {
+            let response = HealthClient::new(wrap_channel_with_compression(channel, config))
+                .check(HealthCheckRequest {
+                    service: long_service_name(),
+                })
+                .await
+                .unwrap();
+            assert_eq!(response.into_inner().status(), ServingStatus::Serving);
+        })
+        .await
+        .unwrap();
+    }
+
+    #[rstest::rstest]
+    #[case::compression_outside_retries(true)]
+    #[case::compression_inside_retries(false)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_compressed_requests_are_replayed(#[case] compression_outside_retries: bool) {
+        let service = Arc::new(FlakyHealthService {
+            failures: AtomicUsize::new(2),
+            ..FlakyHealthService::default()
+        });
+
+        uds_grpc_stream::serve(health_server(&service), |channel| async move {
+            let request = HealthCheckRequest {
+                service: long_service_name(),
+            };
+            let result = if compression_outside_retries {
+                let channel = CompressionLayer::new(CompressionConfig::gzip())
+                    .layer(RetryLayer::default().layer(channel));
+                HealthClient::new(channel).check(request).await
+            } else {
+                let channel = RetryLayer::default()
+                    .layer(CompressionLayer::new(CompressionConfig::gzip()).layer(channel));
+                HealthClient::new(channel).check(request).await
+            };
+            assert_eq!(
+                result.unwrap().into_inner().status(),
+                ServingStatus::Serving
+            );
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            *service.services.lock().unwrap(),
+            vec![long_service_name(); 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_rejects_unsupported_compression() {
+        let (_reporter, health_server) =
+            qcs_dependencies_client::tonic_health::server::health_reporter();
+
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let status = HealthClient::new(wrap_channel_with_compression(
+                channel,
+                CompressionConfig::gzip(),
+            ))
+            .check(HealthCheckRequest::default())
+            .await
+            .unwrap_err();
+            assert_eq!(status.code(), Code::Unimplemented);
+        })
+        .await
+        .unwrap();
+    }
+}
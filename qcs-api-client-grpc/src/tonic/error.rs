@@ -1,13 +1,17 @@
+use std::borrow::Cow;
+
 use qcs_dependencies_client::http::{header::InvalidHeaderValue, uri::InvalidUri};
-use qcs_dependencies_client::tonic::transport::Error as TransportError;
+use qcs_dependencies_client::tonic::{Code, Status, transport::Error as TransportError};
 
-use qcs_api_client_common::configuration::LoadError;
+use qcs_api_client_common::classify::{ClassifyError, ErrorCategory};
+use qcs_api_client_common::configuration::{LoadError, TokenError};
 
-use super::{RequestBodyDuplicationError, channel::ChannelError};
+#[allow(deprecated)]
+use super::{RequestBodyDuplicationError, StatusExt, channel::ChannelError};
 
 /// Errors that may occur when using gRPC.
 #[derive(Debug, thiserror::Error)]
-#[allow(variant_size_differences)]
+#[allow(variant_size_differences, deprecated)]
 pub enum Error<E>
 where
     E: std::error::Error,
@@ -15,6 +19,10 @@ where
     /// Failed to refresh the access token.
     #[error("failed to refresh access token: {0}")]
     Refresh(#[source] E),
+    /// Failed to get an access token from a request's
+    /// [`TokenOverride`](qcs_api_client_common::configuration::tokens::TokenOverride).
+    #[error("failed to get access token from token override: {0}")]
+    RefreshOverride(#[source] TokenError),
     /// Failed to load the QCS configuration.
     #[error("failed to load QCS config: {0}")]
     Load(#[from] LoadError),
@@ -33,6 +41,185 @@ where
     #[cfg(feature = "grpc-web")]
     #[error("The hyper grpc-web client returned an error: {0}")]
     HyperError(#[from] hyper::Error),
+    /// A request through a [`FallbackChannel`](super::FallbackChannel) failed.
+    #[cfg(feature = "grpc-web-fallback")]
+    #[error("service call failed with error: {0}")]
+    Fallback(#[from] super::FallbackError),
+    /// Failed to duplicate a request body for a retry.
+    #[deprecated(
+        note = "request bodies are buffered once and replayed for retries, so this is never returned"
+    )]
     #[error("failed to duplicate request body for retry: {0}")]
     CloneBody(#[from] RequestBodyDuplicationError),
 }
+
+#[allow(deprecated)]
+impl<E> ClassifyError for Error<E>
+where
+    E: std::error::Error + ClassifyError,
+{
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Refresh(error) => error.category(),
+            Self::RefreshOverride(error) => error.category(),
+            Self::Load(error) => error.category(),
+            Self::InvalidUri(_) => ErrorCategory::InvalidInput,
+            Self::Transport(_) => ErrorCategory::Transient,
+            Self::InvalidAccessToken(_) => ErrorCategory::AuthRequired,
+            Self::ChannelError(ChannelError::NotServing(_)) => ErrorCategory::Transient,
+            Self::ChannelError(_) => ErrorCategory::InvalidInput,
+            #[cfg(feature = "grpc-web")]
+            Self::HyperError(_) => ErrorCategory::Transient,
+            #[cfg(feature = "grpc-web-fallback")]
+            Self::Fallback(_) => ErrorCategory::Transient,
+            Self::CloneBody(RequestBodyDuplicationError::Status(status)) => {
+                ClassifiedStatus(status).category()
+            }
+            Self::CloneBody(RequestBodyDuplicationError::HttpBody(_)) => ErrorCategory::Internal,
+        }
+    }
+
+    fn server_request_id(&self) -> Option<&str> {
+        match self {
+            Self::CloneBody(RequestBodyDuplicationError::Status(status)) => {
+                ClassifiedStatus(status).request_id()
+            }
+            _ => None,
+        }
+    }
+
+    fn hint(&self) -> Cow<'static, str> {
+        match self {
+            Self::Refresh(error) => error.hint(),
+            Self::RefreshOverride(error) => error.hint(),
+            Self::Load(error) => error.hint(),
+            Self::CloneBody(RequestBodyDuplicationError::Status(status)) => {
+                ClassifiedStatus(status).hint()
+            }
+            _ => Cow::Borrowed(self.category().hint()),
+        }
+    }
+}
+
+/// A gRPC [`Status`] returned by a QCS service, classified with [`ClassifyError`].
+///
+/// Created with [`StatusExt::classify`](super::StatusExt::classify).
+#[derive(Clone, Copy, Debug)]
+pub struct ClassifiedStatus<'a>(pub &'a Status);
+
+impl<'a> ClassifiedStatus<'a> {
+    /// The `x-request-id` the server attached to the status, if any.
+    #[must_use]
+    pub fn request_id(self) -> Option<&'a str> {
+        self.0
+            .metadata()
+            .get(super::retry::KEY_X_REQUEST_ID)
+            .and_then(|value| value.to_str().ok())
+    }
+}
+
+impl ClassifyError for ClassifiedStatus<'_> {
+    fn category(&self) -> ErrorCategory {
+        match self.0.code() {
+            Code::Unauthenticated => ErrorCategory::AuthRequired,
+            Code::PermissionDenied => ErrorCategory::PermissionDenied,
+            Code::NotFound => ErrorCategory::NotFound,
+            Code::InvalidArgument
+            | Code::FailedPrecondition
+            | Code::OutOfRange
+            | Code::AlreadyExists => ErrorCategory::InvalidInput,
+            Code::ResourceExhausted => ErrorCategory::Quota,
+            Code::Unavailable | Code::DeadlineExceeded | Code::Aborted | Code::Cancelled => {
+                ErrorCategory::Transient
+            }
+            Code::Ok | Code::Unknown | Code::Unimplemented | Code::Internal | Code::DataLoss => {
+                ErrorCategory::Internal
+            }
+        }
+    }
+
+    fn server_request_id(&self) -> Option<&str> {
+        self.request_id()
+    }
+
+    fn hint(&self) -> Cow<'static, str> {
+        let details = self.0.error_details();
+        let violations = details
+            .field_violations()
+            .map(|violation| format!("{}: {}", violation.field, violation.description))
+            .chain(
+                details
+                    .quota_failure()
+                    .into_iter()
+                    .flat_map(|quota_failure| &quota_failure.violations)
+                    .map(|violation| violation.description.clone()),
+            )
+            .collect::<Vec<_>>();
+        if violations.is_empty() {
+            Cow::Borrowed(self.category().hint())
+        } else {
+            Cow::Owned(format!(
+                "{} ({})",
+                self.category().hint(),
+                violations.join("; ")
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use qcs_dependencies_client::http::Uri;
+    use qcs_dependencies_client::prost::Message;
+    use qcs_dependencies_client::tonic::metadata::MetadataValue;
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, health_client::HealthClient, health_server::HealthServer,
+    };
+
+    use super::*;
+    use crate::tonic::error_details::testing::{FailingHealthService, status_with_details};
+    use crate::tonic::{QuotaFailure, QuotaViolation, uds_grpc_stream};
+
+    #[tokio::test]
+    async fn test_classify_status_from_server_response() {
+        let quota_failure = QuotaFailure {
+            violations: vec![QuotaViolation {
+                subject: "user:test".to_string(),
+                description: "too many jobs in queue".to_string(),
+            }],
+        };
+        let mut status = status_with_details(
+            Code::ResourceExhausted,
+            "quota exceeded",
+            vec![("google.rpc.QuotaFailure", quota_failure.encode_to_vec())],
+        );
+        status
+            .metadata_mut()
+            .insert("x-request-id", MetadataValue::from_static("abc-123"));
+        let health_server = HealthServer::new(FailingHealthService(status));
+
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let status = HealthClient::new(channel)
+                .check(HealthCheckRequest::default())
+                .await
+                .unwrap_err();
+            let classified = status.classify();
+            assert_eq!(classified.category(), ErrorCategory::Quota);
+            assert!(classified.is_retryable());
+            assert_eq!(classified.server_request_id(), Some("abc-123"));
+            assert!(classified.hint().contains("too many jobs in queue"));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let error = Error::Refresh(TokenError::NoRefreshToken);
+        assert_eq!(error.category(), ErrorCategory::AuthRequired);
+        assert!(error.server_request_id().is_none());
+
+        let error = Error::<TokenError>::InvalidUri("not a uri".parse::<Uri>().unwrap_err());
+        assert_eq!(error.category(), ErrorCategory::InvalidInput);
+    }
+}
@@ -0,0 +1,433 @@
+//! Decoding of the [rich error model](https://cloud.google.com/apis/design/errors#error_model)
+//! details which servers attach to a gRPC [`Status`] in its `grpc-status-details-bin` metadata.
+
+use std::{collections::HashMap, time::Duration};
+
+use qcs_dependencies_client::pbjson_types::{self, Any};
+use qcs_dependencies_client::prost::{Message, bytes::Bytes};
+use qcs_dependencies_client::tonic::Status;
+
+use super::ClassifiedStatus;
+
+const TYPE_BAD_REQUEST: &str = "google.rpc.BadRequest";
+const TYPE_ERROR_INFO: &str = "google.rpc.ErrorInfo";
+const TYPE_RETRY_INFO: &str = "google.rpc.RetryInfo";
+const TYPE_QUOTA_FAILURE: &str = "google.rpc.QuotaFailure";
+
+/// The `google.rpc.Status` message encoded in a [`Status`]'s details.
+#[derive(Clone, PartialEq, Message)]
+#[prost(prost_path = "::qcs_dependencies_client::prost")]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<Any>,
+}
+
+/// Describes violations in a client request, such as invalid fields (`google.rpc.BadRequest`).
+#[derive(Clone, PartialEq, Eq, Message)]
+#[prost(prost_path = "::qcs_dependencies_client::prost")]
+pub struct BadRequest {
+    /// Describes all violations in the request.
+    #[prost(message, repeated, tag = "1")]
+    pub field_violations: Vec<FieldViolation>,
+}
+
+/// A single invalid field in a [`BadRequest`].
+#[derive(Clone, PartialEq, Eq, Message)]
+#[prost(prost_path = "::qcs_dependencies_client::prost")]
+pub struct FieldViolation {
+    /// The path to the invalid field within the request, e.g. `quil_program`.
+    #[prost(string, tag = "1")]
+    pub field: String,
+    /// Why the field is invalid.
+    #[prost(string, tag = "2")]
+    pub description: String,
+}
+
+/// The cause of an error, in a machine-readable form (`google.rpc.ErrorInfo`).
+#[derive(Clone, PartialEq, Eq, Message)]
+#[prost(prost_path = "::qcs_dependencies_client::prost")]
+pub struct ErrorInfo {
+    /// The reason of the error, a constant in `UPPER_SNAKE_CASE` unique within the `domain`.
+    #[prost(string, tag = "1")]
+    pub reason: String,
+    /// The logical grouping to which the `reason` belongs, usually the service name.
+    #[prost(string, tag = "2")]
+    pub domain: String,
+    /// Additional structured details about the error.
+    #[prost(map = "string, string", tag = "3")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// How long the client should wait before retrying the request (`google.rpc.RetryInfo`).
+#[derive(Clone, PartialEq, Message)]
+#[prost(prost_path = "::qcs_dependencies_client::prost")]
+pub struct RetryInfo {
+    /// The minimum delay before the request is retried.
+    #[prost(message, optional, tag = "1")]
+    pub retry_delay: Option<pbjson_types::Duration>,
+}
+
+impl RetryInfo {
+    /// The minimum delay before the request is retried, if it is given and not negative.
+    #[must_use]
+    pub fn delay(&self) -> Option<Duration> {
+        let delay = self.retry_delay.as_ref()?;
+        let seconds = u64::try_from(delay.seconds).ok()?;
+        let nanos = u32::try_from(delay.nanos).ok()?;
+        Some(Duration::new(seconds, nanos))
+    }
+}
+
+/// Describes how a quota check failed (`google.rpc.QuotaFailure`).
+#[derive(Clone, PartialEq, Eq, Message)]
+#[prost(prost_path = "::qcs_dependencies_client::prost")]
+pub struct QuotaFailure {
+    /// Describes all quota violations.
+    #[prost(message, repeated, tag = "1")]
+    pub violations: Vec<QuotaViolation>,
+}
+
+/// A single exceeded quota in a [`QuotaFailure`].
+#[derive(Clone, PartialEq, Eq, Message)]
+#[prost(prost_path = "::qcs_dependencies_client::prost")]
+pub struct QuotaViolation {
+    /// The subject on which the quota check failed, e.g. `user:<user id>`.
+    #[prost(string, tag = "1")]
+    pub subject: String,
+    /// Why the quota check failed.
+    #[prost(string, tag = "2")]
+    pub description: String,
+}
+
+/// A single detail attached to a gRPC [`Status`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorDetail {
+    /// See [`BadRequest`].
+    BadRequest(BadRequest),
+    /// See [`ErrorInfo`].
+    ErrorInfo(ErrorInfo),
+    /// See [`RetryInfo`].
+    RetryInfo(RetryInfo),
+    /// See [`QuotaFailure`].
+    QuotaFailure(QuotaFailure),
+    /// A detail of a type which is not decoded, or which could not be decoded.
+    Unknown {
+        /// The URL identifying the type of the detail, e.g.
+        /// `type.googleapis.com/google.rpc.DebugInfo`.
+        type_url: String,
+        /// The encoded detail.
+        value: Bytes,
+    },
+}
+
+impl ErrorDetail {
+    fn decode(any: Any) -> Self {
+        let value: &[u8] = any.value.as_ref();
+        let type_name = any.type_url.rsplit('/').next().unwrap_or_default();
+        let decoded = match type_name {
+            TYPE_BAD_REQUEST => BadRequest::decode(value).ok().map(Self::BadRequest),
+            TYPE_ERROR_INFO => ErrorInfo::decode(value).ok().map(Self::ErrorInfo),
+            TYPE_RETRY_INFO => RetryInfo::decode(value).ok().map(Self::RetryInfo),
+            TYPE_QUOTA_FAILURE => QuotaFailure::decode(value).ok().map(Self::QuotaFailure),
+            _ => None,
+        };
+        decoded.unwrap_or_else(|| Self::Unknown {
+            type_url: any.type_url,
+            value: any.value,
+        })
+    }
+}
+
+/// The [`ErrorDetail`]s attached to a gRPC [`Status`].
+///
+/// Statuses returned by the QCS gRPC clients can be inspected with [`ErrorDetails::from_status`],
+/// e.g. to show the [`FieldViolation`]s of an invalid `TranslateQuilToEncryptedControllerJob`
+/// request. A status without (decodable) details has no [`ErrorDetail`]s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ErrorDetails {
+    details: Vec<ErrorDetail>,
+}
+
+impl ErrorDetails {
+    /// Decode the details attached to the given [`Status`].
+    #[must_use]
+    pub fn from_status(status: &Status) -> Self {
+        if status.details().is_empty() {
+            return Self::default();
+        }
+        match RpcStatus::decode(status.details()) {
+            Ok(rpc_status) => Self {
+                details: rpc_status
+                    .details
+                    .into_iter()
+                    .map(ErrorDetail::decode)
+                    .collect(),
+            },
+            #[allow(unused_variables)]
+            Err(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("ignoring undecodable gRPC status details: {error}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Return `true` if there are no details.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.details.is_empty()
+    }
+
+    /// Iterate over all details.
+    pub fn iter(&self) -> std::slice::Iter<'_, ErrorDetail> {
+        self.details.iter()
+    }
+
+    /// The first [`BadRequest`] detail, if any.
+    #[must_use]
+    pub fn bad_request(&self) -> Option<&BadRequest> {
+        self.iter().find_map(|detail| match detail {
+            ErrorDetail::BadRequest(bad_request) => Some(bad_request),
+            _ => None,
+        })
+    }
+
+    /// The [`FieldViolation`]s of all [`BadRequest`] details.
+    pub fn field_violations(&self) -> impl Iterator<Item = &FieldViolation> {
+        self.iter()
+            .filter_map(|detail| match detail {
+                ErrorDetail::BadRequest(bad_request) => Some(&bad_request.field_violations),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// The first [`ErrorInfo`] detail, if any.
+    #[must_use]
+    pub fn error_info(&self) -> Option<&ErrorInfo> {
+        self.iter().find_map(|detail| match detail {
+            ErrorDetail::ErrorInfo(error_info) => Some(error_info),
+            _ => None,
+        })
+    }
+
+    /// The first [`QuotaFailure`] detail, if any.
+    #[must_use]
+    pub fn quota_failure(&self) -> Option<&QuotaFailure> {
+        self.iter().find_map(|detail| match detail {
+            ErrorDetail::QuotaFailure(quota_failure) => Some(quota_failure),
+            _ => None,
+        })
+    }
+
+    /// The delay of the first [`RetryInfo`] detail which gives a valid delay, if any.
+    #[must_use]
+    pub fn retry_delay(&self) -> Option<Duration> {
+        self.iter().find_map(|detail| match detail {
+            ErrorDetail::RetryInfo(retry_info) => retry_info.delay(),
+            _ => None,
+        })
+    }
+}
+
+impl From<&Status> for ErrorDetails {
+    fn from(status: &Status) -> Self {
+        Self::from_status(status)
+    }
+}
+
+impl IntoIterator for ErrorDetails {
+    type Item = ErrorDetail;
+    type IntoIter = std::vec::IntoIter<ErrorDetail>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.details.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ErrorDetails {
+    type Item = &'a ErrorDetail;
+    type IntoIter = std::slice::Iter<'a, ErrorDetail>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Extends [`Status`] with access to the details a QCS server attached to it.
+///
+/// ```no_run
+/// # use qcs_api_client_grpc::tonic::StatusExt;
+/// # fn report(status: &qcs_dependencies_client::tonic::Status) {
+/// for violation in status.error_details().field_violations() {
+///     eprintln!("{}: {}", violation.field, violation.description);
+/// }
+/// # }
+/// ```
+pub trait StatusExt {
+    /// Decode the [`ErrorDetails`] attached to the status; see [`ErrorDetails::from_status`].
+    fn error_details(&self) -> ErrorDetails;
+
+    /// Classify the status, e.g. to decide whether to retry the request or what to tell the
+    /// user; see [`ClassifiedStatus`].
+    fn classify(&self) -> ClassifiedStatus<'_>;
+}
+
+impl StatusExt for Status {
+    fn error_details(&self) -> ErrorDetails {
+        ErrorDetails::from_status(self)
+    }
+
+    fn classify(&self) -> ClassifiedStatus<'_> {
+        ClassifiedStatus(self)
+    }
+}
+
+/// Helpers for testing how clients handle statuses with details.
+#[cfg(test)]
+pub(crate) mod testing {
+    use qcs_dependencies_client::pbjson_types::Any;
+    use qcs_dependencies_client::prost::Message;
+    use qcs_dependencies_client::tonic::{Code, Request, Response, Status};
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, HealthCheckResponse, health_server::Health,
+    };
+    use qcs_dependencies_client::tonic_health::server::HealthService;
+
+    use super::RpcStatus;
+
+    /// Create a [`Status`] carrying the given details, as a server would.
+    pub(crate) fn status_with_details(
+        code: Code,
+        message: &str,
+        details: Vec<(&str, Vec<u8>)>,
+    ) -> Status {
+        let rpc_status = RpcStatus {
+            code: code as i32,
+            message: message.to_string(),
+            details: details
+                .into_iter()
+                .map(|(type_name, value)| Any {
+                    type_url: format!("type.googleapis.com/{type_name}"),
+                    value: value.into(),
+                })
+                .collect(),
+        };
+        Status::with_details(code, message, rpc_status.encode_to_vec().into())
+    }
+
+    /// A health service which rejects every request with the given status.
+    pub(crate) struct FailingHealthService(pub(crate) Status);
+
+    #[qcs_dependencies_client::tonic::async_trait]
+    impl Health for FailingHealthService {
+        type WatchStream = <HealthService as Health>::WatchStream;
+
+        async fn check(
+            &self,
+            _request: Request<HealthCheckRequest>,
+        ) -> Result<Response<HealthCheckResponse>, Status> {
+            Err(self.0.clone())
+        }
+
+        async fn watch(
+            &self,
+            _request: Request<HealthCheckRequest>,
+        ) -> Result<Response<Self::WatchStream>, Status> {
+            Err(self.0.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use qcs_dependencies_client::tonic::Code;
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, health_client::HealthClient, health_server::HealthServer,
+    };
+
+    use super::testing::{FailingHealthService, status_with_details};
+    use super::*;
+    use crate::tonic::uds_grpc_stream;
+
+    #[test]
+    fn test_decodes_error_details() {
+        let bad_request = BadRequest {
+            field_violations: vec![FieldViolation {
+                field: "quil_program".to_string(),
+                description: "unknown gate FOO".to_string(),
+            }],
+        };
+        let retry_info = RetryInfo {
+            retry_delay: Some(pbjson_types::Duration {
+                seconds: 1,
+                nanos: 500_000_000,
+            }),
+        };
+        let status = status_with_details(
+            Code::InvalidArgument,
+            "invalid program",
+            vec![
+                (TYPE_BAD_REQUEST, bad_request.encode_to_vec()),
+                (TYPE_RETRY_INFO, retry_info.encode_to_vec()),
+                ("google.rpc.DebugInfo", vec![]),
+            ],
+        );
+
+        let details = ErrorDetails::from_status(&status);
+        assert_eq!(details.iter().count(), 3);
+        assert_eq!(details.bad_request(), Some(&bad_request));
+        assert_eq!(
+            details.field_violations().collect::<Vec<_>>(),
+            vec![&bad_request.field_violations[0]]
+        );
+        assert_eq!(details.retry_delay(), Some(Duration::from_millis(1500)));
+        assert!(details.error_info().is_none());
+        assert!(matches!(
+            details.iter().last(),
+            Some(ErrorDetail::Unknown { type_url, .. })
+                if type_url == "type.googleapis.com/google.rpc.DebugInfo"
+        ));
+    }
+
+    #[test]
+    fn test_status_without_details() {
+        let status = Status::invalid_argument("invalid program");
+        assert!(ErrorDetails::from_status(&status).is_empty());
+
+        let status = Status::with_details(Code::Internal, "garbage", Bytes::from_static(b"\xff"));
+        assert!(ErrorDetails::from_status(&status).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_error_details_from_server_response() {
+        let bad_request = BadRequest {
+            field_violations: vec![FieldViolation {
+                field: "quil_program".to_string(),
+                description: "unknown gate FOO".to_string(),
+            }],
+        };
+        let status = status_with_details(
+            Code::InvalidArgument,
+            "invalid program",
+            vec![(TYPE_BAD_REQUEST, bad_request.encode_to_vec())],
+        );
+        let health_server = HealthServer::new(FailingHealthService(status));
+
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let status = HealthClient::new(channel)
+                .check(HealthCheckRequest::default())
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), Code::InvalidArgument);
+            assert_eq!(status.error_details().bad_request(), Some(&bad_request));
+        })
+        .await
+        .unwrap();
+    }
+}
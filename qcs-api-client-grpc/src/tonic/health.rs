@@ -0,0 +1,325 @@
+//! Waiting for a gRPC server to be ready, using the standard
+//! [health checking protocol](https://github.com/grpc/grpc/blob/master/doc/health-checking.md).
+
+use std::{fmt, time::Duration};
+
+use qcs_dependencies_client::tonic::{Code, Status, transport::Channel};
+use qcs_dependencies_client::tonic_health::pb::{
+    HealthCheckRequest, health_check_response::ServingStatus, health_client::HealthClient,
+};
+use tokio::sync::watch;
+
+/// How long a [`HealthWatcher`] waits before watching again after the server could not be reached
+/// or ended the watch.
+const WATCH_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Errors that may occur while waiting for a gRPC service to be serving.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum HealthError {
+    /// The server does not implement the health service, so it cannot report when it is ready.
+    #[error("The gRPC server does not implement the health service: {0}")]
+    Unimplemented(#[source] Status),
+    /// The service did not report itself as serving in time.
+    #[error(
+        "The gRPC service {service:?} was not serving within {timeout:?}; last observed: {last_state}"
+    )]
+    Timeout {
+        /// The name of the service that was waited for.
+        service: String,
+        /// How long the service was waited for.
+        timeout: Duration,
+        /// The last state observed before the timeout.
+        last_state: HealthState,
+    },
+}
+
+/// The health of a gRPC service as last observed by a [`HealthWatcher`].
+#[derive(Clone, Debug, Default)]
+pub enum HealthState {
+    /// The server has not responded yet.
+    #[default]
+    Unknown,
+    /// The server reported the given status for the service.
+    Status(ServingStatus),
+    /// The health of the service could not be watched, e.g. because the server is unreachable or
+    /// does not know the service yet.
+    Error(Status),
+}
+
+impl HealthState {
+    /// Return `true` if the server reported the service as serving.
+    #[must_use]
+    pub fn is_serving(&self) -> bool {
+        matches!(self, Self::Status(ServingStatus::Serving))
+    }
+
+    /// Return `true` if the server does not implement the health service.
+    fn is_unimplemented(&self) -> bool {
+        matches!(self, Self::Error(status) if status.code() == Code::Unimplemented)
+    }
+}
+
+impl fmt::Display for HealthState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "no response"),
+            Self::Status(status) => write!(f, "status {}", status.as_str_name()),
+            Self::Error(status) => write!(f, "error: {status}"),
+        }
+    }
+}
+
+/// Watches the health of a gRPC service in the background.
+///
+/// The watch is kept open with the server's `Watch` method, and re-established whenever the server
+/// cannot be reached or ends it, so that the watcher follows the service across server restarts.
+/// The watch stops once the watcher and all of its clones are dropped.
+#[derive(Clone, Debug)]
+pub struct HealthWatcher {
+    service: String,
+    state: watch::Receiver<HealthState>,
+}
+
+impl HealthWatcher {
+    /// Start watching the health of the given service over `channel`. Use an empty service name
+    /// for the health of the server as a whole.
+    ///
+    /// This must be called from within a Tokio runtime, which runs the watch until the watcher and
+    /// all of its clones are dropped.
+    #[must_use]
+    pub fn spawn(channel: Channel, service: impl Into<String>) -> Self {
+        let service = service.into();
+        let (sender, state) = watch::channel(HealthState::Unknown);
+        tokio::spawn(watch_health(channel, service.clone(), sender));
+        Self { service, state }
+    }
+
+    /// The name of the watched service.
+    #[must_use]
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    /// The last observed health of the service.
+    #[must_use]
+    pub fn state(&self) -> HealthState {
+        self.state.borrow().clone()
+    }
+
+    /// Return `true` if the service was last observed to be serving.
+    #[must_use]
+    pub fn is_serving(&self) -> bool {
+        self.state.borrow().is_serving()
+    }
+
+    /// Wait until the service is serving, for at most `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HealthError`] if the server does not implement the health service, or if the
+    /// service is not serving within `timeout`.
+    pub async fn wait_until_serving(&self, timeout: Duration) -> Result<(), HealthError> {
+        let mut state = self.state.clone();
+        let settled = tokio::time::timeout(
+            timeout,
+            state.wait_for(|state| state.is_serving() || state.is_unimplemented()),
+        )
+        .await
+        .map(|result| result.map(|state| (*state).clone()));
+        match settled {
+            Ok(Ok(state)) if state.is_serving() => Ok(()),
+            Ok(Ok(HealthState::Error(status))) => Err(HealthError::Unimplemented(status)),
+            _ => Err(HealthError::Timeout {
+                service: self.service.clone(),
+                timeout,
+                last_state: self.state(),
+            }),
+        }
+    }
+}
+
+/// Wait until the server behind `channel` reports the given service as serving, for at most
+/// `timeout`. Use an empty service name for the server as a whole.
+///
+/// This is useful after (re)starting a server, to get a [`HealthError`] describing why it is not
+/// ready rather than a transport error on the first request.
+///
+/// # Errors
+///
+/// See [`HealthWatcher::wait_until_serving`].
+pub async fn wait_until_serving(
+    channel: Channel,
+    service: impl Into<String>,
+    timeout: Duration,
+) -> Result<(), HealthError> {
+    HealthWatcher::spawn(channel, service)
+        .wait_until_serving(timeout)
+        .await
+}
+
+/// Whether a failed health check was answered by the server itself, rather than failing because
+/// the server could not be reached in time. A server which rejects the check, e.g. because it
+/// does not implement the health service or requires authentication, is up.
+pub(super) fn server_responded(status: &Status) -> bool {
+    !matches!(
+        status.code(),
+        Code::Unavailable | Code::Cancelled | Code::DeadlineExceeded
+    )
+}
+
+/// Watch the health of `service`, publishing each observation to `sender` until every receiver is
+/// dropped.
+async fn watch_health(channel: Channel, service: String, sender: watch::Sender<HealthState>) {
+    let mut client = HealthClient::new(channel);
+    loop {
+        tokio::select! {
+            () = sender.closed() => return,
+            () = watch_once(&mut client, &service, &sender) => {},
+        }
+    }
+}
+
+/// Watch the health of `service` until the watch fails or ends, then wait before it is retried.
+async fn watch_once(
+    client: &mut HealthClient<Channel>,
+    service: &str,
+    sender: &watch::Sender<HealthState>,
+) {
+    let request = HealthCheckRequest {
+        service: service.to_string(),
+    };
+    match client.watch(request).await {
+        Ok(response) => {
+            let mut stream = response.into_inner();
+            loop {
+                match stream.message().await {
+                    Ok(Some(response)) => {
+                        sender.send_replace(HealthState::Status(response.status()));
+                    }
+                    Ok(None) => break,
+                    Err(status) => {
+                        sender.send_replace(HealthState::Error(status));
+                        break;
+                    }
+                }
+            }
+        }
+        Err(status) => {
+            sender.send_replace(HealthState::Error(status));
+        }
+    }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        "gRPC health watch for service {service:?} ended with {}; retrying",
+        *sender.borrow()
+    );
+    tokio::time::sleep(WATCH_RETRY_INTERVAL).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use qcs_dependencies_client::tonic::{Request, Response};
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckResponse,
+        health_server::{Health, HealthServer},
+    };
+    use qcs_dependencies_client::tonic_health::server::{HealthService, health_reporter};
+
+    use super::*;
+    use crate::tonic::uds_grpc_stream;
+
+    /// A health service whose methods are all unimplemented.
+    struct UnimplementedHealthService;
+
+    #[qcs_dependencies_client::tonic::async_trait]
+    impl Health for UnimplementedHealthService {
+        type WatchStream = <HealthService as Health>::WatchStream;
+
+        async fn check(
+            &self,
+            _request: Request<HealthCheckRequest>,
+        ) -> Result<Response<HealthCheckResponse>, Status> {
+            Err(Status::unimplemented("check"))
+        }
+
+        async fn watch(
+            &self,
+            _request: Request<HealthCheckRequest>,
+        ) -> Result<Response<Self::WatchStream>, Status> {
+            Err(Status::unimplemented("watch"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_waits_until_serving() {
+        let (reporter, health_server) = health_reporter();
+        reporter
+            .set_service_status(
+                "qcs.Controller",
+                qcs_dependencies_client::tonic_health::ServingStatus::NotServing,
+            )
+            .await;
+
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let watcher = HealthWatcher::spawn(channel, "qcs.Controller");
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                reporter
+                    .set_service_status(
+                        "qcs.Controller",
+                        qcs_dependencies_client::tonic_health::ServingStatus::Serving,
+                    )
+                    .await;
+            });
+
+            watcher
+                .wait_until_serving(Duration::from_secs(5))
+                .await
+                .unwrap();
+            assert!(watcher.is_serving());
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_times_out_when_not_serving() {
+        let (reporter, health_server) = health_reporter();
+        reporter
+            .set_service_status(
+                "",
+                qcs_dependencies_client::tonic_health::ServingStatus::NotServing,
+            )
+            .await;
+
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let error = wait_until_serving(channel, "", Duration::from_millis(200))
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                error,
+                HealthError::Timeout {
+                    last_state: HealthState::Status(ServingStatus::NotServing),
+                    ..
+                }
+            ));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fails_fast_when_unimplemented() {
+        let health_server = HealthServer::new(UnimplementedHealthService);
+
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let error = wait_until_serving(channel, "", Duration::from_secs(60))
+                .await
+                .unwrap_err();
+            assert!(matches!(error, HealthError::Unimplemented(_)));
+        })
+        .await
+        .unwrap();
+    }
+}
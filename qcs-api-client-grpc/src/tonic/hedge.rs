@@ -0,0 +1,448 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::{Future, poll_fn},
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use qcs_api_client_common::configuration::TokenError;
+use qcs_dependencies_client::http::{HeaderMap, HeaderValue, Request, Response};
+use qcs_dependencies_client::tonic::{Code, client::GrpcService};
+use qcs_dependencies_client::tower::Layer;
+use tokio::time::Instant;
+
+use super::Body;
+use super::replay::{DEFAULT_REPLAY_BUFFER_LIMIT, ReplayableRequest};
+use super::retry::{KEY_X_REQUEST_ID, KEY_X_REQUEST_RETRY_INDEX, RetryIndex, new_request_id};
+
+/// The number of recent latencies kept per method to compute a [`HedgeDelay::Percentile`].
+const LATENCY_WINDOW: usize = 100;
+
+/// The number of latencies a method must have recorded before requests to it are hedged with a
+/// [`HedgeDelay::Percentile`].
+const MIN_LATENCY_SAMPLES: usize = 10;
+
+/// How long a [`HedgeService`] waits for a response before sending a second copy of a request.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum HedgeDelay {
+    /// Send the second copy after a fixed delay.
+    Fixed(Duration),
+    /// Send the second copy once the request has taken longer than the given percentile (between
+    /// `0.0` and `1.0`, e.g. `0.95`) of recent requests to the same method. Requests are not
+    /// hedged until enough latencies have been recorded.
+    Percentile(f64),
+}
+
+/// The [`Layer`] used to hedge requests to idempotent, read-only gRPC methods.
+///
+/// A hedged request is sent as usual, and if no response has arrived after the [`HedgeDelay`], a
+/// second copy is sent. The first successful response is used, and the other attempt is
+/// cancelled. If the first attempt to finish fails, the other attempt's result is used instead.
+///
+/// Only requests to methods added with [`HedgeLayer::with_method`] are hedged; all other requests
+/// are passed through untouched. Only hedge methods which are safe to execute more than once.
+///
+/// A second copy is only sent once the first has sent its whole request body (and the body fits
+/// in the replay buffer), so client-streaming calls are generally not hedged.
+///
+/// Both copies carry the same `x-request-id` header, and the second copy takes the next unused
+/// `x-request-retry-index` of the request. When combined with a [`RetryLayer`](super::RetryLayer),
+/// apply the hedging layer first so that each retry is hedged and shares the retry's request ID;
+/// the indices of the retries and the second copies then never collide.
+#[derive(Debug, Clone)]
+pub struct HedgeLayer {
+    delay: HedgeDelay,
+    methods: Arc<HashSet<String>>,
+    replay_buffer_limit: usize,
+    latencies: Arc<Mutex<HashMap<String, VecDeque<Duration>>>>,
+}
+
+impl HedgeLayer {
+    /// Create a [`HedgeLayer`] which hedges requests after the given delay. No methods are hedged
+    /// until added with [`HedgeLayer::with_method`].
+    #[must_use]
+    pub fn new(delay: HedgeDelay) -> Self {
+        Self {
+            delay,
+            methods: Arc::default(),
+            replay_buffer_limit: DEFAULT_REPLAY_BUFFER_LIMIT,
+            latencies: Arc::default(),
+        }
+    }
+
+    /// Hedge requests to the gRPC method at `path`, e.g.
+    /// `/services.controller.Controller/GetControllerJobStatus`.
+    #[must_use]
+    pub fn with_method(mut self, path: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.methods).insert(path.into());
+        self
+    }
+
+    /// Set the maximum number of request body bytes retained in order to send a second copy of a
+    /// request. Requests with larger bodies are not hedged.
+    #[must_use]
+    pub const fn with_replay_buffer_limit(mut self, limit: usize) -> Self {
+        self.replay_buffer_limit = limit;
+        self
+    }
+}
+
+impl<S: GrpcService<Body>> Layer<S> for HedgeLayer {
+    type Service = HedgeService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        HedgeService {
+            layer: self.clone(),
+            service,
+        }
+    }
+}
+
+/// The [`GrpcService`] that sends a second copy of slow requests to idempotent methods.
+///
+/// See also: [`HedgeLayer`].
+#[derive(Clone, Debug)]
+pub struct HedgeService<S: GrpcService<Body>> {
+    layer: HedgeLayer,
+    service: S,
+}
+
+impl HedgeLayer {
+    /// The delay before hedging a request to `path`, or `None` if it should not be hedged yet.
+    fn delay_for(&self, path: &str) -> Option<Duration> {
+        match self.delay {
+            HedgeDelay::Fixed(delay) => Some(delay),
+            HedgeDelay::Percentile(percentile) => {
+                let latencies = self
+                    .latencies
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let latencies = latencies.get(path)?;
+                if latencies.len() < MIN_LATENCY_SAMPLES {
+                    return None;
+                }
+                let mut sorted = latencies.iter().copied().collect::<Vec<_>>();
+                sorted.sort_unstable();
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_precision_loss,
+                    clippy::cast_sign_loss
+                )]
+                let rank = (percentile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+                sorted.get(rank.saturating_sub(1)).copied()
+            }
+        }
+    }
+
+    fn record_latency(&self, path: &str, latency: Duration) {
+        if !matches!(self.delay, HedgeDelay::Percentile(_)) {
+            return;
+        }
+        let mut latencies = self
+            .latencies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let latencies = latencies.entry(path.to_string()).or_default();
+        if latencies.len() == LATENCY_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+}
+
+impl<S> GrpcService<Body> for HedgeService<S>
+where
+    S: GrpcService<Body> + Send + Clone + 'static,
+    S::Future: Send,
+    S::ResponseBody: Send,
+    S::Error: Send,
+    super::error::Error<TokenError>: From<S::Error>,
+{
+    type ResponseBody = <S as GrpcService<Body>>::ResponseBody;
+    type Error = super::error::Error<TokenError>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Response<Self::ResponseBody>, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service
+            .poll_ready(cx)
+            .map_err(super::error::Error::from)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let service = self.service.clone();
+        // It is necessary to replace self.service with the above clone
+        // because the cloned version may not be "ready".
+        //
+        // See this github issue for more context:
+        // https://github.com/tower-rs/tower/issues/547
+        let mut service = std::mem::replace(&mut self.service, service);
+
+        let path = req.uri().path().to_string();
+        if !self.layer.methods.contains(&path) {
+            let future = service.call(req);
+            return Box::pin(async move { future.await.map_err(super::error::Error::from) });
+        }
+
+        if !req.headers().contains_key(KEY_X_REQUEST_ID) {
+            if let Ok(request_id) = new_request_id() {
+                req.headers_mut().insert(KEY_X_REQUEST_ID, request_id);
+            }
+        }
+        let retry_index = if let Some(retry_index) = req.extensions().get::<RetryIndex>() {
+            retry_index.clone()
+        } else {
+            // Without a retry layer, the request is only ever sent by this service.
+            let first_index = req
+                .headers()
+                .get(KEY_X_REQUEST_RETRY_INDEX)
+                .and_then(|value| value.to_str().ok()?.parse::<u64>().ok())
+                .unwrap_or_default();
+            req.headers_mut()
+                .insert(KEY_X_REQUEST_RETRY_INDEX, HeaderValue::from(first_index));
+            let retry_index = RetryIndex::starting_at(first_index + 1);
+            req.extensions_mut().insert(retry_index.clone());
+            retry_index
+        };
+
+        let layer = self.layer.clone();
+        let mut hedge_service = self.service.clone();
+        super::common::pin_future_with_otel_context_if_available(async move {
+            let start = Instant::now();
+            let delay = layer.delay_for(&path);
+            let (replayable, req) = ReplayableRequest::new(req, layer.replay_buffer_limit);
+            let primary = service.call(req);
+            tokio::pin!(primary);
+
+            let hedge = async {
+                let delay = delay?;
+                tokio::select! {
+                    result = &mut primary => return Some(result),
+                    () = tokio::time::sleep(delay) => {},
+                }
+                let mut request = replayable.buffered_request()?;
+                request.headers_mut().insert(
+                    KEY_X_REQUEST_RETRY_INDEX,
+                    HeaderValue::from(retry_index.next()),
+                );
+                // Ensure that the service is ready before trying to use it.
+                // Failure to do this *will* cause a panic.
+                poll_fn(|cx| hedge_service.poll_ready(cx)).await.ok()?;
+                let hedged = hedge_service.call(request);
+                tokio::pin!(hedged);
+
+                // Take the first success, dropping (and so cancelling) the other attempt.
+                let (result, other) = tokio::select! {
+                    result = &mut primary => (result, Either::Hedged(hedged)),
+                    result = &mut hedged => (result, Either::Primary(&mut primary)),
+                };
+                if is_success(&result) {
+                    return Some(result);
+                }
+                Some(match other {
+                    Either::Primary(primary) => primary.await,
+                    Either::Hedged(hedged) => hedged.await,
+                })
+            };
+            let result = match hedge.await {
+                Some(result) => result,
+                None => primary.await,
+            };
+            if is_success(&result) {
+                layer.record_latency(&path, start.elapsed());
+            }
+            result.map_err(super::error::Error::from)
+        })
+    }
+}
+
+/// The attempt that is still in flight once the other has finished.
+enum Either<P, H> {
+    Primary(P),
+    Hedged(H),
+}
+
+/// Whether the attempt succeeded, i.e. it did not fail to send and the server did not respond with
+/// an error status before any response message.
+fn is_success<B, E>(result: &Result<Response<B>, E>) -> bool {
+    result.as_ref().is_ok_and(|response| {
+        response.status().is_success()
+            && grpc_code(response.headers()).is_none_or(|code| code == Code::Ok)
+    })
+}
+
+fn grpc_code(headers: &HeaderMap) -> Option<Code> {
+    super::common::get_status_code_from_headers(headers).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use qcs_dependencies_client::tonic::server::NamedService;
+    use qcs_dependencies_client::tonic::{Request, Status};
+    use qcs_dependencies_client::tonic_health::pb::health_check_response::ServingStatus;
+    use qcs_dependencies_client::tonic_health::pb::health_server::{Health, HealthServer};
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, HealthCheckResponse, health_client::HealthClient,
+    };
+    use qcs_dependencies_client::tonic_health::server::HealthService;
+
+    use qcs_dependencies_client::tonic::transport::Channel;
+    use qcs_dependencies_client::tower::ServiceBuilder;
+
+    use super::*;
+    use crate::tonic::{RetryLayer, uds_grpc_stream};
+
+    static HEALTH_CHECK_PATH: &str = "/grpc.health.v1.Health/Check";
+
+    /// A health service whose first check is slow, recording the request headers of each check.
+    /// The first `failures` checks fail as unavailable.
+    #[derive(Clone, Default)]
+    struct SlowHealthService {
+        calls: Arc<AtomicUsize>,
+        failures: usize,
+        headers: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    impl SlowHealthService {
+        fn header(request: &Request<HealthCheckRequest>, key: &str) -> String {
+            request
+                .metadata()
+                .get(key)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        }
+    }
+
+    #[qcs_dependencies_client::tonic::async_trait]
+    impl Health for SlowHealthService {
+        type WatchStream =
+            tokio_stream::wrappers::ReceiverStream<Result<HealthCheckResponse, Status>>;
+
+        async fn check(
+            &self,
+            request: Request<HealthCheckRequest>,
+        ) -> Result<qcs_dependencies_client::tonic::Response<HealthCheckResponse>, Status> {
+            self.headers.lock().unwrap().push((
+                SlowHealthService::header(&request, KEY_X_REQUEST_ID),
+                SlowHealthService::header(&request, KEY_X_REQUEST_RETRY_INDEX),
+            ));
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call == 0 {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            if call < self.failures {
+                return Err(Status::unavailable("try again"));
+            }
+            Ok(qcs_dependencies_client::tonic::Response::new(
+                HealthCheckResponse {
+                    status: ServingStatus::Serving as i32,
+                },
+            ))
+        }
+
+        async fn watch(
+            &self,
+            _request: Request<HealthCheckRequest>,
+        ) -> Result<qcs_dependencies_client::tonic::Response<Self::WatchStream>, Status> {
+            Err(Status::unimplemented("watch"))
+        }
+    }
+
+    async fn check_health<L>(service: SlowHealthService, layer: L) -> Duration
+    where
+        L: Layer<Channel> + Send,
+        L::Service: GrpcService<Body> + Send,
+        <L::Service as GrpcService<Body>>::Future: Send,
+        <L::Service as GrpcService<Body>>::ResponseBody:
+            http_body::Body<Data = qcs_dependencies_client::tonic::codegen::Bytes> + Send + 'static,
+        <<L::Service as GrpcService<Body>>::ResponseBody as http_body::Body>::Error:
+            Into<Box<dyn std::error::Error + Send + Sync>> + Send,
+    {
+        let start = Instant::now();
+        uds_grpc_stream::serve(HealthServer::new(service), |channel| async move {
+            let response = HealthClient::new(layer.layer(channel))
+                .check(Request::new(HealthCheckRequest {
+                    service: <HealthServer<HealthService> as NamedService>::NAME.to_string(),
+                }))
+                .await
+                .unwrap();
+            assert_eq!(response.into_inner().status(), ServingStatus::Serving);
+        })
+        .await
+        .unwrap();
+        start.elapsed()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_hedges_slow_request() {
+        let service = SlowHealthService::default();
+        let layer = HedgeLayer::new(HedgeDelay::Fixed(Duration::from_millis(50)))
+            .with_method(HEALTH_CHECK_PATH);
+
+        let elapsed = check_health(service.clone(), layer).await;
+
+        assert!(elapsed < Duration::from_millis(500));
+        let headers = service.headers.lock().unwrap().clone();
+        assert_eq!(headers.len(), 2);
+        assert!(!headers[0].0.is_empty());
+        assert_eq!(headers[0].0, headers[1].0);
+        assert_eq!(headers[0].1, "0");
+        assert_eq!(headers[1].1, "1");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_hedges_and_retries_use_distinct_indices() {
+        let service = SlowHealthService {
+            failures: 2,
+            ..SlowHealthService::default()
+        };
+        let layer = ServiceBuilder::new().layer(RetryLayer::default()).layer(
+            HedgeLayer::new(HedgeDelay::Fixed(Duration::from_millis(50)))
+                .with_method(HEALTH_CHECK_PATH),
+        );
+
+        check_health(service.clone(), layer).await;
+
+        let headers = service.headers.lock().unwrap().clone();
+        let indices = headers
+            .iter()
+            .map(|(_, index)| index.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(indices, vec!["0", "1", "2"]);
+        assert!(headers.iter().all(|(id, _)| *id == headers[0].0));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_does_not_hedge_other_methods() {
+        let service = SlowHealthService::default();
+        let layer = HedgeLayer::new(HedgeDelay::Fixed(Duration::from_millis(50)))
+            .with_method("/grpc.health.v1.Health/Watch");
+
+        let elapsed = check_health(service.clone(), layer).await;
+
+        assert!(elapsed >= Duration::from_millis(500));
+        assert_eq!(service.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_percentile_delay() {
+        let layer = HedgeLayer::new(HedgeDelay::Percentile(0.9));
+        for millis in 1..MIN_LATENCY_SAMPLES as u64 {
+            layer.record_latency(HEALTH_CHECK_PATH, Duration::from_millis(millis));
+        }
+        assert_eq!(layer.delay_for(HEALTH_CHECK_PATH), None);
+
+        layer.record_latency(HEALTH_CHECK_PATH, Duration::from_millis(10));
+        assert_eq!(
+            layer.delay_for(HEALTH_CHECK_PATH),
+            Some(Duration::from_millis(9))
+        );
+        assert_eq!(layer.delay_for("/other.Service/Method"), None);
+    }
+}
@@ -0,0 +1,333 @@
+//! Request metrics for gRPC clients.
+//!
+//! See [`qcs_api_client_common::metrics`] for the metrics that are recorded.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, ready},
+    time::Instant,
+};
+
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use qcs_api_client_common::metrics::{self, Client, STATUS_ERROR};
+use qcs_dependencies_client::http::{HeaderMap, Request, Response};
+use qcs_dependencies_client::prost::bytes::{Buf, Bytes};
+use qcs_dependencies_client::tonic::{Code, client::GrpcService};
+use qcs_dependencies_client::tower::Layer;
+
+use super::{Body, common::get_status_code_from_headers};
+
+/// The [`Layer`] used to record latency, status, and body size metrics for each request.
+///
+/// See also: [`MetricsService`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricsLayer;
+
+impl<S: GrpcService<Body>> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        MetricsService { service }
+    }
+}
+
+/// The [`GrpcService`] that records metrics for each request made through the wrapped service.
+///
+/// A request is considered complete when its response trailers are received, or when the response
+/// body is dropped. The request duration therefore includes the time taken to receive the
+/// response body. When wrapping a [`super::RetryService`], each request includes all of its
+/// retries.
+///
+/// See also: [`MetricsLayer`].
+#[derive(Clone, Debug)]
+pub struct MetricsService<S> {
+    service: S,
+}
+
+impl<S> GrpcService<Body> for MetricsService<S>
+where
+    S: GrpcService<Body>,
+    S::Future: Send + 'static,
+{
+    type ResponseBody = MeteredBody<S::ResponseBody>;
+    type Error = S::Error;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Response<Self::ResponseBody>, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let operation = req.uri().path().to_string();
+        let start = Instant::now();
+
+        let req = req.map(|body| {
+            Body::new(MeteredRequestBody {
+                inner: body,
+                operation: operation.clone(),
+                bytes: 0,
+            })
+        });
+        let future = self.service.call(req);
+
+        super::common::pin_future_with_otel_context_if_available(async move {
+            match future.await {
+                Ok(response) => {
+                    let status = status_from_headers(response.status(), response.headers());
+                    Ok(response.map(|body| MeteredBody {
+                        inner: Box::pin(body),
+                        recorder: Some(ResponseRecorder {
+                            operation,
+                            start,
+                            status,
+                            bytes: 0,
+                        }),
+                    }))
+                }
+                Err(error) => {
+                    metrics::record_request(Client::Grpc, operation, STATUS_ERROR, start.elapsed());
+                    Err(error)
+                }
+            }
+        })
+    }
+}
+
+/// The `status` label for a response, if it can be determined from the response headers alone.
+///
+/// For most responses, the gRPC status is only known once the trailers are received.
+fn status_from_headers(
+    status: qcs_dependencies_client::http::StatusCode,
+    headers: &HeaderMap,
+) -> Option<String> {
+    get_status_code_from_headers(headers).map_or_else(
+        |_| (!status.is_success()).then(|| status.as_str().to_string()),
+        |code| Some(code_label(code)),
+    )
+}
+
+fn code_label(code: Code) -> String {
+    format!("{code:?}")
+}
+
+/// Records the bytes sent in a request body once it is dropped.
+struct MeteredRequestBody {
+    inner: Body,
+    operation: String,
+    bytes: u64,
+}
+
+impl HttpBody for MeteredRequestBody {
+    type Data = Bytes;
+    type Error = qcs_dependencies_client::tonic::Status;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let frame = ready!(Pin::new(&mut self.inner).poll_frame(cx));
+        if let Some(Ok(frame)) = &frame {
+            if let Some(data) = frame.data_ref() {
+                self.bytes += data.len() as u64;
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for MeteredRequestBody {
+    fn drop(&mut self) {
+        metrics::record_bytes_sent(
+            Client::Grpc,
+            std::mem::take(&mut self.operation),
+            self.bytes,
+        );
+    }
+}
+
+/// The state needed to record a request once its response completes.
+struct ResponseRecorder {
+    operation: String,
+    start: Instant,
+    status: Option<String>,
+    bytes: u64,
+}
+
+impl ResponseRecorder {
+    fn record(self, status: Option<String>) {
+        let status = status
+            .or(self.status)
+            .unwrap_or_else(|| code_label(Code::Unknown));
+        metrics::record_request(
+            Client::Grpc,
+            self.operation.clone(),
+            status,
+            self.start.elapsed(),
+        );
+        metrics::record_bytes_received(Client::Grpc, self.operation, self.bytes);
+    }
+}
+
+/// A response body that records request metrics when the response completes.
+///
+/// See [`MetricsService`].
+pub struct MeteredBody<B> {
+    inner: Pin<Box<B>>,
+    recorder: Option<ResponseRecorder>,
+}
+
+impl<B> std::fmt::Debug for MeteredBody<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MeteredBody").finish_non_exhaustive()
+    }
+}
+
+impl<B: HttpBody> HttpBody for MeteredBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let frame = ready!(self.inner.as_mut().poll_frame(cx));
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(data) = frame.data_ref() {
+                    if let Some(recorder) = &mut self.recorder {
+                        recorder.bytes += data.remaining() as u64;
+                    }
+                } else if let Some(trailers) = frame.trailers_ref() {
+                    let status = get_status_code_from_headers(trailers).ok().map(code_label);
+                    if let Some(recorder) = self.recorder.take() {
+                        recorder.record(status);
+                    }
+                }
+            }
+            Some(Err(_)) => {
+                if let Some(recorder) = self.recorder.take() {
+                    recorder.record(Some(STATUS_ERROR.to_string()));
+                }
+            }
+            None => {
+                if let Some(recorder) = self.recorder.take() {
+                    recorder.record(None);
+                }
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> Drop for MeteredBody<B> {
+    fn drop(&mut self) {
+        // The caller stopped reading the response before it completed.
+        if let Some(recorder) = self.recorder.take() {
+            let status = recorder
+                .status
+                .clone()
+                .unwrap_or_else(|| code_label(Code::Cancelled));
+            recorder.record(Some(status));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use qcs_api_client_common::metrics::{REQUESTS, RETRIES};
+    use qcs_dependencies_client::tonic::Request;
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, health_client::HealthClient,
+    };
+
+    use super::*;
+    use crate::tonic::{uds_grpc_stream, wrap_channel_with_metrics};
+
+    #[tokio::test]
+    async fn test_metrics_recorded_per_rpc() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let _guard = ::metrics::set_default_local_recorder(&recorder);
+
+        let (_reporter, health_server) =
+            qcs_dependencies_client::tonic_health::server::health_reporter();
+
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let mut client = HealthClient::new(wrap_channel_with_metrics(channel));
+            client
+                .check(Request::new(HealthCheckRequest {
+                    service: String::new(),
+                }))
+                .await
+                .unwrap();
+            let status = client
+                .check(Request::new(HealthCheckRequest {
+                    service: "unknown".to_string(),
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), Code::NotFound);
+        })
+        .await
+        .unwrap();
+
+        let mut requests = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter_map(|(key, _, _, value)| {
+                let key = key.key();
+                if key.name() == RETRIES {
+                    panic!("no retries should be recorded without a retry layer");
+                }
+                if key.name() != REQUESTS {
+                    return None;
+                }
+                let label = |name: &str| {
+                    key.labels()
+                        .find(|label| label.key() == name)
+                        .map(|label| label.value().to_string())
+                        .unwrap()
+                };
+                let DebugValue::Counter(count) = value else {
+                    panic!("{REQUESTS} should be a counter");
+                };
+                Some((label("client"), label("operation"), label("status"), count))
+            })
+            .collect::<Vec<_>>();
+        requests.sort();
+
+        let operation = "/grpc.health.v1.Health/Check".to_string();
+        assert_eq!(
+            requests,
+            vec![
+                (
+                    "grpc".to_string(),
+                    operation.clone(),
+                    "NotFound".to_string(),
+                    1
+                ),
+                ("grpc".to_string(), operation, "Ok".to_string(), 1),
+            ]
+        );
+    }
+}
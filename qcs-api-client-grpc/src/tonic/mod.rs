@@ -1,35 +1,58 @@
 //! QCS Middleware for [`tonic`] clients.
 
-use std::convert::Infallible;
-
-use futures_util::pin_mut;
-use http_body::{Body as HttpBody, Frame};
-use http_body_util::StreamBody;
-use qcs_dependencies_client::http::Request;
-use qcs_dependencies_client::prost::bytes::Bytes;
-
+mod balance;
 mod channel;
 mod common;
+#[cfg(feature = "compression")]
+mod compression;
 mod error;
+mod error_details;
+#[cfg(feature = "grpc-web-fallback")]
+mod fallback;
 #[cfg(feature = "grpc-web")]
 mod grpc_web;
+mod health;
+mod hedge;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod pool;
 mod refresh;
+mod replay;
+#[cfg(feature = "resolver")]
+mod resolver;
 mod retry;
 #[cfg(feature = "tracing")]
 mod trace;
 
+pub use balance::*;
 pub use channel::*;
+#[cfg(feature = "compression")]
+pub use compression::*;
 pub use error::*;
+pub use error_details::*;
+#[cfg(feature = "grpc-web-fallback")]
+pub use fallback::*;
 #[cfg(feature = "grpc-web")]
 pub use grpc_web::*;
+pub use health::*;
+pub use hedge::*;
+#[cfg(feature = "metrics")]
+pub use metrics::*;
+pub use pool::*;
 use qcs_dependencies_client::tonic::body::Body;
 pub use refresh::*;
+pub use replay::*;
+#[cfg(feature = "resolver")]
+pub use resolver::*;
 pub use retry::*;
 #[cfg(feature = "tracing")]
 pub use trace::*;
 /// An error observed while duplicating a request body. This may be returned by any
 /// [`qcs_dependencies_client::tower::Service`] that duplicates a request body for the purpose of retrying a request.
 #[derive(Debug, thiserror::Error)]
+#[deprecated(
+    note = "request bodies are buffered once and replayed for retries, so this is never returned"
+)]
 pub enum RequestBodyDuplicationError {
     /// The inner service returned an error from the server, or the client cancelled the
     /// request.
@@ -40,6 +63,7 @@ pub enum RequestBodyDuplicationError {
     HttpBody(#[from] qcs_dependencies_client::http::Error),
 }
 
+#[allow(deprecated)]
 impl From<RequestBodyDuplicationError> for qcs_dependencies_client::tonic::Status {
     fn from(err: RequestBodyDuplicationError) -> qcs_dependencies_client::tonic::Status {
         match err {
@@ -53,80 +77,6 @@ impl From<RequestBodyDuplicationError> for qcs_dependencies_client::tonic::Statu
     }
 }
 
-type RequestBodyDuplicationResult<T> = Result<T, RequestBodyDuplicationError>;
-
-/// The AWS ALB can misinterpret requests which contain a `content-length` header,
-/// causing requests which are routed through a gateway to fail with an h2 protocol error.
-/// Using a [`StreamBody`], even for Unary requests, will prevent `tonic` from sending that header.
-fn make_stream_body(bytes: Bytes) -> qcs_dependencies_client::tonic::body::Body {
-    let body = Frame::data(bytes);
-
-    // Create a stream that yields a single frame
-    let stream = futures_util::stream::once(std::future::ready(Ok::<_, Infallible>(body)));
-
-    let stream_body = StreamBody::new(stream);
-
-    qcs_dependencies_client::tonic::body::Body::new(stream_body)
-}
-
-/// This function should only be used with Unary requests; Stream requests are
-/// untested. It eagerly collects all request data into a buffer, consuming the
-/// original stream. Additionally, it assumes that all frames are data frames
-/// (i.e. the stream cannot contain any trailers); if a trailer frame is found,
-/// the cancelled status will be returned.
-async fn build_duplicate_frame_bytes(
-    mut request: Request<qcs_dependencies_client::tonic::body::Body>,
-) -> RequestBodyDuplicationResult<(
-    qcs_dependencies_client::tonic::body::Body,
-    qcs_dependencies_client::tonic::body::Body,
-)> {
-    let mut bytes = Vec::new();
-
-    let body = request.body_mut();
-    pin_mut!(body);
-    while let Some(result) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
-        let frame_bytes = result?.into_data().map_err(|frame| {
-            qcs_dependencies_client::tonic::Status::cancelled(format!(
-                "cannot duplicate a frame that is not a data frame: {frame:?}"
-            ))
-        })?;
-        bytes.extend(frame_bytes);
-    }
-
-    let bytes = Bytes::from(bytes);
-
-    Ok((make_stream_body(bytes.clone()), make_stream_body(bytes)))
-}
-
-/// This function should only be used with Unary requests; Stream requests are
-/// untested. See comment on `build_duplicate_frame_bytes`.
-async fn build_duplicate_request(
-    req: Request<Body>,
-) -> RequestBodyDuplicationResult<(Request<Body>, Request<Body>)> {
-    let mut builder_1 = Request::builder()
-        .method(req.method().clone())
-        .uri(req.uri().clone())
-        .version(req.version());
-
-    let mut builder_2 = Request::builder()
-        .method(req.method().clone())
-        .uri(req.uri().clone())
-        .version(req.version());
-
-    for (key, val) in req.headers() {
-        builder_1 = builder_1.header(key, val);
-        builder_2 = builder_2.header(key, val);
-    }
-
-    let (body_1, body_2) = build_duplicate_frame_bytes(req).await?;
-
-    let req_1 = builder_1.body(body_1)?;
-
-    let req_2 = builder_2.body(body_2)?;
-
-    Ok((req_1, req_2))
-}
-
 /// This module manages a gRPC server-client connection over a Unix domain socket. Useful for unit testing
 /// servers or clients within unit tests - supports parallelization within same process and
 /// requires no port management.
@@ -0,0 +1,348 @@
+//! A cache of channels, so that clients created per request share their connections.
+
+use std::{
+    any::Any,
+    fmt,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+use qcs_api_client_common::configuration::{
+    ClientConfiguration,
+    proxy::ProxyConfig,
+    settings::{ChannelSettings, RetrySettings},
+    tls::TlsConfig,
+};
+use qcs_dependencies_client::http::Uri;
+
+/// How long a [`ChannelPool`] keeps a channel which has not been handed out, by default.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// A cache of built channels, keyed by their address and configuration.
+///
+/// Each call to [`ChannelBuilder::build`](super::ChannelBuilder::build) opens a new connection.
+/// [`ChannelBuilder::build_pooled`](super::ChannelBuilder::build_pooled) instead hands out clones
+/// of one service per address and configuration, so that clients created per request share a
+/// single HTTP/2 connection. Each clone includes all of the layers configured on the builder, such
+/// as authentication, retries and tracing.
+///
+/// A channel is keyed by:
+///
+/// - its URI;
+/// - the type of the service, i.e. which layers are configured;
+/// - the timeout and the [`RetrySettings`], [`ProxyConfig`], [`TlsConfig`] and [`ChannelSettings`]
+///   given to the builder; and
+/// - the [`ClientConfiguration`] given to the builder, if any. Configurations are only considered
+///   the same if they share their tokens (see [`ClientConfiguration::shares_oauth_session_with`]);
+///   and
+/// - the credentials key given to
+///   [`ChannelBuilder::build_pooled_with_key`](super::ChannelBuilder::build_pooled_with_key), if
+///   any. Services which authenticate with any other token refresher are only pooled by that key.
+///
+/// Parameters of individual layers, such as the backoff given to
+/// [`ChannelBuilder::with_retry_backoff`](super::ChannelBuilder::with_retry_backoff), are not part
+/// of the key; use separate pools for channels which only differ in such parameters.
+///
+/// Channels which have not been handed out for the pool's idle timeout (see
+/// [`ChannelPool::with_idle_timeout`]) are evicted whenever a channel is requested, or when
+/// [`ChannelPool::evict_idle`] is called. A connection is closed once the pool has evicted its
+/// channel and all clones handed out have been dropped, so call [`ChannelPool::clear`] on
+/// shutdown.
+///
+/// Clones of a [`ChannelPool`] share the same channels.
+#[derive(Clone, Debug)]
+pub struct ChannelPool {
+    channels: Arc<Mutex<Vec<PooledChannel>>>,
+    idle_timeout: Duration,
+}
+
+impl Default for ChannelPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelPool {
+    /// Create an empty [`ChannelPool`] which evicts channels after [`DEFAULT_POOL_IDLE_TIMEOUT`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            channels: Arc::default(),
+            idle_timeout: DEFAULT_POOL_IDLE_TIMEOUT,
+        }
+    }
+
+    /// The process-wide [`ChannelPool`].
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<ChannelPool> = OnceLock::new();
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// Set how long a channel is kept after it was last handed out.
+    #[must_use]
+    pub const fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// The number of channels in the pool.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Return `true` if the pool holds no channels.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Evict the channels which have not been handed out for the pool's idle timeout.
+    pub fn evict_idle(&self) {
+        let idle_timeout = self.idle_timeout;
+        self.lock()
+            .retain(|channel| channel.last_used.elapsed() < idle_timeout);
+    }
+
+    /// Evict every channel, e.g. on shutdown.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Get a clone of the pooled service with the given key, or build and pool a new one.
+    pub(super) fn get_or_try_insert_with<S, E>(
+        &self,
+        key: ChannelKey,
+        build: impl FnOnce() -> Result<S, E>,
+    ) -> Result<S, E>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let idle_timeout = self.idle_timeout;
+        let mut channels = self.lock();
+        channels.retain(|channel| channel.last_used.elapsed() < idle_timeout);
+
+        let pooled = channels
+            .iter_mut()
+            .find(|channel| channel.key.matches(&key) && channel.service.is::<S>());
+        if let Some(channel) = pooled {
+            if let Some(service) = channel.service.downcast_ref::<S>() {
+                channel.last_used = Instant::now();
+                return Ok(service.clone());
+            }
+        }
+
+        let service = build()?;
+        channels.push(PooledChannel {
+            key,
+            service: Box::new(service.clone()),
+            last_used: Instant::now(),
+        });
+        Ok(service)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<PooledChannel>> {
+        self.channels.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct PooledChannel {
+    key: ChannelKey,
+    service: Box<dyn Any + Send + Sync>,
+    last_used: Instant,
+}
+
+impl fmt::Debug for PooledChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledChannel")
+            .field("uri", &self.key.uri)
+            .field("last_used", &self.last_used)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The configuration of a [`super::ChannelBuilder`] which determines whether a pooled channel can
+/// be reused.
+#[derive(Clone, Debug)]
+pub(super) struct ChannelKey {
+    pub(super) uri: Uri,
+    pub(super) timeout: Option<Duration>,
+    pub(super) retry_settings: RetrySettings,
+    pub(super) proxy_config: Option<ProxyConfig>,
+    pub(super) tls_config: Option<TlsConfig>,
+    pub(super) channel_settings: Option<ChannelSettings>,
+    pub(super) qcs_config: Option<ClientConfiguration>,
+    pub(super) credentials_key: Option<String>,
+}
+
+impl ChannelKey {
+    fn matches(&self, other: &Self) -> bool {
+        let same_credentials = match (&self.qcs_config, &other.qcs_config) {
+            (Some(config), Some(other)) => {
+                config.profile() == other.profile() && config.shares_oauth_session_with(other)
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        same_credentials
+            && self.credentials_key == other.credentials_key
+            && self.uri == other.uri
+            && self.timeout == other.timeout
+            && self.retry_settings == other.retry_settings
+            && self.proxy_config == other.proxy_config
+            && self.tls_config == other.tls_config
+            && self.channel_settings == other.channel_settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use qcs_api_client_common::configuration::{
+        secrets::{SecretAccessToken, SecretRefreshToken},
+        settings::AuthServer,
+        tokens::{OAuthSession, RefreshToken, StaticToken},
+    };
+    use qcs_dependencies_client::tonic::transport::Server;
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, health_check_response::ServingStatus, health_client::HealthClient,
+    };
+    use tokio_stream::{StreamExt, wrappers::UnixListenerStream};
+
+    use super::*;
+    use crate::tonic::{ChannelBuilder, ChannelError, parse_uri};
+
+    fn qcs_config() -> ClientConfiguration {
+        ClientConfiguration::builder()
+            .proxy_config(ProxyConfig::default())
+            .oauth_session(Some(OAuthSession::from_refresh_token(
+                RefreshToken::new(SecretRefreshToken::from("refresh_token")),
+                AuthServer::default(),
+                Some(SecretAccessToken::from("access_token")),
+            )))
+            .build()
+            .expect("should be able to build client config")
+    }
+
+    #[tokio::test]
+    async fn test_reuses_channels_with_the_same_key() {
+        let pool = ChannelPool::new();
+        let uri = Uri::from_static("https://grpc.example.com");
+        let config = qcs_config();
+        let build = |config: ClientConfiguration| {
+            ChannelBuilder::from_uri(uri.clone())
+                .with_qcs_config(config)
+                .with_default_retry()
+                .build_pooled(&pool)
+                .unwrap()
+        };
+
+        let _first = build(config.clone());
+        let _second = build(config.clone());
+        assert_eq!(pool.len(), 1);
+
+        // A separately built configuration has its own tokens.
+        let _other_credentials = build(qcs_config());
+        assert_eq!(pool.len(), 2);
+
+        // Different layers make a different service.
+        let _without_retries = ChannelBuilder::from_uri(uri.clone())
+            .with_qcs_config(config.clone())
+            .build_pooled(&pool)
+            .unwrap();
+        assert_eq!(pool.len(), 3);
+
+        let _with_timeout = ChannelBuilder::from_uri(uri.clone())
+            .with_qcs_config(config)
+            .with_default_retry()
+            .with_timeout(Duration::from_secs(1))
+            .build_pooled(&pool)
+            .unwrap();
+        assert_eq!(pool.len(), 4);
+
+        pool.clear();
+        assert!(pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_only_pools_token_refreshers_by_credentials_key() {
+        let pool = ChannelPool::new();
+        let uri = Uri::from_static("https://grpc.example.com");
+        let build = |token: &str, key: &str| {
+            ChannelBuilder::from_uri(uri.clone())
+                .with_token_refresher(StaticToken::new(token.to_string()))
+                .build_pooled_with_key(&pool, key)
+                .unwrap()
+        };
+
+        let unkeyed = ChannelBuilder::from_uri(uri.clone())
+            .with_token_refresher(StaticToken::new("user-a"))
+            .build_pooled(&pool);
+        assert!(matches!(unkeyed, Err(ChannelError::UnkeyedRefresher)));
+        assert!(pool.is_empty());
+
+        let _user_a = build("user-a", "user-a");
+        let _user_a_again = build("user-a", "user-a");
+        assert_eq!(pool.len(), 1);
+
+        let _user_b = build("user-b", "user-b");
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_idle_channels() {
+        let pool = ChannelPool::new().with_idle_timeout(Duration::ZERO);
+        ChannelBuilder::from_uri(Uri::from_static("https://grpc.example.com"))
+            .build_pooled(&pool)
+            .unwrap();
+        assert_eq!(pool.len(), 1);
+
+        pool.evict_idle();
+        assert!(pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pooled_clients_share_a_connection() {
+        let (reporter, health_server) =
+            qcs_dependencies_client::tonic_health::server::health_reporter();
+        reporter
+            .set_service_status(
+                "",
+                qcs_dependencies_client::tonic_health::ServingStatus::Serving,
+            )
+            .await;
+        let directory = tempfile::TempDir::new().unwrap();
+        let path = directory.path().join("qcs.sock");
+        let connections = Arc::new(AtomicUsize::new(0));
+        let incoming = {
+            let connections = connections.clone();
+            UnixListenerStream::new(tokio::net::UnixListener::bind(&path).unwrap()).map(
+                move |connection| {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    connection
+                },
+            )
+        };
+        tokio::spawn(
+            Server::builder()
+                .add_service(health_server)
+                .serve_with_incoming(incoming),
+        );
+
+        let pool = ChannelPool::new();
+        let uri = parse_uri(&format!("unix://{}", path.display())).unwrap();
+        for _ in 0..3 {
+            let channel = ChannelBuilder::from_uri(uri.clone())
+                .with_default_retry()
+                .build_pooled(&pool)
+                .unwrap();
+            let response = HealthClient::new(channel)
+                .check(HealthCheckRequest::default())
+                .await
+                .unwrap();
+            assert_eq!(response.into_inner().status(), ServingStatus::Serving);
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+}
@@ -13,10 +13,13 @@ use qcs_dependencies_client::tonic::{
 use qcs_dependencies_client::tower::Layer;
 
 use qcs_api_client_common::configuration::{
-    ClientConfiguration, TokenError, secrets::SecretAccessToken, tokens::TokenRefresher,
+    ClientConfiguration, TokenError,
+    secrets::SecretAccessToken,
+    tokens::{TokenEvent, TokenOverride, TokenRefresher},
 };
 
 use super::error::Error;
+use super::replay::{DEFAULT_REPLAY_BUFFER_LIMIT, ReplayableRequest};
 
 /// The [`GrpcService`] that wraps the gRPC client in order to provide QCS authentication.
 ///
@@ -25,18 +28,47 @@ use super::error::Error;
 pub struct RefreshService<S: GrpcService<Body>, T: TokenRefresher> {
     service: S,
     token_refresher: T,
+    replay_buffer_limit: usize,
 }
 
 /// The [`Layer`] used to apply QCS authentication to requests.
+///
+/// If the server rejects the access token, the token is refreshed and the request is sent once
+/// more. As with the [`RetryLayer`](super::RetryLayer), this only happens before the first
+/// response message, and only if the request body fits within the replay buffer (see
+/// [`RefreshLayer::with_replay_buffer_limit`]); otherwise the rejection is returned as is.
+///
+/// A request may carry a [`TokenOverride`] in its extensions to be authenticated with other
+/// credentials than the layer's [`TokenRefresher`], e.g. those of the user it is made on behalf
+/// of:
+///
+/// ```ignore
+/// let mut request = tonic::Request::new(message);
+/// request
+///     .extensions_mut()
+///     .insert(TokenOverride::access_token(user_access_token));
+/// ```
 #[derive(Clone, Debug)]
 pub struct RefreshLayer<T: TokenRefresher> {
     token_refresher: T,
+    replay_buffer_limit: usize,
 }
 
 impl<T: TokenRefresher> RefreshLayer<T> {
     /// Create a new [`RefreshLayer`] with the given [`TokenRefresher`]
     pub const fn with_refresher(token_refresher: T) -> Self {
-        Self { token_refresher }
+        Self {
+            token_refresher,
+            replay_buffer_limit: DEFAULT_REPLAY_BUFFER_LIMIT,
+        }
+    }
+
+    /// Set the maximum number of request body bytes retained in order to resend a request with a
+    /// refreshed access token. Defaults to [`DEFAULT_REPLAY_BUFFER_LIMIT`].
+    #[must_use]
+    pub const fn with_replay_buffer_limit(mut self, limit: usize) -> Self {
+        self.replay_buffer_limit = limit;
+        self
     }
 }
 
@@ -80,6 +112,7 @@ where
     fn layer(&self, inner: S) -> Self::Service {
         RefreshService {
             token_refresher: self.token_refresher.clone(),
+            replay_buffer_limit: self.replay_buffer_limit,
             service: inner,
         }
     }
@@ -90,7 +123,7 @@ where
     S: GrpcService<Body> + Clone + Send + 'static,
     <S as GrpcService<Body>>::Future: Send,
     <S as GrpcService<Body>>::ResponseBody: Send,
-    T: TokenRefresher + Clone + Send + 'static,
+    T: TokenRefresher + Clone + Send + Sync + 'static,
     T::Error: std::error::Error + Sync,
     Error<T::Error>: From<S::Error>,
     <T as TokenRefresher>::Error: Send,
@@ -112,33 +145,87 @@ where
         // See this github issue for more context:
         // https://github.com/tower-rs/tower/issues/547
         let service = std::mem::replace(&mut self.service, service);
-        let token_refresher = self.token_refresher.clone();
+        let token_source = match req.extensions().get::<TokenOverride>() {
+            Some(token_override) => TokenSource::Override(token_override.clone()),
+            None => TokenSource::Refresher(self.token_refresher.clone()),
+        };
         super::common::pin_future_with_otel_context_if_available(service_call(
-            req,
-            token_refresher,
+            ReplayableRequest::new(req, self.replay_buffer_limit),
+            token_source,
             service,
         ))
     }
 }
 
+/// Where the access token for a request comes from.
+enum TokenSource<T> {
+    /// The [`RefreshLayer`]'s own refresher.
+    Refresher(T),
+    /// A [`TokenOverride`] carried by the request.
+    Override(TokenOverride),
+}
+
+impl<T> TokenSource<T>
+where
+    T: TokenRefresher,
+    T::Error: std::error::Error,
+{
+    async fn validated_access_token(&self) -> Result<SecretAccessToken, Error<T::Error>> {
+        match self {
+            Self::Refresher(token_refresher) => token_refresher
+                .validated_access_token()
+                .await
+                .map_err(Error::Refresh),
+            Self::Override(token_override) => token_override
+                .validated_access_token()
+                .await
+                .map_err(Error::RefreshOverride),
+        }
+    }
+
+    /// Get an access token to resend a request with after its token was rejected.
+    async fn refreshed_access_token(&self) -> Result<SecretAccessToken, Error<T::Error>> {
+        match self {
+            Self::Refresher(token_refresher) => {
+                token_refresher.emit_token_event(TokenEvent::AccessTokenRejected);
+                token_refresher
+                    .validated_access_token()
+                    .await
+                    .map_err(Error::Refresh)
+            }
+            Self::Override(token_override) => {
+                token_override.emit_token_event(TokenEvent::AccessTokenRejected);
+                token_override
+                    .refresh_access_token()
+                    .await
+                    .map_err(Error::RefreshOverride)
+            }
+        }
+    }
+
+    /// Returns whether a rejected request may be resent with a refreshed access token.
+    const fn is_refreshable(&self) -> bool {
+        match self {
+            Self::Refresher(_) => true,
+            Self::Override(token_override) => token_override.is_refreshable(),
+        }
+    }
+}
+
 async fn service_call<C, T>(
-    req: Request<Body>,
-    token_refresher: T,
+    (replayable, req): (ReplayableRequest, Request<Body>),
+    token_source: TokenSource<T>,
     mut channel: C,
 ) -> Result<Response<<C as GrpcService<Body>>::ResponseBody>, Error<T::Error>>
 where
     C: GrpcService<Body> + Send,
     <C as GrpcService<Body>>::ResponseBody: Send,
     <C as GrpcService<Body>>::Future: Send,
-    T: TokenRefresher + Send,
+    T: TokenRefresher + Send + Sync,
     T::Error: std::error::Error,
     Error<T::Error>: From<C::Error>,
 {
-    let token = token_refresher
-        .validated_access_token()
-        .await
-        .map_err(Error::Refresh)?;
-    let (req, retry_req) = super::build_duplicate_request(req).await?;
+    let token = token_source.validated_access_token().await?;
     let resp = make_request(&mut channel, req, token).await?;
 
     let grpc_authnz_failure = matches!(
@@ -149,17 +236,21 @@ where
     let http_authnz_failure =
         resp.status() == StatusCode::UNAUTHORIZED || resp.status() == StatusCode::FORBIDDEN;
 
-    if grpc_authnz_failure || http_authnz_failure {
+    // A request which can't be replayed, or whose token can't be refreshed, is passed through
+    // untouched.
+    let retry_req = ((grpc_authnz_failure || http_authnz_failure) && token_source.is_refreshable())
+        .then(|| replayable.request())
+        .flatten();
+
+    if let Some(retry_req) = retry_req {
+        drop(resp);
         #[cfg(feature = "tracing")]
         {
             tracing::info!("refreshing token after receiving unauthorized or forbidden status",);
         }
 
         // Refresh token and try again
-        let token = token_refresher
-            .validated_access_token()
-            .await
-            .map_err(Error::Refresh)?;
+        let token = token_source.refreshed_access_token().await?;
 
         #[cfg(feature = "tracing")]
         {
@@ -193,3 +284,143 @@ where
     request.headers_mut().insert("authorization", header_val);
     service.call(request).await.map_err(Error::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use qcs_dependencies_client::tonic::{
+        self, Code, Status, server::NamedService, service::interceptor::InterceptedService,
+    };
+    use qcs_dependencies_client::tonic_health::pb::{
+        HealthCheckRequest, health_check_response::ServingStatus, health_client::HealthClient,
+        health_server::HealthServer,
+    };
+    use qcs_dependencies_client::tonic_health::server::{HealthService, health_reporter};
+    use qcs_dependencies_client::tower::ServiceBuilder;
+
+    use super::*;
+    use crate::tonic::uds_grpc_stream;
+
+    /// A refresher which hands out a stale access token until it is refreshed.
+    #[derive(Clone, Debug, Default)]
+    struct UserRefresher {
+        refreshed: Arc<Mutex<bool>>,
+    }
+
+    #[qcs_dependencies_client::tonic::async_trait]
+    impl TokenRefresher for UserRefresher {
+        type Error = TokenError;
+
+        async fn validated_access_token(&self) -> Result<SecretAccessToken, Self::Error> {
+            Ok(self.get_access_token().await?.unwrap_or_default())
+        }
+
+        async fn get_access_token(&self) -> Result<Option<SecretAccessToken>, Self::Error> {
+            let token = if *self.refreshed.lock().unwrap() {
+                "fresh_token"
+            } else {
+                "stale_token"
+            };
+            Ok(Some(token.into()))
+        }
+
+        async fn refresh_access_token(&self) -> Result<SecretAccessToken, Self::Error> {
+            *self.refreshed.lock().unwrap() = true;
+            Ok("fresh_token".into())
+        }
+
+        // The dev-dependency on `qcs-api-client-common` enables `tracing-config`, so tests
+        // always implement these, whichever features other crates enable on it.
+        fn base_url(&self) -> &str {
+            ""
+        }
+
+        fn tracing_configuration(
+            &self,
+        ) -> Option<&qcs_api_client_common::tracing_configuration::TracingConfiguration> {
+            None
+        }
+    }
+
+    /// Check the health of the server with the given [`TokenOverride`], returning the status code
+    /// along with the `authorization` headers the server received.
+    async fn check_with_override(
+        accepted_token: &'static str,
+        token_override: TokenOverride,
+    ) -> (Code, Vec<String>) {
+        let (reporter, health_server) = health_reporter();
+        reporter.set_serving::<HealthServer<HealthService>>().await;
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let server = InterceptedService::new(health_server, {
+            let received = received.clone();
+            move |request: tonic::Request<()>| {
+                let authorization = request
+                    .metadata()
+                    .get("authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                let accepted = authorization == format!("Bearer {accepted_token}");
+                received.lock().unwrap().push(authorization);
+                if accepted {
+                    Ok(request)
+                } else {
+                    Err(Status::unauthenticated("invalid access token"))
+                }
+            }
+        });
+
+        let mut code = Code::Unknown;
+        uds_grpc_stream::serve(server, |channel| async {
+            // The layer's own configuration has no credentials, so any token must be the override.
+            let config = ClientConfiguration::builder().build().unwrap();
+            let service = ServiceBuilder::new()
+                .layer(RefreshLayer::with_config(config))
+                .service(channel);
+            let mut request = tonic::Request::new(HealthCheckRequest {
+                service: <HealthServer<HealthService> as NamedService>::NAME.to_string(),
+            });
+            request.extensions_mut().insert(token_override);
+            code = match HealthClient::new(service).check(request).await {
+                Ok(response) => {
+                    assert_eq!(response.into_inner().status(), ServingStatus::Serving);
+                    Code::Ok
+                }
+                Err(status) => status.code(),
+            };
+        })
+        .await
+        .unwrap();
+
+        let received = received.lock().unwrap().clone();
+        (code, received)
+    }
+
+    #[tokio::test]
+    async fn test_request_uses_token_override() {
+        let (code, received) =
+            check_with_override("user_token", TokenOverride::access_token("user_token")).await;
+        assert_eq!(code, Code::Ok);
+        assert_eq!(received, vec!["Bearer user_token"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_token_override_is_not_retried() {
+        let (code, received) =
+            check_with_override("other_token", TokenOverride::access_token("user_token")).await;
+        assert_eq!(code, Code::Unauthenticated);
+        assert_eq!(received, vec!["Bearer user_token"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_token_override_is_refreshed() {
+        let (code, received) = check_with_override(
+            "fresh_token",
+            TokenOverride::refresher(UserRefresher::default()),
+        )
+        .await;
+        assert_eq!(code, Code::Ok);
+        assert_eq!(received, vec!["Bearer stale_token", "Bearer fresh_token"]);
+    }
+}
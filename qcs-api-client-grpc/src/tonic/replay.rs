@@ -0,0 +1,335 @@
+//! Request bodies which can be replayed in order to retry a request.
+
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use qcs_dependencies_client::http::{Extensions, HeaderMap, Request};
+use qcs_dependencies_client::prost::bytes::Bytes;
+use qcs_dependencies_client::tonic::Status;
+
+use super::Body;
+
+/// The default maximum number of request body bytes retained in order to replay a request.
+///
+/// This comfortably fits any unary request, while bounding the memory used by long-lived
+/// client-streaming calls. Once a request body grows beyond the limit, the request is no longer
+/// retried.
+pub const DEFAULT_REPLAY_BUFFER_LIMIT: usize = 64 * 1024;
+
+/// A request which may be sent more than once.
+///
+/// Frames of the original body are recorded as they are read by the first attempt, rather than
+/// eagerly collected up front, so that streaming requests are not delayed until the client
+/// finishes sending. Later attempts replay the recorded frames and then continue reading from the
+/// original body.
+pub(crate) struct ReplayableRequest {
+    parts: RequestParts,
+    body: ReplayBody,
+}
+
+struct RequestParts {
+    method: qcs_dependencies_client::http::Method,
+    uri: qcs_dependencies_client::http::Uri,
+    version: qcs_dependencies_client::http::Version,
+    headers: HeaderMap,
+    extensions: Extensions,
+}
+
+impl ReplayableRequest {
+    /// Wrap `request`, retaining at most `limit` bytes of its body for replay, and return it
+    /// along with the request for the first attempt.
+    pub(crate) fn new(request: Request<Body>, limit: usize) -> (Self, Request<Body>) {
+        let (parts, body) = request.into_parts();
+        let replayable = Self {
+            parts: RequestParts {
+                method: parts.method,
+                uri: parts.uri,
+                version: parts.version,
+                headers: parts.headers,
+                extensions: parts.extensions,
+            },
+            body: ReplayBody::new(body, limit),
+        };
+        let first = replayable.build(replayable.body.replay_unchecked());
+        (replayable, first)
+    }
+
+    /// Build a request for the next attempt, or `None` if the body can no longer be replayed.
+    pub(crate) fn request(&self) -> Option<Request<Body>> {
+        self.body.replay().map(|body| self.build(body))
+    }
+
+    /// Build a request for an attempt which runs alongside the current one, or `None` unless the
+    /// whole body has already been read and retained.
+    ///
+    /// The original body only wakes the last attempt to poll it, so concurrent attempts must not
+    /// both wait on it; a fully buffered body is replayed without reading from it again.
+    pub(crate) fn buffered_request(&self) -> Option<Request<Body>> {
+        self.body
+            .is_buffered()
+            .then(|| self.build(self.body.replay_unchecked()))
+    }
+
+    fn build(&self, body: ReplayBody) -> Request<Body> {
+        let mut request = Request::new(Body::new(body));
+        *request.method_mut() = self.parts.method.clone();
+        *request.uri_mut() = self.parts.uri.clone();
+        *request.version_mut() = self.parts.version;
+        *request.headers_mut() = self.parts.headers.clone();
+        *request.extensions_mut() = self.parts.extensions.clone();
+        request
+    }
+}
+
+/// The state shared by every attempt's view of the original request body.
+struct Shared {
+    body: Body,
+    /// The data frames read from `body` so far, unless `overflowed`.
+    frames: Vec<Bytes>,
+    /// The number of data frames read from `body` so far.
+    frames_read: usize,
+    buffered_bytes: usize,
+    limit: usize,
+    /// Set once frames have been discarded, after which the body cannot be replayed.
+    overflowed: bool,
+    end_of_stream: bool,
+    trailers: Option<HeaderMap>,
+}
+
+impl Shared {
+    fn record(&mut self, data: &Bytes) {
+        self.frames_read += 1;
+        if self.overflowed {
+            return;
+        }
+        self.buffered_bytes += data.len();
+        if self.buffered_bytes > self.limit {
+            self.discard();
+        } else {
+            self.frames.push(data.clone());
+        }
+    }
+
+    fn discard(&mut self) {
+        self.overflowed = true;
+        self.frames = Vec::new();
+    }
+}
+
+/// One attempt's view of a request body that is shared between attempts.
+///
+/// The AWS ALB can misinterpret requests which contain a `content-length` header,
+/// causing requests which are routed through a gateway to fail with an h2 protocol error.
+/// This body never reports an exact size, even for Unary requests, which prevents `tonic` from
+/// sending that header.
+struct ReplayBody {
+    shared: Arc<Mutex<Shared>>,
+    /// The index of the next data frame this attempt will yield.
+    position: usize,
+    trailers_sent: bool,
+}
+
+impl ReplayBody {
+    fn new(body: Body, limit: usize) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(Shared {
+                body,
+                frames: Vec::new(),
+                frames_read: 0,
+                buffered_bytes: 0,
+                limit,
+                overflowed: false,
+                end_of_stream: false,
+                trailers: None,
+            })),
+            position: 0,
+            trailers_sent: false,
+        }
+    }
+
+    fn is_replayable(&self) -> bool {
+        !self
+            .shared
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .overflowed
+    }
+
+    fn is_buffered(&self) -> bool {
+        let shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.end_of_stream && !shared.overflowed
+    }
+
+    /// A view of the body from its start, or `None` if frames have been discarded.
+    fn replay(&self) -> Option<Self> {
+        self.is_replayable().then(|| self.replay_unchecked())
+    }
+
+    fn replay_unchecked(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            position: 0,
+            trailers_sent: false,
+        }
+    }
+}
+
+impl HttpBody for ReplayBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let mut shared = this.shared.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if this.position < shared.frames_read {
+            let Some(data) = shared.frames.get(this.position).cloned() else {
+                return Poll::Ready(Some(Err(Status::cancelled(
+                    "request body exceeded the replay buffer and cannot be resent",
+                ))));
+            };
+            this.position += 1;
+            return Poll::Ready(Some(Ok(Frame::data(data))));
+        }
+
+        if shared.end_of_stream {
+            if this.trailers_sent {
+                return Poll::Ready(None);
+            }
+            this.trailers_sent = true;
+            return Poll::Ready(
+                shared
+                    .trailers
+                    .clone()
+                    .map(|trailers| Ok(Frame::trailers(trailers))),
+            );
+        }
+
+        let frame = match Pin::new(&mut shared.body).poll_frame(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(None) => {
+                shared.end_of_stream = true;
+                this.trailers_sent = true;
+                return Poll::Ready(None);
+            }
+            Poll::Ready(Some(Err(status))) => {
+                // The remainder of the body is lost, so it cannot be replayed.
+                shared.discard();
+                return Poll::Ready(Some(Err(status)));
+            }
+            Poll::Ready(Some(Ok(frame))) => frame,
+        };
+
+        let frame = match frame.into_data() {
+            Ok(data) => {
+                shared.record(&data);
+                this.position += 1;
+                return Poll::Ready(Some(Ok(Frame::data(data))));
+            }
+            Err(frame) => frame,
+        };
+
+        if let Some(trailers) = frame.trailers_ref() {
+            shared.trailers = Some(trailers.clone());
+            shared.end_of_stream = true;
+            this.trailers_sent = true;
+        } else {
+            // An unknown kind of frame cannot be recorded.
+            shared.discard();
+        }
+        Poll::Ready(Some(Ok(frame)))
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http_body_util::{BodyExt, StreamBody};
+
+    use super::*;
+
+    fn streaming_request(chunks: &[&'static [u8]]) -> Request<Body> {
+        let frames = chunks
+            .iter()
+            .map(|chunk| Ok::<_, Infallible>(Frame::data(Bytes::from_static(chunk))))
+            .collect::<Vec<_>>();
+        Request::new(Body::new(StreamBody::new(futures_util::stream::iter(
+            frames,
+        ))))
+    }
+
+    async fn collect(request: Request<Body>) -> Result<Bytes, Status> {
+        Ok(request.into_body().collect().await?.to_bytes())
+    }
+
+    #[tokio::test]
+    async fn test_replays_whole_body() {
+        let (replayable, first) = ReplayableRequest::new(streaming_request(&[b"abc", b"def"]), 16);
+
+        assert_eq!(collect(first).await.unwrap(), "abcdef");
+        let second = replayable.request().expect("body should be replayable");
+        assert_eq!(collect(second).await.unwrap(), "abcdef");
+    }
+
+    #[tokio::test]
+    async fn test_replay_continues_partially_read_body() {
+        let (replayable, first) = ReplayableRequest::new(streaming_request(&[b"abc", b"def"]), 16);
+
+        let mut first = first.into_body();
+        let frame = first.frame().await.unwrap().unwrap();
+        assert_eq!(frame.into_data().unwrap(), "abc");
+        drop(first);
+
+        let second = replayable.request().expect("body should be replayable");
+        assert_eq!(collect(second).await.unwrap(), "abcdef");
+    }
+
+    #[tokio::test]
+    async fn test_buffered_request_requires_whole_body() {
+        let (replayable, first) = ReplayableRequest::new(streaming_request(&[b"abc", b"def"]), 16);
+        assert!(replayable.buffered_request().is_none());
+
+        let mut first = first.into_body();
+        first.frame().await.unwrap().unwrap();
+        assert!(replayable.buffered_request().is_none());
+
+        assert_eq!(first.collect().await.unwrap().to_bytes(), "def");
+        let second = replayable
+            .buffered_request()
+            .expect("whole body should be buffered");
+        assert_eq!(collect(second).await.unwrap(), "abcdef");
+    }
+
+    #[tokio::test]
+    async fn test_body_exceeding_limit_is_not_replayable() {
+        let (replayable, first) = ReplayableRequest::new(streaming_request(&[b"abc", b"def"]), 4);
+
+        assert_eq!(collect(first).await.unwrap(), "abcdef");
+        assert!(replayable.request().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay_created_before_overflow_fails() {
+        let (replayable, first) = ReplayableRequest::new(streaming_request(&[b"abc", b"def"]), 4);
+
+        let second = replayable.request().unwrap();
+        assert_eq!(collect(first).await.unwrap(), "abcdef");
+        let error = collect(second).await.unwrap_err();
+        assert_eq!(
+            error.code(),
+            qcs_dependencies_client::tonic::Code::Cancelled
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Resolution of a quantum processor's gRPC address through the QCS OpenAPI.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use futures_util::stream::{FuturesOrdered, StreamExt};
+use qcs_api_client_common::configuration::ClientConfiguration;
+use qcs_api_client_openapi::apis::{
+    self,
+    configuration::Configuration,
+    endpoints_api::{GetDefaultEndpointError, get_default_endpoint},
+    quantum_processors_api::{GetQuantumProcessorAccessorsError, get_quantum_processor_accessors},
+};
+use qcs_api_client_openapi::models::QuantumProcessorAccessor;
+use qcs_dependencies_client::http::Uri;
+use qcs_dependencies_client::tonic_health::pb::{HealthCheckRequest, health_client::HealthClient};
+
+use super::{
+    BalancedChannel, ChannelBuilder, ChannelError, DEFAULT_HEALTH_CHECK_INTERVAL, RefreshOptions,
+    health::server_responded,
+};
+
+/// How long a resolution is cached by default.
+pub const DEFAULT_RESOLUTION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long to wait for an address to respond before failing over to the next one.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors that may occur when resolving the gRPC address of a quantum processor.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// Failed to list the accessors of the quantum processor.
+    #[error("failed to list accessors for quantum processor {quantum_processor_id}: {source}")]
+    Accessors {
+        /// The ID of the quantum processor.
+        quantum_processor_id: String,
+        /// The source of the error.
+        #[source]
+        source: apis::Error<GetQuantumProcessorAccessorsError>,
+    },
+    /// Failed to get the default endpoint of the quantum processor.
+    #[error(
+        "failed to get default endpoint for quantum processor {quantum_processor_id}: {source}"
+    )]
+    DefaultEndpoint {
+        /// The ID of the quantum processor.
+        quantum_processor_id: String,
+        /// The source of the error.
+        #[source]
+        source: apis::Error<GetDefaultEndpointError>,
+    },
+    /// The quantum processor has no live accessor or default endpoint with a gRPC address.
+    #[error("quantum processor {0} has no gRPC address")]
+    NoAddress(String),
+    /// None of the gRPC addresses of the quantum processor could be reached.
+    #[error(
+        "none of the gRPC addresses of quantum processor {quantum_processor_id} are reachable: {addresses:?}"
+    )]
+    Unreachable {
+        /// The ID of the quantum processor.
+        quantum_processor_id: String,
+        /// The addresses that were tried, in order.
+        addresses: Vec<Uri>,
+    },
+    /// Failed to create a channel to the resolved addresses.
+    #[error("failed to create a channel to the resolved addresses: {0}")]
+    Channel(#[source] ChannelError),
+}
+
+/// Resolves a quantum processor ID into a [`ChannelBuilder`] for its controller.
+///
+/// The gRPC addresses of a quantum processor are those of its live accessors, ordered by rank
+/// (lowest first), or if it has none, the gRPC address of its default endpoint. When building a
+/// channel, the addresses are tried in order and the first one that responds is used.
+///
+/// Resolved addresses are cached for [`DEFAULT_RESOLUTION_TTL`] (see
+/// [`ProcessorResolver::with_ttl`]). The cache is shared between clones of the resolver.
+#[derive(Clone, Debug)]
+pub struct ProcessorResolver {
+    configuration: Arc<Configuration>,
+    ttl: Duration,
+    cache: Arc<Mutex<HashMap<String, CachedAddresses>>>,
+}
+
+#[derive(Clone, Debug)]
+struct CachedAddresses {
+    resolved_at: Instant,
+    addresses: Vec<Uri>,
+}
+
+impl ProcessorResolver {
+    /// Create a [`ProcessorResolver`] which uses the given OpenAPI [`Configuration`].
+    #[must_use]
+    pub fn new(configuration: Configuration) -> Self {
+        Self {
+            configuration: Arc::new(configuration),
+            ttl: DEFAULT_RESOLUTION_TTL,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Create a [`ProcessorResolver`] from an existing [`ClientConfiguration`].
+    #[must_use]
+    pub fn with_qcs_config(config: ClientConfiguration) -> Self {
+        Self::new(Configuration::with_qcs_config(config))
+    }
+
+    /// Set how long resolved addresses are cached.
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Remove any cached addresses for the quantum processor, e.g. after its controller stopped
+    /// responding.
+    pub fn invalidate(&self, quantum_processor_id: &str) {
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(quantum_processor_id);
+    }
+
+    /// Resolve the gRPC addresses of the quantum processor, in the order they should be tried.
+    ///
+    /// # Errors
+    ///
+    /// See [`ResolveError`].
+    pub async fn resolve(&self, quantum_processor_id: &str) -> Result<Vec<Uri>, ResolveError> {
+        let cached = self
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(quantum_processor_id)
+            .filter(|cached| cached.resolved_at.elapsed() < self.ttl)
+            .map(|cached| cached.addresses.clone());
+        if let Some(addresses) = cached {
+            return Ok(addresses);
+        }
+
+        let addresses = self.fetch_addresses(quantum_processor_id).await?;
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                quantum_processor_id.to_string(),
+                CachedAddresses {
+                    resolved_at: Instant::now(),
+                    addresses: addresses.clone(),
+                },
+            );
+        Ok(addresses)
+    }
+
+    /// Resolve the quantum processor into a [`ChannelBuilder`] for the first of its addresses
+    /// that responds, with QCS authentication configured.
+    ///
+    /// # Errors
+    ///
+    /// See [`ResolveError`].
+    pub async fn channel_builder(
+        &self,
+        quantum_processor_id: &str,
+    ) -> Result<ChannelBuilder<RefreshOptions<(), ClientConfiguration>>, ResolveError> {
+        let addresses = self.resolve(quantum_processor_id).await?;
+        // Probe every address at once, but prefer them in order: an address is only used once
+        // all of those before it are known to be unreachable.
+        let mut probes = addresses
+            .clone()
+            .into_iter()
+            .map(|uri| async move {
+                let builder = ChannelBuilder::from_uri(uri)
+                    .with_qcs_config(self.configuration.qcs_config.clone());
+                let reachable = is_reachable(&builder).await;
+                (builder, reachable)
+            })
+            .collect::<FuturesOrdered<_>>();
+        while let Some((builder, reachable)) = probes.next().await {
+            if reachable {
+                return Ok(builder);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                "failing over from unreachable gRPC address {}",
+                builder.uri()
+            );
+        }
+
+        // The addresses may be stale, so resolve them again next time.
+        self.invalidate(quantum_processor_id);
+        Err(ResolveError::Unreachable {
+            quantum_processor_id: quantum_processor_id.to_string(),
+            addresses,
+        })
+    }
+
+    /// Resolve the quantum processor into a [`BalancedChannel`] across all of its addresses,
+    /// using the proxy, TLS and channel settings of the QCS configuration.
+    ///
+    /// The channel does not authenticate requests; wrap it with a
+    /// [`RefreshLayer`](super::RefreshLayer) (e.g. with [`wrap_channel`](super::wrap_channel)) to
+    /// do so.
+    ///
+    /// # Errors
+    ///
+    /// See [`ResolveError`].
+    pub async fn balanced_channel(
+        &self,
+        quantum_processor_id: &str,
+    ) -> Result<BalancedChannel, ResolveError> {
+        let addresses = self.resolve(quantum_processor_id).await?;
+        let builders = addresses.into_iter().map(|uri| {
+            ChannelBuilder::from_uri(uri).with_qcs_config(self.configuration.qcs_config.clone())
+        });
+        BalancedChannel::from_builders(builders, DEFAULT_HEALTH_CHECK_INTERVAL)
+            .map_err(ResolveError::Channel)
+    }
+
+    async fn fetch_addresses(&self, quantum_processor_id: &str) -> Result<Vec<Uri>, ResolveError> {
+        let accessors = get_quantum_processor_accessors(&self.configuration, quantum_processor_id)
+            .await
+            .map_err(|source| ResolveError::Accessors {
+                quantum_processor_id: quantum_processor_id.to_string(),
+                source,
+            })?
+            .accessors;
+        let addresses = ranked_addresses(accessors);
+        if !addresses.is_empty() {
+            return Ok(addresses);
+        }
+
+        let endpoint = get_default_endpoint(&self.configuration, quantum_processor_id)
+            .await
+            .map_err(|source| ResolveError::DefaultEndpoint {
+                quantum_processor_id: quantum_processor_id.to_string(),
+                source,
+            })?;
+        endpoint
+            .addresses
+            .grpc
+            .as_deref()
+            .and_then(parse_address)
+            .map(|uri| vec![uri])
+            .ok_or_else(|| ResolveError::NoAddress(quantum_processor_id.to_string()))
+    }
+}
+
+/// The addresses of the live accessors, ordered by rank. Accessors without a rank come last.
+fn ranked_addresses(mut accessors: Vec<QuantumProcessorAccessor>) -> Vec<Uri> {
+    accessors.retain(|accessor| accessor.live);
+    accessors.sort_by_key(|accessor| (accessor.rank.is_none(), accessor.rank));
+    accessors
+        .iter()
+        .filter_map(|accessor| parse_address(&accessor.url))
+        .collect()
+}
+
+fn parse_address(address: &str) -> Option<Uri> {
+    let uri = address.parse::<Uri>();
+    #[cfg(feature = "tracing")]
+    if let Err(error) = &uri {
+        tracing::warn!("ignoring invalid gRPC address {address:?}: {error}");
+    }
+    uri.ok()
+}
+
+/// Whether a gRPC server responds at the address of `builder`, connecting with its proxy and TLS
+/// settings. A server which does not implement the health service still counts as reachable.
+async fn is_reachable<O>(builder: &ChannelBuilder<O>) -> bool {
+    let Ok(channel) = builder.build_channel() else {
+        return false;
+    };
+    let mut client = HealthClient::new(channel);
+    let check = client.check(HealthCheckRequest::default());
+    match tokio::time::timeout(PROBE_TIMEOUT, check).await {
+        Ok(Ok(_)) => true,
+        Ok(Err(status)) => server_responded(&status),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use qcs_api_client_openapi::models::QuantumProcessorAccessorType;
+
+    use super::*;
+
+    fn accessor(url: &str, live: bool, rank: Option<i64>) -> QuantumProcessorAccessor {
+        QuantumProcessorAccessor {
+            rank,
+            ..QuantumProcessorAccessor::new(
+                QuantumProcessorAccessorType::GatewayV1,
+                live,
+                url.to_string(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_ranked_addresses() {
+        let addresses = ranked_addresses(vec![
+            accessor("https://unranked.example.com", true, None),
+            accessor("https://second.example.com", true, Some(2)),
+            accessor("https://offline.example.com", false, Some(0)),
+            accessor("not a uri", true, Some(0)),
+            accessor("https://first.example.com", true, Some(1)),
+        ]);
+
+        assert_eq!(
+            addresses,
+            vec![
+                Uri::from_static("https://first.example.com"),
+                Uri::from_static("https://second.example.com"),
+                Uri::from_static("https://unranked.example.com"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_uses_cached_addresses() {
+        let resolver = ProcessorResolver::with_qcs_config(
+            ClientConfiguration::builder()
+                .build()
+                .expect("should be able to build default client config"),
+        );
+        let addresses = vec![Uri::from_static("https://cached.example.com")];
+        resolver.cache.lock().unwrap().insert(
+            "Ankaa-3".to_string(),
+            CachedAddresses {
+                resolved_at: Instant::now(),
+                addresses: addresses.clone(),
+            },
+        );
+
+        assert_eq!(resolver.resolve("Ankaa-3").await.unwrap(), addresses);
+
+        resolver.invalidate("Ankaa-3");
+        assert!(resolver.cache.lock().unwrap().is_empty());
+    }
+}
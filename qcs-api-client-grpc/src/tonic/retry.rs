@@ -1,34 +1,146 @@
 use super::Body;
 use qcs_api_client_common::{
-    backoff::{self, ExponentialBackoff, backoff::Backoff},
-    configuration::TokenError,
+    backoff::{ExponentialBackoff, backoff::Backoff},
+    configuration::{TokenError, settings::RetrySettings},
 };
-use qcs_dependencies_client::http::{HeaderValue, Request, Response};
-use qcs_dependencies_client::tonic::{Status, client::GrpcService};
+use qcs_dependencies_client::http::{HeaderMap, HeaderValue, Request, Response};
+use qcs_dependencies_client::tonic::{Code, Status, client::GrpcService};
 
-use qcs_api_client_common::backoff::duration_from_response as duration_from_http_response;
+use qcs_api_client_common::backoff::{
+    duration_from_response_with_settings as duration_from_http_response, duration_from_retry_after,
+};
 use std::{
+    collections::HashMap,
     future::{Future, poll_fn},
     pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     task::{Context, Poll},
     time::Duration,
 };
+use tokio::time::Instant;
 
-use super::{RequestBodyDuplicationError, build_duplicate_request};
+use super::ErrorDetails;
+use super::replay::{DEFAULT_REPLAY_BUFFER_LIMIT, ReplayableRequest};
 use qcs_dependencies_client::tower::Layer;
 
 /// The [`Layer`] used to apply exponential backoff retry logic to requests.
+///
+/// A request is retried when the server responds with one of the retryable gRPC status codes (by
+/// default, only [`Code::Unavailable`]). As described by the
+/// [gRPC retry design](https://github.com/grpc/proposal/blob/master/A6-client-retries.md#pushback),
+/// a server may push back on a retry with `grpc-retry-pushback-ms` metadata: a non-negative value
+/// is used as the delay before the next attempt, and any other value prevents the retry. Without
+/// such metadata, the delay of a [`RetryInfo`](super::RetryInfo) attached to the status (see
+/// [`ErrorDetails`]) is used, and otherwise a `retry-after` metadata entry is honored in the same
+/// way as the HTTP `Retry-After` header. In any case, the request is not retried once the backoff
+/// is exhausted.
+///
+/// Only responses which carry their status in the response headers (i.e. "trailers-only"
+/// responses, which is how servers report errors that occur before any response message) are
+/// retried. Since such a response precedes any response message, streaming calls are only
+/// retried before the first response message arrives; afterwards the response stream is passed
+/// through untouched.
+///
+/// To replay a request, up to [`DEFAULT_REPLAY_BUFFER_LIMIT`] bytes of its body are retained while
+/// the body is sent (see [`RetryLayer::with_replay_buffer_limit`]). A request whose body exceeds the
+/// limit, such as a long client stream, is not retried and its response is returned as is.
+///
+/// Individual methods can be given their own [`MethodRetryPolicy`] with
+/// [`RetryLayer::with_method_policy`].
+///
+/// If a request has a `grpc-timeout` (e.g. one set with
+/// [`tonic::Request::set_timeout`](qcs_dependencies_client::tonic::Request::set_timeout)), that
+/// timeout is treated as a budget for all attempts: each attempt is sent with the time remaining
+/// until the original deadline, and no retry is scheduled that would start after it.
 #[derive(Debug, Clone)]
 pub struct RetryLayer {
     pub(crate) backoff: ExponentialBackoff,
+    pub(crate) retryable_codes: Arc<[Code]>,
+    pub(crate) retry_settings: Arc<RetrySettings>,
+    pub(crate) method_policies: Arc<HashMap<String, MethodRetryPolicy>>,
+    pub(crate) replay_buffer_limit: usize,
+}
+
+/// How requests to a particular gRPC method are retried by a [`RetryLayer`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum MethodRetryPolicy {
+    /// Requests to the method are never retried, e.g. because the method is not idempotent.
+    Never,
+    /// Requests to the method are retried using the given backoff instead of the layer's.
+    Backoff(ExponentialBackoff),
 }
 
 impl Default for RetryLayer {
     fn default() -> Self {
+        Self::from_retry_settings(RetrySettings::default())
+    }
+}
+
+impl RetryLayer {
+    /// Create a [`RetryLayer`] that uses the backoff and retryable status codes described by the
+    /// given [`RetrySettings`], such as those of a [`ClientConfiguration`].
+    ///
+    /// Unrecognized gRPC code names in [`RetrySettings::retryable_grpc_codes`] are ignored.
+    ///
+    /// [`ClientConfiguration`]: qcs_api_client_common::ClientConfiguration
+    #[must_use]
+    pub fn from_retry_settings(retry_settings: RetrySettings) -> Self {
+        let retryable_codes = retry_settings
+            .retryable_grpc_codes
+            .iter()
+            .filter_map(|name| {
+                let code = code_from_name(name);
+                #[cfg(feature = "tracing")]
+                if code.is_none() {
+                    tracing::warn!("ignoring unrecognized retryable gRPC code {name:?}");
+                }
+                code
+            })
+            .collect();
+
         Self {
-            backoff: backoff::default_backoff(),
+            backoff: retry_settings.backoff(),
+            retryable_codes,
+            retry_settings: Arc::new(retry_settings),
+            method_policies: Arc::default(),
+            replay_buffer_limit: DEFAULT_REPLAY_BUFFER_LIMIT,
         }
     }
+
+    /// Set the gRPC status codes for which requests are retried, replacing any existing set.
+    ///
+    /// Only include codes for which a retry is safe. For example, [`Code::ResourceExhausted`] or
+    /// [`Code::Aborted`] are usually safe to retry for read-only RPCs.
+    #[must_use]
+    pub fn with_retryable_codes(mut self, codes: impl IntoIterator<Item = Code>) -> Self {
+        self.retryable_codes = codes.into_iter().collect();
+        self
+    }
+
+    /// Set the [`MethodRetryPolicy`] for requests to the gRPC method at `path`, e.g.
+    /// `/services.controller.Controller/ExecuteControllerJob`. Methods without a policy are
+    /// retried using this layer's backoff.
+    #[must_use]
+    pub fn with_method_policy(
+        mut self,
+        path: impl Into<String>,
+        policy: MethodRetryPolicy,
+    ) -> Self {
+        Arc::make_mut(&mut self.method_policies).insert(path.into(), policy);
+        self
+    }
+
+    /// Set the maximum number of request body bytes retained in order to retry a request. Requests
+    /// with larger bodies are sent once, without retries.
+    #[must_use]
+    pub const fn with_replay_buffer_limit(mut self, limit: usize) -> Self {
+        self.replay_buffer_limit = limit;
+        self
+    }
 }
 
 impl<S: GrpcService<Body>> Layer<S> for RetryLayer {
@@ -37,6 +149,10 @@ impl<S: GrpcService<Body>> Layer<S> for RetryLayer {
     fn layer(&self, service: S) -> Self::Service {
         Self::Service {
             backoff: self.backoff.clone(),
+            retryable_codes: self.retryable_codes.clone(),
+            retry_settings: self.retry_settings.clone(),
+            method_policies: self.method_policies.clone(),
+            replay_buffer_limit: self.replay_buffer_limit,
             service,
         }
     }
@@ -53,26 +169,109 @@ impl<S: GrpcService<Body>> Layer<S> for RetryLayer {
 #[derive(Clone, Debug)]
 pub struct RetryService<S: GrpcService<Body>> {
     backoff: ExponentialBackoff,
+    retryable_codes: Arc<[Code]>,
+    retry_settings: Arc<RetrySettings>,
+    method_policies: Arc<HashMap<String, MethodRetryPolicy>>,
+    replay_buffer_limit: usize,
     service: S,
 }
 
+/// Parse a gRPC status code from its name, e.g. `UNAVAILABLE`, `unavailable`, or `Unavailable`.
+fn code_from_name(name: &str) -> Option<Code> {
+    let code = match name.replace('_', "").to_ascii_uppercase().as_str() {
+        "OK" => Code::Ok,
+        "CANCELLED" => Code::Cancelled,
+        "UNKNOWN" => Code::Unknown,
+        "INVALIDARGUMENT" => Code::InvalidArgument,
+        "DEADLINEEXCEEDED" => Code::DeadlineExceeded,
+        "NOTFOUND" => Code::NotFound,
+        "ALREADYEXISTS" => Code::AlreadyExists,
+        "PERMISSIONDENIED" => Code::PermissionDenied,
+        "RESOURCEEXHAUSTED" => Code::ResourceExhausted,
+        "FAILEDPRECONDITION" => Code::FailedPrecondition,
+        "ABORTED" => Code::Aborted,
+        "OUTOFRANGE" => Code::OutOfRange,
+        "UNIMPLEMENTED" => Code::Unimplemented,
+        "INTERNAL" => Code::Internal,
+        "UNAVAILABLE" => Code::Unavailable,
+        "DATALOSS" => Code::DataLoss,
+        "UNAUTHENTICATED" => Code::Unauthenticated,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// The server's pushback on retrying a request.
+#[derive(Debug, PartialEq, Eq)]
+enum Pushback {
+    /// The server did not push back, so the backoff determines the delay.
+    None,
+    /// The server asked for the next attempt to be delayed by this duration.
+    Delay(Duration),
+    /// The server asked for the request not to be retried.
+    DoNotRetry,
+}
+
+impl Pushback {
+    fn from_metadata(headers: &HeaderMap) -> Self {
+        if let Some(value) = headers.get(KEY_GRPC_RETRY_PUSHBACK_MS) {
+            // Negative or unparseable values mean that the request should not be retried.
+            return value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map_or(Self::DoNotRetry, |millis| {
+                    Self::Delay(Duration::from_millis(millis))
+                });
+        }
+
+        duration_from_retry_after(headers).map_or(Self::None, Self::Delay)
+    }
+
+    /// Like [`Pushback::from_metadata`], but a [`RetryInfo`](super::RetryInfo) attached to
+    /// `status` takes precedence over a `retry-after` entry.
+    fn from_status(status: &Status, headers: &HeaderMap) -> Self {
+        if !headers.contains_key(KEY_GRPC_RETRY_PUSHBACK_MS) {
+            if let Some(delay) = ErrorDetails::from_status(status).retry_delay() {
+                return Self::Delay(delay);
+            }
+        }
+        Self::from_metadata(headers)
+    }
+}
+
 /// Return `Some` if the request should be retried and the provided `backoff`
 /// has another backoff to try, or, for an http request, if the response
 /// specifies a `Retry-After` header. If `None` is returned, the request should
 /// not be retried.
+///
+/// A gRPC request is not retried if the retry would start after the request's `deadline`.
 fn duration_from_response<T>(
     response: &Response<T>,
     backoff: &mut ExponentialBackoff,
+    retryable_codes: &[Code],
+    retry_settings: &RetrySettings,
+    deadline: Option<Instant>,
 ) -> Option<Duration> {
     if let Some(grpc_status) = Status::from_header_map(response.headers()) {
-        match grpc_status.code() {
-            // gRPC has no equivalent to RETRY-AFTER, so just use the backoff
-            qcs_dependencies_client::tonic::Code::Unavailable => backoff.next_backoff(),
-            // No other gRPC statuses are retried.
-            _ => None,
+        if !retryable_codes.contains(&grpc_status.code()) {
+            return None;
         }
+
+        match Pushback::from_status(&grpc_status, response.headers()) {
+            Pushback::None => backoff.next_backoff(),
+            // Still consult the backoff, so that pushback can't retry a request forever.
+            Pushback::Delay(delay) => backoff.next_backoff().map(|_| delay),
+            Pushback::DoNotRetry => None,
+        }
+        .filter(|duration| deadline.is_none_or(|deadline| Instant::now() + *duration < deadline))
     } else {
-        duration_from_http_response(response.status(), response.headers(), backoff)
+        duration_from_http_response(
+            response.status(),
+            response.headers(),
+            backoff,
+            retry_settings,
+        )
     }
 }
 
@@ -81,7 +280,7 @@ where
     S: GrpcService<Body> + Send + Clone + 'static,
     S::Future: Send,
     S::ResponseBody: Send,
-    super::error::Error<TokenError>: From<S::Error> + From<RequestBodyDuplicationError>,
+    super::error::Error<TokenError>: From<S::Error>,
 {
     type ResponseBody = <S as GrpcService<Body>>::ResponseBody;
     type Error = super::error::Error<TokenError>;
@@ -98,10 +297,26 @@ where
         if let Ok(request_id) = new_request_id() {
             req.headers_mut().insert(KEY_X_REQUEST_ID, request_id);
         }
+        let retry_index = RetryIndex::default();
+        req.extensions_mut().insert(retry_index.clone());
+        let replay_buffer_limit = self.replay_buffer_limit;
 
         // Clone the `backoff` so that new requests don't reuse it
         // and so that the `backoff` can be moved into the async closure.
-        let mut backoff = self.backoff.clone();
+        let mut backoff = match self.method_policies.get(req.uri().path()) {
+            None => Some(self.backoff.clone()),
+            Some(MethodRetryPolicy::Backoff(backoff)) => Some(backoff.clone()),
+            Some(MethodRetryPolicy::Never) => None,
+        };
+        let retryable_codes = self.retryable_codes.clone();
+        let retry_settings = self.retry_settings.clone();
+        #[cfg(feature = "metrics")]
+        let operation = req.uri().path().to_string();
+        let deadline = req
+            .headers()
+            .get(KEY_GRPC_TIMEOUT)
+            .and_then(parse_grpc_timeout)
+            .map(|timeout| Instant::now() + timeout);
         let mut service = self.service.clone();
         // It is necessary to replace self.service with the above clone
         // because the cloned version may not be "ready".
@@ -111,52 +326,134 @@ where
         std::mem::swap(&mut self.service, &mut service);
 
         super::common::pin_future_with_otel_context_if_available(async move {
-            let mut attempt = 0;
+            let (req, mut request) = ReplayableRequest::new(req, replay_buffer_limit);
             loop {
-                let (mut request, retained) = build_duplicate_request(req).await?;
-                req = retained;
-
                 // Ensure that the service is ready before trying to use it.
                 // Failure to do this *will* cause a panic.
                 poll_fn(|cx| -> Poll<Result<(), _>> { service.poll_ready(cx) })
                     .await
                     .map_err(super::error::Error::from)?;
 
-                if let Ok(retry_index_header_value) =
-                    qcs_dependencies_client::http::HeaderValue::from_str(
-                        attempt.to_string().as_str(),
-                    )
-                {
-                    request
-                        .headers_mut()
-                        .insert(KEY_X_REQUEST_RETRY_INDEX, retry_index_header_value);
+                request
+                    .headers_mut()
+                    .insert(KEY_X_REQUEST_RETRY_INDEX, retry_index.next().into());
+                // Each attempt only gets the time remaining until the original deadline.
+                if let Some(timeout) = deadline.and_then(|deadline| {
+                    encode_grpc_timeout(deadline.saturating_duration_since(Instant::now())).ok()
+                }) {
+                    request.headers_mut().insert(KEY_GRPC_TIMEOUT, timeout);
                 }
-                let duration = match service.call(request).await {
-                    Ok(response) => {
-                        if let Some(duration) = duration_from_response(&response, &mut backoff) {
-                            duration
-                        } else {
-                            break Ok(response);
-                        }
-                    }
+                let response = match service.call(request).await {
+                    Ok(response) => response,
                     Err(error) => break Err(super::error::Error::from(error)),
                 };
+                // A request which can't be replayed is passed through untouched.
+                let Some(next_request) = req.request() else {
+                    break Ok(response);
+                };
+                let Some(duration) = backoff.as_mut().and_then(|backoff| {
+                    duration_from_response(
+                        &response,
+                        backoff,
+                        &retryable_codes,
+                        &retry_settings,
+                        deadline,
+                    )
+                }) else {
+                    break Ok(response);
+                };
+                #[cfg(feature = "metrics")]
+                record_retry(&operation, &response);
+                drop(response);
 
                 tokio::time::sleep(duration).await;
-                attempt += 1;
+                request = next_request;
             }
         })
     }
 }
 
-fn new_request_id() -> Result<HeaderValue, qcs_dependencies_client::http::header::InvalidHeaderValue>
-{
+/// Record a retry of `operation` after `response`, labelled with its gRPC status code name, or
+/// with its HTTP status code if it has no gRPC status.
+#[cfg(feature = "metrics")]
+fn record_retry<T>(operation: &str, response: &Response<T>) {
+    use qcs_api_client_common::metrics::{Client, record_retry};
+
+    let cause = Status::from_header_map(response.headers()).map_or_else(
+        || response.status().as_str().to_string(),
+        |status| format!("{:?}", status.code()),
+    );
+    record_retry(Client::Grpc, operation.to_string(), cause);
+}
+
+/// Parse a `grpc-timeout` header value, e.g. `100m` for 100 milliseconds.
+fn parse_grpc_timeout(value: &HeaderValue) -> Option<Duration> {
+    // `to_str` only succeeds for visible ASCII, so it is safe to split at any index.
+    let value = value.to_str().ok()?;
+    let (amount, unit) = value.split_at(value.len().checked_sub(1)?);
+    // The timeout is at most 8 digits long.
+    if amount.is_empty() || amount.len() > 8 {
+        return None;
+    }
+    let amount = amount.parse::<u64>().ok()?;
+    let timeout = match unit {
+        "H" => Duration::from_secs(amount * 60 * 60),
+        "M" => Duration::from_secs(amount * 60),
+        "S" => Duration::from_secs(amount),
+        "m" => Duration::from_millis(amount),
+        "u" => Duration::from_micros(amount),
+        "n" => Duration::from_nanos(amount),
+        _ => return None,
+    };
+    Some(timeout)
+}
+
+/// Encode `timeout` as a `grpc-timeout` header value, using the most precise unit that fits.
+fn encode_grpc_timeout(
+    timeout: Duration,
+) -> Result<HeaderValue, qcs_dependencies_client::http::header::InvalidHeaderValue> {
+    const MAX_AMOUNT: u128 = 99_999_999;
+
+    let value = if timeout.as_micros() <= MAX_AMOUNT {
+        format!("{}u", timeout.as_micros())
+    } else if timeout.as_millis() <= MAX_AMOUNT {
+        format!("{}m", timeout.as_millis())
+    } else {
+        format!("{}S", u128::from(timeout.as_secs()).min(MAX_AMOUNT))
+    };
+    HeaderValue::from_str(&value)
+}
+
+/// The source of the `x-request-retry-index` of each attempt at a request.
+///
+/// It is shared through the request's extensions by every attempt, including the retries of a
+/// [`RetryService`] and the second copies sent by a [`HedgeService`](super::HedgeService), so
+/// that no two attempts are sent with the same index.
+#[derive(Clone, Debug, Default)]
+pub(super) struct RetryIndex(Arc<AtomicU64>);
+
+impl RetryIndex {
+    /// A source whose first index is `index`.
+    pub(super) fn starting_at(index: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(index)))
+    }
+
+    /// Take the next unused index.
+    pub(super) fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+pub(super) fn new_request_id()
+-> Result<HeaderValue, qcs_dependencies_client::http::header::InvalidHeaderValue> {
     let request_id = uuid::Uuid::new_v4().to_string();
     HeaderValue::from_str(request_id.as_str())
 }
 
-const KEY_X_REQUEST_ID: &str = "x-request-id";
-const KEY_X_REQUEST_RETRY_INDEX: &str = "x-request-retry-index";
+pub(super) const KEY_X_REQUEST_ID: &str = "x-request-id";
+pub(super) const KEY_X_REQUEST_RETRY_INDEX: &str = "x-request-retry-index";
+const KEY_GRPC_RETRY_PUSHBACK_MS: &str = "grpc-retry-pushback-ms";
+const KEY_GRPC_TIMEOUT: &str = "grpc-timeout";
 
 #[cfg(test)]
 mod tests {
@@ -177,12 +474,18 @@ mod tests {
 
     struct FlakyHealthService {
         required_tries_count: AtomicUsize,
+        error: fn() -> Status,
     }
 
     impl FlakyHealthService {
         const fn new(required_tries_count: usize) -> Self {
+            Self::with_error(required_tries_count, || Status::unavailable("unavailable"))
+        }
+
+        const fn with_error(required_tries_count: usize, error: fn() -> Status) -> Self {
             Self {
                 required_tries_count: AtomicUsize::new(required_tries_count),
+                error,
             }
         }
 
@@ -200,7 +503,7 @@ mod tests {
             } else {
                 self.required_tries_count
                     .store(remaining - 1, Ordering::SeqCst);
-                Err(Status::unavailable("unavailable"))
+                Err((self.error)())
             }
         }
     }
@@ -234,8 +537,13 @@ mod tests {
             &self,
             _request: Request<qcs_dependencies_client::tonic_health::pb::HealthCheckRequest>,
         ) -> Result<qcs_dependencies_client::tonic::Response<Self::WatchStream>, Status> {
-            let (tx, rx) = tokio::sync::mpsc::channel(1);
-            tx.send(self.make_response()).await.unwrap();
+            // Fail before the first message as `check` does, then interrupt the stream after it.
+            let response = self.make_response()?;
+            let (tx, rx) = tokio::sync::mpsc::channel(2);
+            tx.send(Ok(response)).await.unwrap();
+            tx.send(Err(Status::unavailable("stream interrupted")))
+                .await
+                .unwrap();
             Ok(qcs_dependencies_client::tonic::Response::new(
                 tokio_stream::wrappers::ReceiverStream::new(rx),
             ))
@@ -262,6 +570,42 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_streaming_retries_only_before_first_message() {
+        let health_server = HealthServer::new(FlakyHealthService::new(2));
+
+        uds_grpc_stream::serve(health_server, |channel| async {
+            let mut stream = HealthClient::new(wrap_channel_with_retry(channel))
+                .watch(Request::new(
+                    qcs_dependencies_client::tonic_health::pb::HealthCheckRequest {
+                        service: <HealthServer<HealthService> as NamedService>::NAME.to_string(),
+                    },
+                ))
+                .await
+                .unwrap()
+                .into_inner();
+            let message = stream.message().await.unwrap().unwrap();
+            assert_eq!(message.status(), ServingStatus::Serving);
+            // Once a message has been received, errors are passed through untouched.
+            let status = stream.message().await.unwrap_err();
+            assert_eq!(status.code(), Code::Unavailable);
+            assert_eq!(status.message(), "stream interrupted");
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_request_exceeding_replay_buffer_is_not_retried() {
+        let health_server = HealthServer::new(FlakyHealthService::new(1));
+        let layer = RetryLayer::default().with_replay_buffer_limit(0);
+
+        assert_eq!(
+            check_health(health_server, layer).await,
+            Err(Code::Unavailable)
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_retry_is_not_infinite_long() {
         let health_server = HealthServer::new(FlakyHealthService::new(50));
@@ -273,6 +617,7 @@ mod tests {
                         .with_max_interval(Duration::from_millis(100))
                         .with_max_elapsed_time(Some(Duration::from_secs(1)))
                         .build(),
+                    ..RetryLayer::default()
                 }
                 .layer(channel),
             )
@@ -291,4 +636,215 @@ mod tests {
         .await
         .unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_retry_only_configured_codes() {
+        let health_server = HealthServer::new(FlakyHealthService::new(1));
+
+        uds_grpc_stream::serve(health_server, |channel| async {
+            let layer = RetryLayer::from_retry_settings(RetrySettings {
+                retryable_grpc_codes: vec!["RESOURCE_EXHAUSTED".to_string()],
+                ..RetrySettings::default()
+            });
+            let status = HealthClient::new(layer.layer(channel))
+                .check(Request::new(
+                    qcs_dependencies_client::tonic_health::pb::HealthCheckRequest {
+                        service: <HealthServer<HealthService> as NamedService>::NAME.to_string(),
+                    },
+                ))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), Code::Unavailable);
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_code_from_name() {
+        for name in ["UNAVAILABLE", "unavailable", "Unavailable"] {
+            assert_eq!(code_from_name(name), Some(Code::Unavailable));
+        }
+        assert_eq!(
+            code_from_name("RESOURCE_EXHAUSTED"),
+            Some(Code::ResourceExhausted)
+        );
+        assert_eq!(
+            code_from_name("ResourceExhausted"),
+            Some(Code::ResourceExhausted)
+        );
+        assert_eq!(code_from_name("NOT_A_CODE"), None);
+    }
+
+    fn status_with_metadata(code: Code, key: &'static str, value: &'static str) -> Status {
+        let mut status = Status::new(code, "pushback");
+        status
+            .metadata_mut()
+            .insert(key, value.parse().expect("should be valid metadata"));
+        status
+    }
+
+    async fn check_health(
+        health_server: HealthServer<FlakyHealthService>,
+        layer: RetryLayer,
+    ) -> Result<ServingStatus, Code> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        uds_grpc_stream::serve(health_server, |channel| async move {
+            let result = HealthClient::new(layer.layer(channel))
+                .check(Request::new(
+                    qcs_dependencies_client::tonic_health::pb::HealthCheckRequest {
+                        service: <HealthServer<HealthService> as NamedService>::NAME.to_string(),
+                    },
+                ))
+                .await
+                .map(|response| response.into_inner().status())
+                .map_err(|status| status.code());
+            tx.send(result).unwrap();
+        })
+        .await
+        .unwrap();
+        rx.await.unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_retry_configured_code_after_pushback() {
+        let health_server = HealthServer::new(FlakyHealthService::with_error(1, || {
+            status_with_metadata(Code::ResourceExhausted, KEY_GRPC_RETRY_PUSHBACK_MS, "10")
+        }));
+        let layer = RetryLayer::default()
+            .with_retryable_codes([Code::Unavailable, Code::ResourceExhausted]);
+
+        assert_eq!(
+            check_health(health_server, layer).await,
+            Ok(ServingStatus::Serving)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_negative_pushback_prevents_retry() {
+        let health_server = HealthServer::new(FlakyHealthService::with_error(1, || {
+            status_with_metadata(Code::Unavailable, KEY_GRPC_RETRY_PUSHBACK_MS, "-1")
+        }));
+
+        assert_eq!(
+            check_health(health_server, RetryLayer::default()).await,
+            Err(Code::Unavailable)
+        );
+    }
+
+    #[test]
+    fn test_pushback_from_retry_info() {
+        let retry_info = crate::tonic::RetryInfo {
+            retry_delay: Some(qcs_dependencies_client::pbjson_types::Duration {
+                seconds: 0,
+                nanos: 10_000_000,
+            }),
+        };
+        let status = crate::tonic::error_details::testing::status_with_details(
+            Code::ResourceExhausted,
+            "slow down",
+            vec![(
+                "google.rpc.RetryInfo",
+                qcs_dependencies_client::prost::Message::encode_to_vec(&retry_info),
+            )],
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        assert_eq!(
+            Pushback::from_status(&status, &headers),
+            Pushback::Delay(Duration::from_millis(10))
+        );
+
+        // Explicit pushback metadata takes precedence.
+        headers.insert(KEY_GRPC_RETRY_PUSHBACK_MS, HeaderValue::from_static("-1"));
+        assert_eq!(
+            Pushback::from_status(&status, &headers),
+            Pushback::DoNotRetry
+        );
+    }
+
+    #[test]
+    fn test_pushback_from_metadata() {
+        let pushback = |key: &'static str, value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(key, HeaderValue::from_static(value));
+            Pushback::from_metadata(&headers)
+        };
+
+        assert_eq!(Pushback::from_metadata(&HeaderMap::new()), Pushback::None);
+        assert_eq!(
+            pushback(KEY_GRPC_RETRY_PUSHBACK_MS, "250"),
+            Pushback::Delay(Duration::from_millis(250))
+        );
+        assert_eq!(
+            pushback(KEY_GRPC_RETRY_PUSHBACK_MS, "-1"),
+            Pushback::DoNotRetry
+        );
+        assert_eq!(
+            pushback(KEY_GRPC_RETRY_PUSHBACK_MS, "soon"),
+            Pushback::DoNotRetry
+        );
+        assert_eq!(
+            pushback("retry-after", "2"),
+            Pushback::Delay(Duration::from_secs(2))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_method_policy_never_retries() {
+        let health_server = HealthServer::new(FlakyHealthService::new(1));
+        let layer = RetryLayer::default()
+            .with_method_policy("/grpc.health.v1.Health/Check", MethodRetryPolicy::Never);
+
+        assert_eq!(
+            check_health(health_server, layer).await,
+            Err(Code::Unavailable)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_retries_stop_at_request_deadline() {
+        let health_server = HealthServer::new(FlakyHealthService::new(50));
+
+        uds_grpc_stream::serve(health_server, |channel| async {
+            let mut request = Request::new(
+                qcs_dependencies_client::tonic_health::pb::HealthCheckRequest {
+                    service: <HealthServer<HealthService> as NamedService>::NAME.to_string(),
+                },
+            );
+            request.set_timeout(Duration::from_millis(500));
+
+            let start = std::time::Instant::now();
+            // The default backoff would keep retrying for minutes.
+            HealthClient::new(RetryLayer::default().layer(channel))
+                .check(request)
+                .await
+                .unwrap_err();
+            assert!(start.elapsed() < Duration::from_secs(2));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_grpc_timeout_encoding() {
+        let parse = |value: &'static str| parse_grpc_timeout(&HeaderValue::from_static(value));
+
+        assert_eq!(parse("100m"), Some(Duration::from_millis(100)));
+        assert_eq!(parse("2S"), Some(Duration::from_secs(2)));
+        assert_eq!(parse("1H"), Some(Duration::from_secs(60 * 60)));
+        assert_eq!(parse("m"), None);
+        assert_eq!(parse("100"), None);
+        assert_eq!(parse("123456789m"), None);
+
+        for timeout in [
+            Duration::from_micros(1500),
+            Duration::from_secs(200),
+            Duration::from_secs(200_000),
+        ] {
+            let encoded = encode_grpc_timeout(timeout).unwrap();
+            assert_eq!(parse_grpc_timeout(&encoded), Some(timeout));
+        }
+    }
 }
@@ -14,13 +14,21 @@
 
 use qcs_api_client_common::ClientConfiguration;
 use qcs_api_client_grpc::client_configuration::TokenError;
+#[cfg(any(feature = "compression", feature = "grpc-web-fallback"))]
+use qcs_api_client_grpc::services::controller::controller_client::ControllerClient;
 use qcs_api_client_grpc::services::translation::translation_client::TranslationClient;
 use qcs_api_client_grpc::tonic::{
     Error, get_channel, parse_uri, wrap_channel_with, wrap_channel_with_retry,
 };
 
+#[cfg(feature = "grpc-web-fallback")]
+use qcs_api_client_grpc::tonic::get_fallback_channel;
 #[cfg(feature = "grpc-web")]
 use qcs_api_client_grpc::tonic::wrap_channel_with_grpc_web;
+#[cfg(feature = "compression")]
+use qcs_api_client_grpc::tonic::{
+    ChannelBuilder, CompressionConfig, wrap_channel_with_compression,
+};
 
 #[allow(dead_code)]
 async fn build_client() -> Result<(), Error<TokenError>> {
@@ -35,3 +43,38 @@ async fn build_client() -> Result<(), Error<TokenError>> {
 
     Ok(())
 }
+
+#[cfg(feature = "grpc-web-fallback")]
+#[allow(dead_code, clippy::result_large_err)]
+fn build_fallback_client() -> Result<(), Error<TokenError>> {
+    let config = ClientConfiguration::load_default()?;
+
+    let service = wrap_channel_with_retry(wrap_channel_with(
+        get_fallback_channel(parse_uri("")?)?,
+        config,
+    ));
+    let _client = ControllerClient::new(service);
+
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+#[allow(dead_code, clippy::result_large_err)]
+fn build_compressed_clients() -> Result<(), Error<TokenError>> {
+    let config = ClientConfiguration::load_default()?;
+
+    let service = wrap_channel_with_compression(
+        wrap_channel_with(get_channel(parse_uri("")?)?, config.clone()),
+        CompressionConfig::gzip(),
+    );
+    let _client = TranslationClient::new(service);
+
+    let service = ChannelBuilder::from_uri(parse_uri("")?)
+        .with_qcs_config(config)
+        .with_default_retry()
+        .with_compression(CompressionConfig::gzip())
+        .build()?;
+    let _client = ControllerClient::new(service);
+
+    Ok(())
+}
@@ -23,10 +23,16 @@
  */
 
 use super::{ContentType, Error, configuration};
-use crate::{apis::ResponseContent, models};
+use crate::{
+    apis::{ApiError, ResponseContent},
+    models,
+};
 use ::qcs_api_client_common::backoff::{
-    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error, duration_from_response,
+    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error,
+    duration_from_response_with_settings,
 };
+#[cfg(feature = "metrics")]
+use ::qcs_api_client_common::backoff::{io_error_retry_cause, reqwest_error_retry_cause};
 #[cfg(feature = "tracing")]
 use qcs_api_client_common::configuration::tokens::TokenRefresher;
 use qcs_dependencies_client::reqwest::{self, StatusCode};
@@ -839,6 +845,7 @@ pub enum UpdateViewerUserProfileError {
 async fn activate_user_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     activate_user_request: Option<crate::models::ActivateUserRequest>,
 ) -> Result<models::User, Error<ActivateUserError>> {
     let local_var_configuration = configuration;
@@ -871,6 +878,8 @@ async fn activate_user_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making activate_user request",
             );
         }
@@ -888,10 +897,7 @@ async fn activate_user_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -906,8 +912,15 @@ async fn activate_user_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_activate_user_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("activate_user", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -920,6 +933,8 @@ async fn activate_user_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -935,16 +950,25 @@ async fn activate_user_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ActivateUserError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -956,11 +980,17 @@ pub async fn activate_user(
     activate_user_request: Option<crate::models::ActivateUserRequest>,
 ) -> Result<models::User, Error<ActivateUserError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
-        let result =
-            activate_user_inner(configuration, &mut backoff, activate_user_request.clone()).await;
+        let result = activate_user_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            activate_user_request.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -971,6 +1001,22 @@ pub async fn activate_user(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -994,6 +1040,8 @@ pub async fn activate_user(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("activate_user", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1002,19 +1050,29 @@ pub async fn activate_user(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "activate_user",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "activate_user",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -1023,6 +1081,7 @@ pub async fn activate_user(
 async fn add_group_user_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     add_group_user_request: crate::models::AddGroupUserRequest,
 ) -> Result<(), Error<AddGroupUserError>> {
     let local_var_configuration = configuration;
@@ -1055,6 +1114,8 @@ async fn add_group_user_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making add_group_user request",
             );
         }
@@ -1072,10 +1133,7 @@ async fn add_group_user_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1090,24 +1148,40 @@ async fn add_group_user_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_add_group_user_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("add_group_user", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<AddGroupUserError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -1119,11 +1193,17 @@ pub async fn add_group_user(
     add_group_user_request: crate::models::AddGroupUserRequest,
 ) -> Result<(), Error<AddGroupUserError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
-        let result =
-            add_group_user_inner(configuration, &mut backoff, add_group_user_request.clone()).await;
+        let result = add_group_user_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            add_group_user_request.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -1134,6 +1214,22 @@ pub async fn add_group_user(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -1157,6 +1253,8 @@ pub async fn add_group_user(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("add_group_user", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1165,19 +1263,29 @@ pub async fn add_group_user(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "add_group_user",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "add_group_user",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -1186,6 +1294,7 @@ pub async fn add_group_user(
 async fn dismiss_viewer_announcement_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     announcement_id: i64,
 ) -> Result<(), Error<DismissViewerAnnouncementError>> {
     let local_var_configuration = configuration;
@@ -1219,6 +1328,8 @@ async fn dismiss_viewer_announcement_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="DELETE",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making dismiss_viewer_announcement request",
             );
         }
@@ -1236,10 +1347,7 @@ async fn dismiss_viewer_announcement_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1252,24 +1360,41 @@ async fn dismiss_viewer_announcement_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("dismiss_viewer_announcement", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<DismissViewerAnnouncementError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -1281,12 +1406,17 @@ pub async fn dismiss_viewer_announcement(
     announcement_id: i64,
 ) -> Result<(), Error<DismissViewerAnnouncementError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::DELETE;
     loop {
-        let result =
-            dismiss_viewer_announcement_inner(configuration, &mut backoff, announcement_id.clone())
-                .await;
+        let result = dismiss_viewer_announcement_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            announcement_id.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -1297,6 +1427,22 @@ pub async fn dismiss_viewer_announcement(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -1320,6 +1466,8 @@ pub async fn dismiss_viewer_announcement(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("dismiss_viewer_announcement", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1328,19 +1476,29 @@ pub async fn dismiss_viewer_announcement(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "dismiss_viewer_announcement",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "dismiss_viewer_announcement",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -1349,6 +1507,7 @@ pub async fn dismiss_viewer_announcement(
 async fn get_group_balance_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     group_name: &str,
 ) -> Result<models::AccountBalance, Error<GetGroupBalanceError>> {
     let local_var_configuration = configuration;
@@ -1382,6 +1541,8 @@ async fn get_group_balance_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_group_balance request",
             );
         }
@@ -1399,10 +1560,7 @@ async fn get_group_balance_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1415,8 +1573,15 @@ async fn get_group_balance_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("get_group_balance", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -1429,6 +1594,8 @@ async fn get_group_balance_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -1444,16 +1611,25 @@ async fn get_group_balance_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetGroupBalanceError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -1465,10 +1641,17 @@ pub async fn get_group_balance(
     group_name: &str,
 ) -> Result<models::AccountBalance, Error<GetGroupBalanceError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = get_group_balance_inner(configuration, &mut backoff, group_name.clone()).await;
+        let result = get_group_balance_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            group_name.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -1479,6 +1662,22 @@ pub async fn get_group_balance(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -1502,6 +1701,8 @@ pub async fn get_group_balance(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_group_balance", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1510,19 +1711,29 @@ pub async fn get_group_balance(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_group_balance",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_group_balance",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -1531,6 +1742,7 @@ pub async fn get_group_balance(
 async fn get_group_billing_customer_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     group_name: &str,
 ) -> Result<models::BillingCustomer, Error<GetGroupBillingCustomerError>> {
     let local_var_configuration = configuration;
@@ -1564,6 +1776,8 @@ async fn get_group_billing_customer_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_group_billing_customer request",
             );
         }
@@ -1581,10 +1795,7 @@ async fn get_group_billing_customer_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1597,8 +1808,16 @@ async fn get_group_billing_customer_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("get_group_billing_customer", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -1611,6 +1830,8 @@ async fn get_group_billing_customer_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -1626,16 +1847,25 @@ async fn get_group_billing_customer_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetGroupBillingCustomerError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -1647,11 +1877,17 @@ pub async fn get_group_billing_customer(
     group_name: &str,
 ) -> Result<models::BillingCustomer, Error<GetGroupBillingCustomerError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result =
-            get_group_billing_customer_inner(configuration, &mut backoff, group_name.clone()).await;
+        let result = get_group_billing_customer_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            group_name.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -1662,6 +1898,22 @@ pub async fn get_group_billing_customer(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -1685,6 +1937,8 @@ pub async fn get_group_billing_customer(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_group_billing_customer", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1693,19 +1947,29 @@ pub async fn get_group_billing_customer(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_group_billing_customer",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_group_billing_customer",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -1714,6 +1978,7 @@ pub async fn get_group_billing_customer(
 async fn get_group_upcoming_billing_invoice_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     group_name: &str,
 ) -> Result<models::BillingUpcomingInvoice, Error<GetGroupUpcomingBillingInvoiceError>> {
     let local_var_configuration = configuration;
@@ -1747,6 +2012,8 @@ async fn get_group_upcoming_billing_invoice_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_group_upcoming_billing_invoice request",
             );
         }
@@ -1764,10 +2031,7 @@ async fn get_group_upcoming_billing_invoice_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1780,8 +2044,16 @@ async fn get_group_upcoming_billing_invoice_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("get_group_upcoming_billing_invoice", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -1794,6 +2066,8 @@ async fn get_group_upcoming_billing_invoice_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -1809,16 +2083,25 @@ async fn get_group_upcoming_billing_invoice_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetGroupUpcomingBillingInvoiceError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -1830,12 +2113,14 @@ pub async fn get_group_upcoming_billing_invoice(
     group_name: &str,
 ) -> Result<models::BillingUpcomingInvoice, Error<GetGroupUpcomingBillingInvoiceError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = get_group_upcoming_billing_invoice_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             group_name.clone(),
         )
         .await;
@@ -1849,6 +2134,22 @@ pub async fn get_group_upcoming_billing_invoice(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -1872,6 +2173,11 @@ pub async fn get_group_upcoming_billing_invoice(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_group_upcoming_billing_invoice",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1880,19 +2186,29 @@ pub async fn get_group_upcoming_billing_invoice(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_group_upcoming_billing_invoice",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_group_upcoming_billing_invoice",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -1901,6 +2217,7 @@ pub async fn get_group_upcoming_billing_invoice(
 async fn get_user_balance_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
 ) -> Result<models::AccountBalance, Error<GetUserBalanceError>> {
     let local_var_configuration = configuration;
@@ -1934,6 +2251,8 @@ async fn get_user_balance_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_user_balance request",
             );
         }
@@ -1951,10 +2270,7 @@ async fn get_user_balance_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1967,8 +2283,15 @@ async fn get_user_balance_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("get_user_balance", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -1981,6 +2304,8 @@ async fn get_user_balance_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -1996,16 +2321,25 @@ async fn get_user_balance_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetUserBalanceError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -2017,10 +2351,17 @@ pub async fn get_user_balance(
     user_id: &str,
 ) -> Result<models::AccountBalance, Error<GetUserBalanceError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = get_user_balance_inner(configuration, &mut backoff, user_id.clone()).await;
+        let result = get_user_balance_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            user_id.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -2031,6 +2372,22 @@ pub async fn get_user_balance(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -2054,6 +2411,8 @@ pub async fn get_user_balance(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_user_balance", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -2062,19 +2421,29 @@ pub async fn get_user_balance(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_balance",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_balance",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -2083,6 +2452,7 @@ pub async fn get_user_balance(
 async fn get_user_billing_customer_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
 ) -> Result<models::BillingCustomer, Error<GetUserBillingCustomerError>> {
     let local_var_configuration = configuration;
@@ -2116,6 +2486,8 @@ async fn get_user_billing_customer_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_user_billing_customer request",
             );
         }
@@ -2133,10 +2505,7 @@ async fn get_user_billing_customer_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -2149,8 +2518,16 @@ async fn get_user_billing_customer_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("get_user_billing_customer", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -2163,6 +2540,8 @@ async fn get_user_billing_customer_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -2178,16 +2557,25 @@ async fn get_user_billing_customer_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetUserBillingCustomerError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -2199,11 +2587,17 @@ pub async fn get_user_billing_customer(
     user_id: &str,
 ) -> Result<models::BillingCustomer, Error<GetUserBillingCustomerError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result =
-            get_user_billing_customer_inner(configuration, &mut backoff, user_id.clone()).await;
+        let result = get_user_billing_customer_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            user_id.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -2214,6 +2608,22 @@ pub async fn get_user_billing_customer(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -2237,6 +2647,8 @@ pub async fn get_user_billing_customer(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_user_billing_customer", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -2245,19 +2657,29 @@ pub async fn get_user_billing_customer(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_billing_customer",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_billing_customer",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -2266,6 +2688,7 @@ pub async fn get_user_billing_customer(
 async fn get_user_event_billing_price_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
     get_account_event_billing_price_request: crate::models::GetAccountEventBillingPriceRequest,
 ) -> Result<models::EventBillingPriceRate, Error<GetUserEventBillingPriceError>> {
@@ -2301,6 +2724,8 @@ async fn get_user_event_billing_price_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_user_event_billing_price request",
             );
         }
@@ -2318,10 +2743,7 @@ async fn get_user_event_billing_price_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -2337,8 +2759,16 @@ async fn get_user_event_billing_price_inner(
     local_var_req_builder =
         local_var_req_builder.json(&p_body_get_account_event_billing_price_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("get_user_event_billing_price", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -2351,6 +2781,8 @@ async fn get_user_event_billing_price_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -2366,16 +2798,25 @@ async fn get_user_event_billing_price_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetUserEventBillingPriceError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -2388,12 +2829,14 @@ pub async fn get_user_event_billing_price(
     get_account_event_billing_price_request: crate::models::GetAccountEventBillingPriceRequest,
 ) -> Result<models::EventBillingPriceRate, Error<GetUserEventBillingPriceError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = get_user_event_billing_price_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             user_id.clone(),
             get_account_event_billing_price_request.clone(),
         )
@@ -2408,6 +2851,22 @@ pub async fn get_user_event_billing_price(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -2431,6 +2890,8 @@ pub async fn get_user_event_billing_price(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_user_event_billing_price", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -2439,19 +2900,29 @@ pub async fn get_user_event_billing_price(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_event_billing_price",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_event_billing_price",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -2460,6 +2931,7 @@ pub async fn get_user_event_billing_price(
 async fn get_user_upcoming_billing_invoice_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
 ) -> Result<models::BillingUpcomingInvoice, Error<GetUserUpcomingBillingInvoiceError>> {
     let local_var_configuration = configuration;
@@ -2493,6 +2965,8 @@ async fn get_user_upcoming_billing_invoice_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_user_upcoming_billing_invoice request",
             );
         }
@@ -2510,10 +2984,7 @@ async fn get_user_upcoming_billing_invoice_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -2526,8 +2997,16 @@ async fn get_user_upcoming_billing_invoice_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("get_user_upcoming_billing_invoice", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -2540,6 +3019,8 @@ async fn get_user_upcoming_billing_invoice_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -2555,16 +3036,25 @@ async fn get_user_upcoming_billing_invoice_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetUserUpcomingBillingInvoiceError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -2576,12 +3066,17 @@ pub async fn get_user_upcoming_billing_invoice(
     user_id: &str,
 ) -> Result<models::BillingUpcomingInvoice, Error<GetUserUpcomingBillingInvoiceError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result =
-            get_user_upcoming_billing_invoice_inner(configuration, &mut backoff, user_id.clone())
-                .await;
+        let result = get_user_upcoming_billing_invoice_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            user_id.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -2592,6 +3087,22 @@ pub async fn get_user_upcoming_billing_invoice(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -2615,6 +3126,11 @@ pub async fn get_user_upcoming_billing_invoice(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_upcoming_billing_invoice",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -2623,19 +3139,29 @@ pub async fn get_user_upcoming_billing_invoice(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_upcoming_billing_invoice",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_user_upcoming_billing_invoice",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -2644,6 +3170,7 @@ pub async fn get_user_upcoming_billing_invoice(
 async fn get_viewer_user_onboarding_completed_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
 ) -> Result<models::ViewerUserOnboardingCompleted, Error<GetViewerUserOnboardingCompletedError>> {
     let local_var_configuration = configuration;
 
@@ -2673,6 +3200,8 @@ async fn get_viewer_user_onboarding_completed_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_viewer_user_onboarding_completed request",
             );
         }
@@ -2690,10 +3219,7 @@ async fn get_viewer_user_onboarding_completed_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -2706,8 +3232,16 @@ async fn get_viewer_user_onboarding_completed_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("get_viewer_user_onboarding_completed", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -2720,6 +3254,8 @@ async fn get_viewer_user_onboarding_completed_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -2735,16 +3271,25 @@ async fn get_viewer_user_onboarding_completed_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetViewerUserOnboardingCompletedError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -2755,10 +3300,16 @@ pub async fn get_viewer_user_onboarding_completed(
     configuration: &configuration::Configuration,
 ) -> Result<models::ViewerUserOnboardingCompleted, Error<GetViewerUserOnboardingCompletedError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = get_viewer_user_onboarding_completed_inner(configuration, &mut backoff).await;
+        let result = get_viewer_user_onboarding_completed_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -2769,6 +3320,22 @@ pub async fn get_viewer_user_onboarding_completed(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -2792,6 +3359,11 @@ pub async fn get_viewer_user_onboarding_completed(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_viewer_user_onboarding_completed",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -2800,19 +3372,29 @@ pub async fn get_viewer_user_onboarding_completed(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_viewer_user_onboarding_completed",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_viewer_user_onboarding_completed",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -2821,6 +3403,7 @@ pub async fn get_viewer_user_onboarding_completed(
 async fn list_group_billing_invoice_lines_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     group_name: &str,
     billing_invoice_id: &str,
     page_token: Option<&str>,
@@ -2862,6 +3445,8 @@ async fn list_group_billing_invoice_lines_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_group_billing_invoice_lines request",
             );
         }
@@ -2888,10 +3473,7 @@ async fn list_group_billing_invoice_lines_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -2904,8 +3486,16 @@ async fn list_group_billing_invoice_lines_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_group_billing_invoice_lines", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -2918,6 +3508,8 @@ async fn list_group_billing_invoice_lines_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -2933,16 +3525,25 @@ async fn list_group_billing_invoice_lines_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListGroupBillingInvoiceLinesError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -2958,12 +3559,14 @@ pub async fn list_group_billing_invoice_lines(
 ) -> Result<models::ListAccountBillingInvoiceLinesResponse, Error<ListGroupBillingInvoiceLinesError>>
 {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_group_billing_invoice_lines_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             group_name.clone(),
             billing_invoice_id.clone(),
             page_token.clone(),
@@ -2980,6 +3583,22 @@ pub async fn list_group_billing_invoice_lines(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -3003,6 +3622,11 @@ pub async fn list_group_billing_invoice_lines(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_billing_invoice_lines",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -3011,19 +3635,29 @@ pub async fn list_group_billing_invoice_lines(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_billing_invoice_lines",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_billing_invoice_lines",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -3032,6 +3666,7 @@ pub async fn list_group_billing_invoice_lines(
 async fn list_group_billing_invoices_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     group_name: &str,
     page_token: Option<&str>,
     page_size: Option<i64>,
@@ -3069,6 +3704,8 @@ async fn list_group_billing_invoices_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_group_billing_invoices request",
             );
         }
@@ -3095,10 +3732,7 @@ async fn list_group_billing_invoices_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -3111,8 +3745,16 @@ async fn list_group_billing_invoices_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_group_billing_invoices", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -3125,6 +3767,8 @@ async fn list_group_billing_invoices_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -3140,16 +3784,25 @@ async fn list_group_billing_invoices_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListGroupBillingInvoicesError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -3163,12 +3816,14 @@ pub async fn list_group_billing_invoices(
     page_size: Option<i64>,
 ) -> Result<models::ListAccountBillingInvoicesResponse, Error<ListGroupBillingInvoicesError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_group_billing_invoices_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             group_name.clone(),
             page_token.clone(),
             page_size.clone(),
@@ -3184,6 +3839,22 @@ pub async fn list_group_billing_invoices(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -3207,6 +3878,8 @@ pub async fn list_group_billing_invoices(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("list_group_billing_invoices", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -3215,19 +3888,29 @@ pub async fn list_group_billing_invoices(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_billing_invoices",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_billing_invoices",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -3236,6 +3919,7 @@ pub async fn list_group_billing_invoices(
 async fn list_group_upcoming_billing_invoice_lines_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     group_name: &str,
     page_token: Option<&str>,
     page_size: Option<i64>,
@@ -3276,6 +3960,8 @@ async fn list_group_upcoming_billing_invoice_lines_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_group_upcoming_billing_invoice_lines request",
             );
         }
@@ -3302,10 +3988,7 @@ async fn list_group_upcoming_billing_invoice_lines_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -3318,8 +4001,16 @@ async fn list_group_upcoming_billing_invoice_lines_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_group_upcoming_billing_invoice_lines", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -3332,6 +4023,8 @@ async fn list_group_upcoming_billing_invoice_lines_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -3347,16 +4040,25 @@ async fn list_group_upcoming_billing_invoice_lines_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListGroupUpcomingBillingInvoiceLinesError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -3373,12 +4075,14 @@ pub async fn list_group_upcoming_billing_invoice_lines(
     Error<ListGroupUpcomingBillingInvoiceLinesError>,
 > {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_group_upcoming_billing_invoice_lines_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             group_name.clone(),
             page_token.clone(),
             page_size.clone(),
@@ -3394,6 +4098,22 @@ pub async fn list_group_upcoming_billing_invoice_lines(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -3417,6 +4137,11 @@ pub async fn list_group_upcoming_billing_invoice_lines(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_upcoming_billing_invoice_lines",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -3425,19 +4150,29 @@ pub async fn list_group_upcoming_billing_invoice_lines(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_upcoming_billing_invoice_lines",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_upcoming_billing_invoice_lines",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -3446,6 +4181,7 @@ pub async fn list_group_upcoming_billing_invoice_lines(
 async fn list_group_users_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     group_name: &str,
     page_size: Option<i64>,
     page_token: Option<&str>,
@@ -3483,6 +4219,8 @@ async fn list_group_users_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_group_users request",
             );
         }
@@ -3509,10 +4247,7 @@ async fn list_group_users_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -3525,8 +4260,15 @@ async fn list_group_users_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("list_group_users", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -3539,6 +4281,8 @@ async fn list_group_users_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -3554,16 +4298,25 @@ async fn list_group_users_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListGroupUsersError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -3577,12 +4330,14 @@ pub async fn list_group_users(
     page_token: Option<&str>,
 ) -> Result<models::ListGroupUsersResponse, Error<ListGroupUsersError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_group_users_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             group_name.clone(),
             page_size.clone(),
             page_token.clone(),
@@ -3598,6 +4353,22 @@ pub async fn list_group_users(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -3621,6 +4392,8 @@ pub async fn list_group_users(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("list_group_users", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -3629,19 +4402,29 @@ pub async fn list_group_users(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_users",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_group_users",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -3650,6 +4433,7 @@ pub async fn list_group_users(
 async fn list_user_billing_invoice_lines_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
     billing_invoice_id: &str,
     page_token: Option<&str>,
@@ -3691,6 +4475,8 @@ async fn list_user_billing_invoice_lines_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_user_billing_invoice_lines request",
             );
         }
@@ -3717,10 +4503,7 @@ async fn list_user_billing_invoice_lines_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -3733,8 +4516,16 @@ async fn list_user_billing_invoice_lines_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_user_billing_invoice_lines", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -3747,6 +4538,8 @@ async fn list_user_billing_invoice_lines_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -3762,16 +4555,25 @@ async fn list_user_billing_invoice_lines_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListUserBillingInvoiceLinesError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -3787,12 +4589,14 @@ pub async fn list_user_billing_invoice_lines(
 ) -> Result<models::ListAccountBillingInvoiceLinesResponse, Error<ListUserBillingInvoiceLinesError>>
 {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_user_billing_invoice_lines_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             user_id.clone(),
             billing_invoice_id.clone(),
             page_token.clone(),
@@ -3809,6 +4613,22 @@ pub async fn list_user_billing_invoice_lines(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -3832,6 +4652,11 @@ pub async fn list_user_billing_invoice_lines(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_billing_invoice_lines",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -3840,19 +4665,29 @@ pub async fn list_user_billing_invoice_lines(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_billing_invoice_lines",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_billing_invoice_lines",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -3861,6 +4696,7 @@ pub async fn list_user_billing_invoice_lines(
 async fn list_user_billing_invoices_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
     page_token: Option<&str>,
     page_size: Option<i64>,
@@ -3898,6 +4734,8 @@ async fn list_user_billing_invoices_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_user_billing_invoices request",
             );
         }
@@ -3924,10 +4762,7 @@ async fn list_user_billing_invoices_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -3940,8 +4775,16 @@ async fn list_user_billing_invoices_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_user_billing_invoices", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -3954,6 +4797,8 @@ async fn list_user_billing_invoices_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -3969,16 +4814,25 @@ async fn list_user_billing_invoices_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListUserBillingInvoicesError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -3992,12 +4846,14 @@ pub async fn list_user_billing_invoices(
     page_size: Option<i64>,
 ) -> Result<models::ListAccountBillingInvoicesResponse, Error<ListUserBillingInvoicesError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_user_billing_invoices_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             user_id.clone(),
             page_token.clone(),
             page_size.clone(),
@@ -4013,6 +4869,22 @@ pub async fn list_user_billing_invoices(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -4036,6 +4908,8 @@ pub async fn list_user_billing_invoices(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("list_user_billing_invoices", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -4044,19 +4918,29 @@ pub async fn list_user_billing_invoices(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_billing_invoices",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_billing_invoices",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -4065,6 +4949,7 @@ pub async fn list_user_billing_invoices(
 async fn list_user_groups_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
     page_size: Option<i64>,
     page_token: Option<&str>,
@@ -4102,6 +4987,8 @@ async fn list_user_groups_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_user_groups request",
             );
         }
@@ -4128,10 +5015,7 @@ async fn list_user_groups_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -4144,8 +5028,15 @@ async fn list_user_groups_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("list_user_groups", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -4158,6 +5049,8 @@ async fn list_user_groups_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -4173,16 +5066,25 @@ async fn list_user_groups_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListUserGroupsError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -4196,12 +5098,14 @@ pub async fn list_user_groups(
     page_token: Option<&str>,
 ) -> Result<models::ListGroupsResponse, Error<ListUserGroupsError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_user_groups_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             user_id.clone(),
             page_size.clone(),
             page_token.clone(),
@@ -4217,6 +5121,22 @@ pub async fn list_user_groups(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -4240,6 +5160,8 @@ pub async fn list_user_groups(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("list_user_groups", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -4248,19 +5170,29 @@ pub async fn list_user_groups(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_groups",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_groups",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -4269,6 +5201,7 @@ pub async fn list_user_groups(
 async fn list_user_upcoming_billing_invoice_lines_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     user_id: &str,
     page_token: Option<&str>,
     page_size: Option<i64>,
@@ -4309,6 +5242,8 @@ async fn list_user_upcoming_billing_invoice_lines_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_user_upcoming_billing_invoice_lines request",
             );
         }
@@ -4335,10 +5270,7 @@ async fn list_user_upcoming_billing_invoice_lines_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -4351,8 +5283,16 @@ async fn list_user_upcoming_billing_invoice_lines_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_user_upcoming_billing_invoice_lines", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -4365,6 +5305,8 @@ async fn list_user_upcoming_billing_invoice_lines_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -4380,16 +5322,25 @@ async fn list_user_upcoming_billing_invoice_lines_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListUserUpcomingBillingInvoiceLinesError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -4406,12 +5357,14 @@ pub async fn list_user_upcoming_billing_invoice_lines(
     Error<ListUserUpcomingBillingInvoiceLinesError>,
 > {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_user_upcoming_billing_invoice_lines_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             user_id.clone(),
             page_token.clone(),
             page_size.clone(),
@@ -4427,6 +5380,22 @@ pub async fn list_user_upcoming_billing_invoice_lines(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -4450,6 +5419,11 @@ pub async fn list_user_upcoming_billing_invoice_lines(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_upcoming_billing_invoice_lines",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -4458,19 +5432,29 @@ pub async fn list_user_upcoming_billing_invoice_lines(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_upcoming_billing_invoice_lines",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_user_upcoming_billing_invoice_lines",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -4479,6 +5463,7 @@ pub async fn list_user_upcoming_billing_invoice_lines(
 async fn list_viewer_announcements_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     page_size: Option<i64>,
     page_token: Option<&str>,
     include_dismissed: Option<bool>,
@@ -4515,6 +5500,8 @@ async fn list_viewer_announcements_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_viewer_announcements request",
             );
         }
@@ -4545,10 +5532,7 @@ async fn list_viewer_announcements_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -4561,8 +5545,16 @@ async fn list_viewer_announcements_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_viewer_announcements", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -4575,6 +5567,8 @@ async fn list_viewer_announcements_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -4590,16 +5584,25 @@ async fn list_viewer_announcements_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListViewerAnnouncementsError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -4613,12 +5616,14 @@ pub async fn list_viewer_announcements(
     include_dismissed: Option<bool>,
 ) -> Result<models::AnnouncementsResponse, Error<ListViewerAnnouncementsError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_viewer_announcements_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             page_size.clone(),
             page_token.clone(),
             include_dismissed.clone(),
@@ -4634,6 +5639,22 @@ pub async fn list_viewer_announcements(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -4657,6 +5678,8 @@ pub async fn list_viewer_announcements(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("list_viewer_announcements", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -4665,19 +5688,29 @@ pub async fn list_viewer_announcements(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_viewer_announcements",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_viewer_announcements",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -4686,6 +5719,7 @@ pub async fn list_viewer_announcements(
 async fn put_viewer_user_onboarding_completed_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     viewer_user_onboarding_completed: Option<crate::models::ViewerUserOnboardingCompleted>,
 ) -> Result<models::ViewerUserOnboardingCompleted, Error<PutViewerUserOnboardingCompletedError>> {
     let local_var_configuration = configuration;
@@ -4718,6 +5752,8 @@ async fn put_viewer_user_onboarding_completed_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="PUT",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making put_viewer_user_onboarding_completed request",
             );
         }
@@ -4735,10 +5771,7 @@ async fn put_viewer_user_onboarding_completed_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -4753,8 +5786,16 @@ async fn put_viewer_user_onboarding_completed_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_viewer_user_onboarding_completed);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("put_viewer_user_onboarding_completed", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -4767,6 +5808,8 @@ async fn put_viewer_user_onboarding_completed_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -4782,16 +5825,25 @@ async fn put_viewer_user_onboarding_completed_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<PutViewerUserOnboardingCompletedError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -4803,12 +5855,14 @@ pub async fn put_viewer_user_onboarding_completed(
     viewer_user_onboarding_completed: Option<crate::models::ViewerUserOnboardingCompleted>,
 ) -> Result<models::ViewerUserOnboardingCompleted, Error<PutViewerUserOnboardingCompletedError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::PUT;
     loop {
         let result = put_viewer_user_onboarding_completed_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             viewer_user_onboarding_completed.clone(),
         )
         .await;
@@ -4822,6 +5876,22 @@ pub async fn put_viewer_user_onboarding_completed(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -4845,6 +5915,11 @@ pub async fn put_viewer_user_onboarding_completed(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "put_viewer_user_onboarding_completed",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -4853,19 +5928,29 @@ pub async fn put_viewer_user_onboarding_completed(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "put_viewer_user_onboarding_completed",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "put_viewer_user_onboarding_completed",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -4874,6 +5959,7 @@ pub async fn put_viewer_user_onboarding_completed(
 async fn remove_group_user_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     remove_group_user_request: crate::models::RemoveGroupUserRequest,
 ) -> Result<(), Error<RemoveGroupUserError>> {
     let local_var_configuration = configuration;
@@ -4906,6 +5992,8 @@ async fn remove_group_user_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making remove_group_user request",
             );
         }
@@ -4923,10 +6011,7 @@ async fn remove_group_user_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -4941,24 +6026,40 @@ async fn remove_group_user_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_remove_group_user_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("remove_group_user", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<RemoveGroupUserError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -4970,12 +6071,14 @@ pub async fn remove_group_user(
     remove_group_user_request: crate::models::RemoveGroupUserRequest,
 ) -> Result<(), Error<RemoveGroupUserError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = remove_group_user_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             remove_group_user_request.clone(),
         )
         .await;
@@ -4989,6 +6092,22 @@ pub async fn remove_group_user(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -5012,6 +6131,8 @@ pub async fn remove_group_user(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("remove_group_user", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -5020,19 +6141,29 @@ pub async fn remove_group_user(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "remove_group_user",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "remove_group_user",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -5041,6 +6172,7 @@ pub async fn remove_group_user(
 async fn update_viewer_user_profile_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     update_viewer_user_profile_request: crate::models::UpdateViewerUserProfileRequest,
 ) -> Result<models::User, Error<UpdateViewerUserProfileError>> {
     let local_var_configuration = configuration;
@@ -5073,6 +6205,8 @@ async fn update_viewer_user_profile_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="PUT",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making update_viewer_user_profile request",
             );
         }
@@ -5090,10 +6224,7 @@ async fn update_viewer_user_profile_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -5108,8 +6239,16 @@ async fn update_viewer_user_profile_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_update_viewer_user_profile_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("update_viewer_user_profile", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -5122,6 +6261,8 @@ async fn update_viewer_user_profile_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -5137,16 +6278,25 @@ async fn update_viewer_user_profile_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<UpdateViewerUserProfileError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -5158,12 +6308,14 @@ pub async fn update_viewer_user_profile(
     update_viewer_user_profile_request: crate::models::UpdateViewerUserProfileRequest,
 ) -> Result<models::User, Error<UpdateViewerUserProfileError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::PUT;
     loop {
         let result = update_viewer_user_profile_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             update_viewer_user_profile_request.clone(),
         )
         .await;
@@ -5177,6 +6329,22 @@ pub async fn update_viewer_user_profile(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -5200,6 +6368,8 @@ pub async fn update_viewer_user_profile(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("update_viewer_user_profile", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -5208,19 +6378,29 @@ pub async fn update_viewer_user_profile(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "update_viewer_user_profile",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "update_viewer_user_profile",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -23,10 +23,16 @@
  */
 
 use super::{ContentType, Error, configuration};
-use crate::{apis::ResponseContent, models};
+use crate::{
+    apis::{ApiError, ResponseContent},
+    models,
+};
 use ::qcs_api_client_common::backoff::{
-    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error, duration_from_response,
+    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error,
+    duration_from_response_with_settings,
 };
+#[cfg(feature = "metrics")]
+use ::qcs_api_client_common::backoff::{io_error_retry_cause, reqwest_error_retry_cause};
 #[cfg(feature = "tracing")]
 use qcs_api_client_common::configuration::tokens::TokenRefresher;
 use qcs_dependencies_client::reqwest::{self, StatusCode};
@@ -159,6 +165,7 @@ pub enum AuthResetPasswordWithTokenError {
 async fn auth_email_password_reset_token_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     auth_email_password_reset_token_request: Option<
         crate::models::AuthEmailPasswordResetTokenRequest,
     >,
@@ -193,6 +200,8 @@ async fn auth_email_password_reset_token_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making auth_email_password_reset_token request",
             );
         }
@@ -210,10 +219,7 @@ async fn auth_email_password_reset_token_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -229,24 +235,41 @@ async fn auth_email_password_reset_token_inner(
     local_var_req_builder =
         local_var_req_builder.json(&p_body_auth_email_password_reset_token_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("auth_email_password_reset_token", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<AuthEmailPasswordResetTokenError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -260,12 +283,14 @@ pub async fn auth_email_password_reset_token(
     >,
 ) -> Result<(), Error<AuthEmailPasswordResetTokenError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = auth_email_password_reset_token_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             auth_email_password_reset_token_request.clone(),
         )
         .await;
@@ -279,6 +304,22 @@ pub async fn auth_email_password_reset_token(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -302,6 +343,11 @@ pub async fn auth_email_password_reset_token(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_email_password_reset_token",
+                        response.status.as_str(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -310,19 +356,29 @@ pub async fn auth_email_password_reset_token(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_email_password_reset_token",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_email_password_reset_token",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -331,6 +387,7 @@ pub async fn auth_email_password_reset_token(
 async fn auth_get_user_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
 ) -> Result<models::User, Error<AuthGetUserError>> {
     let local_var_configuration = configuration;
 
@@ -360,6 +417,8 @@ async fn auth_get_user_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making auth_get_user request",
             );
         }
@@ -377,10 +436,7 @@ async fn auth_get_user_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -393,8 +449,15 @@ async fn auth_get_user_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("auth_get_user", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -407,6 +470,8 @@ async fn auth_get_user_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -422,16 +487,25 @@ async fn auth_get_user_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<AuthGetUserError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -442,10 +516,11 @@ pub async fn auth_get_user(
     configuration: &configuration::Configuration,
 ) -> Result<models::User, Error<AuthGetUserError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = auth_get_user_inner(configuration, &mut backoff).await;
+        let result = auth_get_user_inner(configuration, &mut backoff, &mut request_id).await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -456,6 +531,22 @@ pub async fn auth_get_user(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -479,6 +570,8 @@ pub async fn auth_get_user(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("auth_get_user", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -487,19 +580,29 @@ pub async fn auth_get_user(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_get_user",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_get_user",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -508,6 +611,7 @@ pub async fn auth_get_user(
 async fn auth_reset_password_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     auth_reset_password_request: crate::models::AuthResetPasswordRequest,
 ) -> Result<(), Error<AuthResetPasswordError>> {
     let local_var_configuration = configuration;
@@ -540,6 +644,8 @@ async fn auth_reset_password_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making auth_reset_password request",
             );
         }
@@ -557,10 +663,7 @@ async fn auth_reset_password_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -575,24 +678,40 @@ async fn auth_reset_password_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_auth_reset_password_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("auth_reset_password", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<AuthResetPasswordError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -604,12 +723,14 @@ pub async fn auth_reset_password(
     auth_reset_password_request: crate::models::AuthResetPasswordRequest,
 ) -> Result<(), Error<AuthResetPasswordError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = auth_reset_password_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             auth_reset_password_request.clone(),
         )
         .await;
@@ -623,6 +744,22 @@ pub async fn auth_reset_password(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -646,6 +783,8 @@ pub async fn auth_reset_password(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("auth_reset_password", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -654,19 +793,29 @@ pub async fn auth_reset_password(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_reset_password",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_reset_password",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -675,6 +824,7 @@ pub async fn auth_reset_password(
 async fn auth_reset_password_with_token_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     auth_reset_password_with_token_request: crate::models::AuthResetPasswordWithTokenRequest,
 ) -> Result<(), Error<AuthResetPasswordWithTokenError>> {
     let local_var_configuration = configuration;
@@ -707,6 +857,8 @@ async fn auth_reset_password_with_token_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making auth_reset_password_with_token request",
             );
         }
@@ -724,10 +876,7 @@ async fn auth_reset_password_with_token_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -743,24 +892,41 @@ async fn auth_reset_password_with_token_inner(
     local_var_req_builder =
         local_var_req_builder.json(&p_body_auth_reset_password_with_token_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("auth_reset_password_with_token", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<AuthResetPasswordWithTokenError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -772,12 +938,14 @@ pub async fn auth_reset_password_with_token(
     auth_reset_password_with_token_request: crate::models::AuthResetPasswordWithTokenRequest,
 ) -> Result<(), Error<AuthResetPasswordWithTokenError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = auth_reset_password_with_token_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             auth_reset_password_with_token_request.clone(),
         )
         .await;
@@ -791,6 +959,22 @@ pub async fn auth_reset_password_with_token(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -814,6 +998,8 @@ pub async fn auth_reset_password_with_token(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("auth_reset_password_with_token", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -822,19 +1008,29 @@ pub async fn auth_reset_password_with_token(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_reset_password_with_token",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "auth_reset_password_with_token",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
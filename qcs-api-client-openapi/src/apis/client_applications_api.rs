@@ -23,10 +23,16 @@
  */
 
 use super::{ContentType, Error, configuration};
-use crate::{apis::ResponseContent, models};
+use crate::{
+    apis::{ApiError, ResponseContent},
+    models,
+};
 use ::qcs_api_client_common::backoff::{
-    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error, duration_from_response,
+    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error,
+    duration_from_response_with_settings,
 };
+#[cfg(feature = "metrics")]
+use ::qcs_api_client_common::backoff::{io_error_retry_cause, reqwest_error_retry_cause};
 #[cfg(feature = "tracing")]
 use qcs_api_client_common::configuration::tokens::TokenRefresher;
 use qcs_dependencies_client::reqwest::{self, StatusCode};
@@ -125,6 +131,7 @@ pub enum ListClientApplicationsError {
 async fn check_client_application_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     check_client_application_request: crate::models::CheckClientApplicationRequest,
 ) -> Result<models::CheckClientApplicationResponse, Error<CheckClientApplicationError>> {
     let local_var_configuration = configuration;
@@ -157,6 +164,8 @@ async fn check_client_application_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making check_client_application request",
             );
         }
@@ -174,10 +183,7 @@ async fn check_client_application_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -192,8 +198,16 @@ async fn check_client_application_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_check_client_application_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("check_client_application", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -206,6 +220,8 @@ async fn check_client_application_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -221,16 +237,25 @@ async fn check_client_application_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<CheckClientApplicationError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -242,12 +267,14 @@ pub async fn check_client_application(
     check_client_application_request: crate::models::CheckClientApplicationRequest,
 ) -> Result<models::CheckClientApplicationResponse, Error<CheckClientApplicationError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = check_client_application_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             check_client_application_request.clone(),
         )
         .await;
@@ -261,6 +288,22 @@ pub async fn check_client_application(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -284,6 +327,8 @@ pub async fn check_client_application(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("check_client_application", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -292,19 +337,29 @@ pub async fn check_client_application(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "check_client_application",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "check_client_application",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -313,6 +368,7 @@ pub async fn check_client_application(
 async fn get_client_application_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     client_application_name: &str,
 ) -> Result<models::ClientApplication, Error<GetClientApplicationError>> {
     let local_var_configuration = configuration;
@@ -346,6 +402,8 @@ async fn get_client_application_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_client_application request",
             );
         }
@@ -363,10 +421,7 @@ async fn get_client_application_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -379,8 +434,15 @@ async fn get_client_application_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("get_client_application", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -393,6 +455,8 @@ async fn get_client_application_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -408,16 +472,25 @@ async fn get_client_application_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetClientApplicationError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -429,12 +502,14 @@ pub async fn get_client_application(
     client_application_name: &str,
 ) -> Result<models::ClientApplication, Error<GetClientApplicationError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = get_client_application_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             client_application_name.clone(),
         )
         .await;
@@ -448,6 +523,22 @@ pub async fn get_client_application(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -471,6 +562,8 @@ pub async fn get_client_application(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_client_application", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -479,19 +572,29 @@ pub async fn get_client_application(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_client_application",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_client_application",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -500,6 +603,7 @@ pub async fn get_client_application(
 async fn list_client_applications_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
 ) -> Result<models::ListClientApplicationsResponse, Error<ListClientApplicationsError>> {
     let local_var_configuration = configuration;
 
@@ -529,6 +633,8 @@ async fn list_client_applications_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_client_applications request",
             );
         }
@@ -546,10 +652,7 @@ async fn list_client_applications_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -562,8 +665,16 @@ async fn list_client_applications_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics =
+        super::RequestMetrics::start("list_client_applications", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -576,6 +687,8 @@ async fn list_client_applications_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -591,16 +704,25 @@ async fn list_client_applications_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListClientApplicationsError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -611,10 +733,12 @@ pub async fn list_client_applications(
     configuration: &configuration::Configuration,
 ) -> Result<models::ListClientApplicationsResponse, Error<ListClientApplicationsError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = list_client_applications_inner(configuration, &mut backoff).await;
+        let result =
+            list_client_applications_inner(configuration, &mut backoff, &mut request_id).await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -625,6 +749,22 @@ pub async fn list_client_applications(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -648,6 +788,8 @@ pub async fn list_client_applications(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("list_client_applications", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -656,19 +798,29 @@ pub async fn list_client_applications(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_client_applications",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_client_applications",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -23,6 +23,9 @@
  */
 
 use qcs_api_client_common::backoff;
+use qcs_api_client_common::configuration::{
+    TokenError, secrets::SecretAccessToken, tokens::TokenOverride,
+};
 use qcs_dependencies_client::http;
 use qcs_dependencies_client::reqwest;
 use qcs_dependencies_client::reqwest_middleware;
@@ -44,6 +47,9 @@ pub struct Configuration {
     pub client: reqwest_middleware::ClientWithMiddleware,
     pub qcs_config: crate::common::ClientConfiguration,
     pub backoff: backoff::ExponentialBackoff,
+    /// Credentials used in place of the `qcs_config` tokens, see
+    /// [`Configuration::with_token_override`].
+    pub token_override: Option<TokenOverride>,
 }
 
 pub type BasicAuth = (String, Option<String>);
@@ -58,16 +64,52 @@ static USER_AGENT: &str = "QCS OpenAPI Client (Rust)/2020-07-31";
 
 impl Configuration {
     pub async fn new() -> Result<Self, crate::common::configuration::LoadError> {
-        crate::common::ClientConfiguration::load_default().map(Self::with_qcs_config)
+        let qcs_config = crate::common::ClientConfiguration::load_default()?;
+        Self::try_with_qcs_config(qcs_config)
+            .map_err(|error| crate::common::configuration::LoadError::Load(Box::new(error)))
     }
 
+    /// Like [`Configuration::try_with_qcs_config`], but if the proxy or TLS settings of
+    /// `qcs_config` are invalid, a warning is logged and requests are sent without a proxy and
+    /// with the default TLS settings instead.
     pub fn with_qcs_config(qcs_config: crate::common::ClientConfiguration) -> Configuration {
-        let client = reqwest::Client::builder()
+        match Self::try_with_qcs_config(qcs_config.clone()) {
+            Ok(configuration) => configuration,
+            Err(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    "Ignoring the invalid proxy and TLS settings of the QCS configuration: {error}"
+                );
+                #[cfg(not(feature = "tracing"))]
+                let _ = error;
+                let client = reqwest::Client::builder()
+                    .user_agent(USER_AGENT)
+                    .no_proxy()
+                    .build()
+                    .expect("failed to add User-Agent to HTTP client");
+                Self::with_client_and_qcs_config(client, qcs_config)
+            }
+        }
+    }
+
+    /// Create a configuration whose HTTP client routes and secures requests according to the
+    /// proxy and TLS settings of `qcs_config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`reqwest::Error`] if the proxy or TLS settings are invalid.
+    pub fn try_with_qcs_config(
+        qcs_config: crate::common::ClientConfiguration,
+    ) -> Result<Self, reqwest::Error> {
+        // Route and secure REST requests the same way as gRPC and auth server requests.
+        let client = qcs_config
+            .tls_config()
+            .configure_reqwest(reqwest::Client::builder())?
             .user_agent(USER_AGENT)
-            .build()
-            .expect("failed to add User-Agent to HTTP client");
+            .proxy(qcs_config.proxy_config().reqwest_proxy()?)
+            .build()?;
 
-        Self::with_client_and_qcs_config(client, qcs_config)
+        Ok(Self::with_client_and_qcs_config(client, qcs_config))
     }
 
     pub fn with_client_and_qcs_config(
@@ -87,10 +129,34 @@ impl Configuration {
             client_builder.build()
         };
 
+        let backoff = qcs_config.retry_settings().backoff();
+
         Self {
             qcs_config,
             client,
-            backoff: backoff::default_backoff(),
+            backoff,
+            token_override: None,
+        }
+    }
+
+    /// Returns a copy of this configuration which authenticates requests with the given
+    /// [`TokenOverride`] rather than the tokens of its `qcs_config`, e.g. to make requests on behalf
+    /// of another user. The copy shares the HTTP client, and therefore the connection pool, of
+    /// this configuration.
+    #[must_use]
+    pub fn with_token_override(&self, token_override: TokenOverride) -> Self {
+        Self {
+            token_override: Some(token_override),
+            ..self.clone()
+        }
+    }
+
+    /// Get the access token to authenticate a request with, from the token override if one is
+    /// set and from the `qcs_config` otherwise.
+    pub async fn bearer_access_token(&self) -> Result<SecretAccessToken, TokenError> {
+        match &self.token_override {
+            Some(token_override) => token_override.validated_access_token().await,
+            None => self.qcs_config.get_bearer_access_token().await,
         }
     }
 }
@@ -171,12 +237,20 @@ impl reqwest_tracing::ReqwestOtelSpanBackend for FilteredSpanBackend {
             .get("User-Agent")
             .and_then(|ua| ua.to_str().ok())
             .unwrap_or("");
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("")
+        };
         let span = reqwest_otel_span!(
             name = "HTTP request",
             req,
             http.url = uri,
             http.target = http_target,
-            http.user_agent = user_agent
+            http.user_agent = user_agent,
+            qcs.request_id = header(super::KEY_X_REQUEST_ID),
+            qcs.request_retry_index = header(super::KEY_X_REQUEST_RETRY_INDEX)
         );
         Self::add_header_metadata(
             &span,
@@ -218,6 +292,57 @@ mod tests {
     // Additionally, reqwest still doesn't support Unix domain sockets, so unit testing is fairly
     // limited for here. See more info on UDS, see <https://github.com/seanmonstar/reqwest/issues/39>.
 
+    /// Test that invalid proxy and TLS settings fail the configuration rather than being ignored.
+    #[test]
+    fn test_invalid_proxy_and_tls_settings() {
+        use qcs_api_client_common::configuration::{
+            proxy::ProxyConfig,
+            tls::{ClientIdentity, TlsConfig},
+        };
+
+        let qcs_config = crate::common::ClientConfiguration::builder()
+            .proxy_config(ProxyConfig {
+                https_proxy: Some("not a proxy url".to_string()),
+                ..ProxyConfig::default()
+            })
+            .build()
+            .expect("should build client configuration");
+        assert!(Configuration::try_with_qcs_config(qcs_config).is_err());
+
+        let qcs_config = crate::common::ClientConfiguration::builder()
+            .proxy_config(ProxyConfig::default())
+            .tls_config(TlsConfig {
+                client_identity: Some(ClientIdentity::new("not a certificate", "not a key")),
+                ..TlsConfig::default()
+            })
+            .build()
+            .expect("should build client configuration");
+        assert!(Configuration::try_with_qcs_config(qcs_config).is_err());
+    }
+
+    /// Test that the infallible constructor falls back to the default settings instead of panicking.
+    #[test]
+    fn test_with_qcs_config_ignores_invalid_proxy_settings() {
+        use qcs_api_client_common::configuration::proxy::ProxyConfig;
+
+        let qcs_config = crate::common::ClientConfiguration::builder()
+            .proxy_config(ProxyConfig {
+                https_proxy: Some("not a proxy url".to_string()),
+                ..ProxyConfig::default()
+            })
+            .build()
+            .expect("should build client configuration");
+        let configuration = Configuration::with_qcs_config(qcs_config);
+        assert_eq!(
+            configuration
+                .qcs_config
+                .proxy_config()
+                .https_proxy
+                .as_deref(),
+            Some("not a proxy url")
+        );
+    }
+
     /// Test that all requests are traced when no filter is specified.
     #[cfg(feature = "tracing-opentelemetry")]
     #[rstest]
@@ -23,10 +23,16 @@
  */
 
 use super::{ContentType, Error, configuration};
-use crate::{apis::ResponseContent, models};
+use crate::{
+    apis::{ApiError, ResponseContent},
+    models,
+};
 use ::qcs_api_client_common::backoff::{
-    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error, duration_from_response,
+    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error,
+    duration_from_response_with_settings,
 };
+#[cfg(feature = "metrics")]
+use ::qcs_api_client_common::backoff::{io_error_retry_cause, reqwest_error_retry_cause};
 #[cfg(feature = "tracing")]
 use qcs_api_client_common::configuration::tokens::TokenRefresher;
 use qcs_dependencies_client::reqwest::{self, StatusCode};
@@ -108,6 +114,7 @@ pub enum HealthCheckDeprecatedError {
 async fn get_health_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
 ) -> Result<models::Health, Error<GetHealthError>> {
     let local_var_configuration = configuration;
 
@@ -134,6 +141,8 @@ async fn get_health_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_health request",
             );
         }
@@ -151,10 +160,7 @@ async fn get_health_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -167,8 +173,15 @@ async fn get_health_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("get_health", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -181,6 +194,8 @@ async fn get_health_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -196,16 +211,25 @@ async fn get_health_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetHealthError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -216,10 +240,11 @@ pub async fn get_health(
     configuration: &configuration::Configuration,
 ) -> Result<models::Health, Error<GetHealthError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = get_health_inner(configuration, &mut backoff).await;
+        let result = get_health_inner(configuration, &mut backoff, &mut request_id).await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -230,6 +255,22 @@ pub async fn get_health(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -253,6 +294,8 @@ pub async fn get_health(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_health", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -261,19 +304,29 @@ pub async fn get_health(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_health",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_health",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -282,6 +335,7 @@ pub async fn get_health(
 async fn health_check_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
 ) -> Result<(), Error<HealthCheckError>> {
     let local_var_configuration = configuration;
 
@@ -311,6 +365,8 @@ async fn health_check_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making health_check request",
             );
         }
@@ -328,10 +384,7 @@ async fn health_check_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -344,24 +397,40 @@ async fn health_check_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("health_check", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<HealthCheckError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -372,10 +441,11 @@ pub async fn health_check(
     configuration: &configuration::Configuration,
 ) -> Result<(), Error<HealthCheckError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = health_check_inner(configuration, &mut backoff).await;
+        let result = health_check_inner(configuration, &mut backoff, &mut request_id).await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -386,6 +456,22 @@ pub async fn health_check(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -409,6 +495,8 @@ pub async fn health_check(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("health_check", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -417,19 +505,29 @@ pub async fn health_check(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "health_check",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "health_check",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -438,6 +536,7 @@ pub async fn health_check(
 async fn health_check_deprecated_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
 ) -> Result<serde_json::Value, Error<HealthCheckDeprecatedError>> {
     let local_var_configuration = configuration;
 
@@ -464,6 +563,8 @@ async fn health_check_deprecated_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making health_check_deprecated request",
             );
         }
@@ -481,10 +582,7 @@ async fn health_check_deprecated_inner(
         )]
         let is_jwt_bearer_optional: bool = false;
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -497,8 +595,15 @@ async fn health_check_deprecated_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("health_check_deprecated", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -511,6 +616,8 @@ async fn health_check_deprecated_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -526,16 +633,25 @@ async fn health_check_deprecated_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<HealthCheckDeprecatedError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -547,10 +663,12 @@ pub async fn health_check_deprecated(
     configuration: &configuration::Configuration,
 ) -> Result<serde_json::Value, Error<HealthCheckDeprecatedError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = health_check_deprecated_inner(configuration, &mut backoff).await;
+        let result =
+            health_check_deprecated_inner(configuration, &mut backoff, &mut request_id).await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -561,6 +679,22 @@ pub async fn health_check_deprecated(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -584,6 +718,8 @@ pub async fn health_check_deprecated(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("health_check_deprecated", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -592,19 +728,29 @@ pub async fn health_check_deprecated(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "health_check_deprecated",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "health_check_deprecated",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -23,10 +23,16 @@
  */
 
 use super::{ContentType, Error, configuration};
-use crate::{apis::ResponseContent, models};
+use crate::{
+    apis::{ApiError, ResponseContent},
+    models,
+};
 use ::qcs_api_client_common::backoff::{
-    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error, duration_from_response,
+    ExponentialBackoff, duration_from_io_error, duration_from_reqwest_error,
+    duration_from_response_with_settings,
 };
+#[cfg(feature = "metrics")]
+use ::qcs_api_client_common::backoff::{io_error_retry_cause, reqwest_error_retry_cause};
 #[cfg(feature = "tracing")]
 use qcs_api_client_common::configuration::tokens::TokenRefresher;
 use qcs_dependencies_client::reqwest::{self, StatusCode};
@@ -231,6 +237,7 @@ pub enum RestartEndpointError {
 async fn create_endpoint_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     create_endpoint_parameters: crate::models::CreateEndpointParameters,
 ) -> Result<models::Endpoint, Error<CreateEndpointError>> {
     let local_var_configuration = configuration;
@@ -263,6 +270,8 @@ async fn create_endpoint_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making create_endpoint request",
             );
         }
@@ -280,10 +289,7 @@ async fn create_endpoint_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -298,8 +304,15 @@ async fn create_endpoint_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_create_endpoint_parameters);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("create_endpoint", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -312,6 +325,8 @@ async fn create_endpoint_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -327,16 +342,25 @@ async fn create_endpoint_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<CreateEndpointError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -348,12 +372,14 @@ pub async fn create_endpoint(
     create_endpoint_parameters: crate::models::CreateEndpointParameters,
 ) -> Result<models::Endpoint, Error<CreateEndpointError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = create_endpoint_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             create_endpoint_parameters.clone(),
         )
         .await;
@@ -367,6 +393,22 @@ pub async fn create_endpoint(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -390,6 +432,8 @@ pub async fn create_endpoint(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("create_endpoint", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -398,19 +442,29 @@ pub async fn create_endpoint(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "create_endpoint",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "create_endpoint",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -419,6 +473,7 @@ pub async fn create_endpoint(
 async fn delete_endpoint_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     endpoint_id: &str,
 ) -> Result<(), Error<DeleteEndpointError>> {
     let local_var_configuration = configuration;
@@ -452,6 +507,8 @@ async fn delete_endpoint_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="DELETE",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making delete_endpoint request",
             );
         }
@@ -469,10 +526,7 @@ async fn delete_endpoint_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -485,24 +539,40 @@ async fn delete_endpoint_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("delete_endpoint", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<DeleteEndpointError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -514,10 +584,17 @@ pub async fn delete_endpoint(
     endpoint_id: &str,
 ) -> Result<(), Error<DeleteEndpointError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::DELETE;
     loop {
-        let result = delete_endpoint_inner(configuration, &mut backoff, endpoint_id.clone()).await;
+        let result = delete_endpoint_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            endpoint_id.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -528,6 +605,22 @@ pub async fn delete_endpoint(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -551,6 +644,8 @@ pub async fn delete_endpoint(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("delete_endpoint", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -559,19 +654,29 @@ pub async fn delete_endpoint(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "delete_endpoint",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "delete_endpoint",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -580,6 +685,7 @@ pub async fn delete_endpoint(
 async fn get_default_endpoint_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     quantum_processor_id: &str,
 ) -> Result<models::Endpoint, Error<GetDefaultEndpointError>> {
     let local_var_configuration = configuration;
@@ -613,6 +719,8 @@ async fn get_default_endpoint_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_default_endpoint request",
             );
         }
@@ -630,10 +738,7 @@ async fn get_default_endpoint_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -646,8 +751,15 @@ async fn get_default_endpoint_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("get_default_endpoint", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -660,6 +772,8 @@ async fn get_default_endpoint_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -675,16 +789,25 @@ async fn get_default_endpoint_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetDefaultEndpointError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -696,12 +819,17 @@ pub async fn get_default_endpoint(
     quantum_processor_id: &str,
 ) -> Result<models::Endpoint, Error<GetDefaultEndpointError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result =
-            get_default_endpoint_inner(configuration, &mut backoff, quantum_processor_id.clone())
-                .await;
+        let result = get_default_endpoint_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            quantum_processor_id.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -712,6 +840,22 @@ pub async fn get_default_endpoint(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -735,6 +879,8 @@ pub async fn get_default_endpoint(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_default_endpoint", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -743,19 +889,29 @@ pub async fn get_default_endpoint(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_default_endpoint",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_default_endpoint",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -764,6 +920,7 @@ pub async fn get_default_endpoint(
 async fn get_endpoint_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     endpoint_id: &str,
 ) -> Result<models::Endpoint, Error<GetEndpointError>> {
     let local_var_configuration = configuration;
@@ -797,6 +954,8 @@ async fn get_endpoint_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making get_endpoint request",
             );
         }
@@ -814,10 +973,7 @@ async fn get_endpoint_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -830,8 +986,15 @@ async fn get_endpoint_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("get_endpoint", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -844,6 +1007,8 @@ async fn get_endpoint_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -859,16 +1024,25 @@ async fn get_endpoint_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<GetEndpointError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -880,10 +1054,17 @@ pub async fn get_endpoint(
     endpoint_id: &str,
 ) -> Result<models::Endpoint, Error<GetEndpointError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
-        let result = get_endpoint_inner(configuration, &mut backoff, endpoint_id.clone()).await;
+        let result = get_endpoint_inner(
+            configuration,
+            &mut backoff,
+            &mut request_id,
+            endpoint_id.clone(),
+        )
+        .await;
 
         match result {
             Ok(result) => return Ok(result),
@@ -894,6 +1075,22 @@ pub async fn get_endpoint(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -917,6 +1114,8 @@ pub async fn get_endpoint(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("get_endpoint", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -925,19 +1124,29 @@ pub async fn get_endpoint(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_endpoint",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "get_endpoint",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -946,6 +1155,7 @@ pub async fn get_endpoint(
 async fn list_endpoints_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     filter: Option<&str>,
     page_size: Option<i64>,
     page_token: Option<&str>,
@@ -982,6 +1192,8 @@ async fn list_endpoints_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="GET",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making list_endpoints request",
             );
         }
@@ -1012,10 +1224,7 @@ async fn list_endpoints_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1028,8 +1237,15 @@ async fn list_endpoints_inner(
         }
     }
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("list_endpoints", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
     let local_var_raw_content_type = local_var_resp
@@ -1042,6 +1258,8 @@ async fn list_endpoints_inner(
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         match local_var_content_type {
             ContentType::Json => serde_path_to_error::deserialize(
                 &mut serde_json::Deserializer::from_str(&local_var_content),
@@ -1057,16 +1275,25 @@ async fn list_endpoints_inner(
             }),
         }
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<ListEndpointsError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -1080,12 +1307,14 @@ pub async fn list_endpoints(
     page_token: Option<&str>,
 ) -> Result<models::ListEndpointsResponse, Error<ListEndpointsError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::GET;
     loop {
         let result = list_endpoints_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             filter.clone(),
             page_size.clone(),
             page_token.clone(),
@@ -1101,6 +1330,22 @@ pub async fn list_endpoints(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -1124,6 +1369,8 @@ pub async fn list_endpoints(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("list_endpoints", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1132,19 +1379,29 @@ pub async fn list_endpoints(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_endpoints",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "list_endpoints",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -1153,6 +1410,7 @@ pub async fn list_endpoints(
 async fn restart_endpoint_inner(
     configuration: &configuration::Configuration,
     backoff: &mut ExponentialBackoff,
+    request_id: &mut super::RequestId,
     endpoint_id: &str,
     restart_endpoint_request: Option<crate::models::RestartEndpointRequest>,
 ) -> Result<(), Error<RestartEndpointError>> {
@@ -1188,6 +1446,8 @@ async fn restart_endpoint_inner(
             ::tracing::debug!(
                 url=%local_var_uri_str,
                 method="POST",
+                request_id=%request_id,
+                retry_index=request_id.retry_index(),
                 "making restart_endpoint request",
             );
         }
@@ -1205,10 +1465,7 @@ async fn restart_endpoint_inner(
         )]
         let is_jwt_bearer_optional: bool = false || "JWTBearer" == "JWTBearerOptional";
 
-        let token = local_var_configuration
-            .qcs_config
-            .get_bearer_access_token()
-            .await;
+        let token = local_var_configuration.bearer_access_token().await;
 
         if is_jwt_bearer_optional && matches!(token, Err(TokenError::NoCredentials)) {
             // the client is configured without any OAuthSession, but this call does not require one.
@@ -1223,24 +1480,40 @@ async fn restart_endpoint_inner(
 
     local_var_req_builder = local_var_req_builder.json(&p_body_restart_endpoint_request);
 
+    local_var_req_builder = request_id.apply(local_var_req_builder);
+
     let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    #[cfg(feature = "metrics")]
+    let local_var_metrics = super::RequestMetrics::start("restart_endpoint", &local_var_req);
+    let local_var_resp = local_var_client.execute(local_var_req).await;
+    #[cfg(feature = "metrics")]
+    local_var_metrics.record_response(&local_var_resp);
+    let local_var_resp = local_var_resp?;
 
     let local_var_status = local_var_resp.status();
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
     } else {
-        let local_var_retry_delay =
-            duration_from_response(local_var_resp.status(), local_var_resp.headers(), backoff);
+        let local_var_retry_delay = duration_from_response_with_settings(
+            local_var_resp.status(),
+            local_var_resp.headers(),
+            backoff,
+            local_var_configuration.qcs_config.retry_settings(),
+        );
         let local_var_content = local_var_resp.text().await?;
+        #[cfg(feature = "metrics")]
+        local_var_metrics.record_content(&local_var_content);
         let local_var_entity: Option<RestartEndpointError> =
             serde_json::from_str(&local_var_content).ok();
+        let local_var_api_error = ApiError::from_response(local_var_status, &local_var_content);
         let local_var_error = ResponseContent {
             status: local_var_status,
             content: local_var_content,
             entity: local_var_entity,
             retry_delay: local_var_retry_delay,
+            request_id: request_id.to_string(),
+            api_error: local_var_api_error,
         };
         Err(Error::ResponseError(local_var_error))
     }
@@ -1253,12 +1526,14 @@ pub async fn restart_endpoint(
     restart_endpoint_request: Option<crate::models::RestartEndpointRequest>,
 ) -> Result<(), Error<RestartEndpointError>> {
     let mut backoff = configuration.backoff.clone();
+    let mut request_id = super::RequestId::new();
     let mut refreshed_credentials = false;
     let method = reqwest::Method::POST;
     loop {
         let result = restart_endpoint_inner(
             configuration,
             &mut backoff,
+            &mut request_id,
             endpoint_id.clone(),
             restart_endpoint_request.clone(),
         )
@@ -1273,6 +1548,22 @@ pub async fn restart_endpoint(
                         StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
                     )
                 {
+                    if let Some(token_override) = &configuration.token_override {
+                        token_override.emit_token_event(
+                            ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                        );
+                        if token_override.is_refreshable() {
+                            token_override.refresh_access_token().await?;
+                            refreshed_credentials = true;
+                            continue;
+                        }
+                        return Err(Error::ResponseError(response));
+                    }
+
+                    configuration.qcs_config.emit_token_event(
+                        ::qcs_api_client_common::configuration::tokens::TokenEvent::AccessTokenRejected,
+                    );
+
                     // Attempt to refresh credentials
                     match configuration.qcs_config.refresh().await {
                         Ok(_) => {
@@ -1296,6 +1587,8 @@ pub async fn restart_endpoint(
                         Err(e) => return Err(e.into()),
                     }
                 } else if let Some(duration) = response.retry_delay {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry("restart_endpoint", response.status.as_str());
                     tokio::time::sleep(duration).await;
                     continue;
                 }
@@ -1304,19 +1597,29 @@ pub async fn restart_endpoint(
             }
             Err(Error::Reqwest(error)) => {
                 if let Some(duration) = duration_from_reqwest_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "restart_endpoint",
+                        reqwest_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Reqwest(error));
+                return Err(Error::Reqwest(error.with_request_id(&request_id)));
             }
             Err(Error::Io(error)) => {
                 if let Some(duration) = duration_from_io_error(&method, &error, &mut backoff) {
+                    #[cfg(feature = "metrics")]
+                    super::record_retry(
+                        "restart_endpoint",
+                        io_error_retry_cause(&error).unwrap_or_default(),
+                    );
                     tokio::time::sleep(duration).await;
                     continue;
                 }
 
-                return Err(Error::Io(error));
+                return Err(Error::Io(error.with_request_id(&request_id)));
             }
             Err(error) => return Err(error),
         }
@@ -12,26 +12,121 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::error;
 use std::fmt;
 
+use qcs_api_client_common::classify::{ClassifyError, ErrorCategory};
 use qcs_dependencies_client::reqwest;
 #[cfg(feature = "tracing-opentelemetry")]
 use qcs_dependencies_client::reqwest_middleware;
 
+use crate::models;
+
 #[derive(Debug, Clone)]
 pub struct ResponseContent<T> {
     pub status: reqwest::StatusCode,
     pub content: String,
     pub entity: Option<T>,
     pub retry_delay: Option<std::time::Duration>,
+    /// The `x-request-id` sent with every attempt of the request, see [`KEY_X_REQUEST_ID`].
+    pub request_id: String,
+    /// The QCS API error parsed from the `content`, if the response body is one.
+    pub api_error: Option<ApiError>,
+}
+
+/// An error response returned by the QCS API, parsed from a [`models::Error`] body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    status: reqwest::StatusCode,
+    error: models::Error,
 }
 
+impl ApiError {
+    /// Parse the body of an error response, returning `None` if it is not a [`models::Error`].
+    pub fn from_response(status: reqwest::StatusCode, content: &str) -> Option<Self> {
+        serde_json::from_str(content)
+            .ok()
+            .map(|error| Self { status, error })
+    }
+
+    /// The HTTP status code of the response.
+    pub fn status(&self) -> reqwest::StatusCode {
+        self.status
+    }
+
+    /// The machine-readable error code, e.g. `not_found`.
+    pub fn code(&self) -> &str {
+        &self.error.code
+    }
+
+    /// The human-readable error message.
+    pub fn message(&self) -> &str {
+        &self.error.message
+    }
+
+    /// The ID the QCS API assigned to the failed request, for use in support requests.
+    pub fn request_id(&self) -> &str {
+        &self.error.request_id
+    }
+
+    /// The reasons the request was rejected as invalid, if any.
+    pub fn validation_errors(&self) -> &[models::ValidationError] {
+        self.error.validation_errors.as_deref().unwrap_or_default()
+    }
+
+    /// Return `true` if the requested resource does not exist.
+    pub fn is_not_found(&self) -> bool {
+        self.status == reqwest::StatusCode::NOT_FOUND
+    }
+
+    /// Return `true` if the request conflicts with the current state of the resource.
+    pub fn is_conflict(&self) -> bool {
+        self.status == reqwest::StatusCode::CONFLICT
+    }
+
+    /// Return `true` if the request may succeed if it is retried later, i.e. if its
+    /// [`ErrorCategory`] is retryable.
+    pub fn is_retryable(&self) -> bool {
+        ErrorCategory::from_http_status(self.status).is_retryable()
+    }
+
+    /// The underlying [`models::Error`].
+    pub fn as_model(&self) -> &models::Error {
+        &self.error
+    }
+
+    /// Convert into the underlying [`models::Error`].
+    pub fn into_model(self) -> models::Error {
+        self.error
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): {} [request ID: {}]",
+            self.error.code, self.status, self.error.message, self.error.request_id
+        )?;
+        for validation_error in self.validation_errors() {
+            write!(f, "; {:?}", validation_error.r#in)?;
+            if let Some(path) = &validation_error.path {
+                write!(f, " {}", path.join("."))?;
+            }
+            write!(f, ": {}", validation_error.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ApiError {}
+
 #[derive(Debug)]
 pub enum Error<T> {
-    Reqwest(reqwest::Error),
+    Reqwest(WithRequestId<reqwest::Error>),
     Serde(serde_path_to_error::Error<serde_json::Error>),
-    Io(std::io::Error),
+    Io(WithRequestId<std::io::Error>),
     QcsToken(crate::common::configuration::TokenError),
     ResponseError(ResponseContent<T>),
     InvalidContentType {
@@ -49,6 +144,45 @@ impl<T> Error<T> {
             _ => None,
         }
     }
+
+    /// The QCS API error returned in the response, if the request failed with one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::ResponseError(err) => err.api_error.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The ID of the failed request: the one the QCS API returned, if any, or otherwise the
+    /// `x-request-id` the client sent.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::ResponseError(err) => Some(
+                err.api_error
+                    .as_ref()
+                    .map_or(err.request_id.as_str(), ApiError::request_id),
+            ),
+            Self::Reqwest(e) => e.request_id(),
+            Self::Io(e) => e.request_id(),
+            _ => None,
+        }
+    }
+
+    /// Return `true` if the requested resource does not exist.
+    pub fn is_not_found(&self) -> bool {
+        self.status_code() == Some(reqwest::StatusCode::NOT_FOUND)
+    }
+
+    /// Return `true` if the request conflicts with the current state of the resource.
+    pub fn is_conflict(&self) -> bool {
+        self.status_code() == Some(reqwest::StatusCode::CONFLICT)
+    }
+
+    /// Return `true` if the failed request may succeed if it is retried later. This is the same as
+    /// [`ClassifyError::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.category().is_retryable()
+    }
 }
 
 impl<T> fmt::Display for Error<T> {
@@ -60,7 +194,10 @@ impl<T> fmt::Display for Error<T> {
             Error::QcsToken(e) => ("refresh_qcs_token", e.to_string()),
             Error::ResponseError(e) => (
                 "response",
-                format!("status code {}: {}", e.status, e.content),
+                format!(
+                    "status code {} (request ID {}): {}",
+                    e.status, e.request_id, e.content
+                ),
             ),
             Error::InvalidContentType {
                 content_type,
@@ -78,12 +215,74 @@ impl<T> fmt::Display for Error<T> {
     }
 }
 
+/// An error which a request failed with, along with the `x-request-id` sent with the request if
+/// known, see [`KEY_X_REQUEST_ID`].
+///
+/// This dereferences to the underlying error.
+#[derive(Debug)]
+pub struct WithRequestId<E> {
+    error: E,
+    request_id: Option<String>,
+}
+
+impl<E> WithRequestId<E> {
+    /// Attach the ID of the request which failed with this error.
+    pub fn with_request_id(self, request_id: impl ToString) -> Self {
+        Self {
+            request_id: Some(request_id.to_string()),
+            ..self
+        }
+    }
+
+    /// The `x-request-id` sent with the failed request, if known.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Convert into the underlying error.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E> From<E> for WithRequestId<E> {
+    fn from(error: E) -> Self {
+        Self {
+            error,
+            request_id: None,
+        }
+    }
+}
+
+impl<E> std::ops::Deref for WithRequestId<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for WithRequestId<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.request_id {
+            Some(request_id) => write!(f, "{} (request ID {request_id})", self.error),
+            None => self.error.fmt(f),
+        }
+    }
+}
+
+impl<E: error::Error> error::Error for WithRequestId<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
 impl<T: fmt::Debug> error::Error for Error<T> {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         Some(match self {
-            Error::Reqwest(e) => e,
+            Error::Reqwest(e) => &**e,
             Error::Serde(e) => e,
-            Error::Io(e) => e,
+            Error::Io(e) => &**e,
             Error::QcsToken(e) => e,
             #[cfg(feature = "tracing-opentelemetry")]
             Error::ReqwestMiddleware(e) => e.source()?,
@@ -93,9 +292,34 @@ impl<T: fmt::Debug> error::Error for Error<T> {
     }
 }
 
+impl<T> ClassifyError for Error<T> {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Reqwest(e) => ErrorCategory::from_reqwest_error(e),
+            Error::Io(_) => ErrorCategory::Transient,
+            Error::QcsToken(e) => e.category(),
+            Error::ResponseError(e) => ErrorCategory::from_http_status(e.status),
+            Error::Serde(_) | Error::InvalidContentType { .. } => ErrorCategory::Internal,
+            #[cfg(feature = "tracing-opentelemetry")]
+            Error::ReqwestMiddleware(_) => ErrorCategory::Internal,
+        }
+    }
+
+    fn server_request_id(&self) -> Option<&str> {
+        self.request_id()
+    }
+
+    fn hint(&self) -> Cow<'static, str> {
+        match self {
+            Error::QcsToken(e) => e.hint(),
+            _ => Cow::Borrowed(self.category().hint()),
+        }
+    }
+}
+
 impl<T> From<reqwest::Error> for Error<T> {
     fn from(e: reqwest::Error) -> Self {
-        Error::Reqwest(e)
+        Error::Reqwest(e.into())
     }
 }
 
@@ -103,7 +327,7 @@ impl<T> From<reqwest::Error> for Error<T> {
 impl<T> From<reqwest_middleware::Error> for Error<T> {
     fn from(e: reqwest_middleware::Error) -> Self {
         match e {
-            reqwest_middleware::Error::Reqwest(e) => Error::Reqwest(e),
+            reqwest_middleware::Error::Reqwest(e) => e.into(),
             reqwest_middleware::Error::Middleware(e) => Error::ReqwestMiddleware(e),
         }
     }
@@ -117,7 +341,7 @@ impl<T> From<serde_path_to_error::Error<serde_json::Error>> for Error<T> {
 
 impl<T> From<std::io::Error> for Error<T> {
     fn from(e: std::io::Error) -> Self {
-        Error::Io(e)
+        Error::Io(e.into())
     }
 }
 
@@ -162,6 +386,13 @@ pub fn parse_deep_object(prefix: &str, value: &serde_json::Value) -> Vec<(String
     unimplemented!("Only objects are supported with style=deepObject, got: {value:#}")
 }
 
+/// Internal use only
+/// The builder of requests sent by the [`configuration::Configuration::client`].
+#[cfg(feature = "tracing-opentelemetry")]
+type RequestBuilder = reqwest_middleware::RequestBuilder;
+#[cfg(not(feature = "tracing-opentelemetry"))]
+type RequestBuilder = reqwest::RequestBuilder;
+
 /// Internal use only
 /// A content type supported by this client.
 #[allow(dead_code)]
@@ -183,6 +414,113 @@ impl From<&str> for ContentType {
     }
 }
 
+/// The header identifying all attempts of a single API call, so that they can be correlated with
+/// server logs.
+pub const KEY_X_REQUEST_ID: &str = "x-request-id";
+
+/// The header numbering the attempts of a single API call, starting from zero.
+pub const KEY_X_REQUEST_RETRY_INDEX: &str = "x-request-retry-index";
+
+/// Internal use only
+/// Identifies the attempts of a single API call with the same [`KEY_X_REQUEST_ID`] and
+/// increasing [`KEY_X_REQUEST_RETRY_INDEX`] headers.
+#[derive(Debug)]
+struct RequestId {
+    id: String,
+    retry_index: u32,
+}
+
+impl RequestId {
+    /// Create a new, random request ID.
+    fn new() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            retry_index: 0,
+        }
+    }
+
+    /// The retry index of the next attempt.
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    fn retry_index(&self) -> u32 {
+        self.retry_index
+    }
+
+    /// Add the request ID headers of the next attempt to a request.
+    fn apply(&mut self, request: RequestBuilder) -> RequestBuilder {
+        let request = request
+            .header(KEY_X_REQUEST_ID, self.id.as_str())
+            .header(KEY_X_REQUEST_RETRY_INDEX, self.retry_index);
+        self.retry_index += 1;
+        request
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+/// Internal use only
+/// Records [`qcs_api_client_common::metrics`] for a single request attempt.
+#[cfg(feature = "metrics")]
+struct RequestMetrics {
+    operation: &'static str,
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "metrics")]
+impl RequestMetrics {
+    /// Start timing a request attempt for `operation`, recording the size of the request body.
+    fn start(operation: &'static str, request: &reqwest::Request) -> Self {
+        use qcs_api_client_common::metrics::{Client, record_bytes_sent};
+
+        let bytes = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map_or(0, <[u8]>::len);
+        record_bytes_sent(Client::OpenApi, operation, bytes as u64);
+
+        Self {
+            operation,
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Record the outcome of the request attempt once the response headers are received.
+    fn record_response<E>(&self, response: &Result<reqwest::Response, E>) {
+        use qcs_api_client_common::metrics::{Client, STATUS_ERROR, record_request};
+
+        let status = response.as_ref().map_or_else(
+            |_| STATUS_ERROR.to_string(),
+            |response| response.status().as_str().to_string(),
+        );
+        record_request(
+            Client::OpenApi,
+            self.operation,
+            status,
+            self.start.elapsed(),
+        );
+    }
+
+    /// Record the size of the response body.
+    fn record_content(&self, content: &str) {
+        use qcs_api_client_common::metrics::{Client, record_bytes_received};
+
+        record_bytes_received(Client::OpenApi, self.operation, content.len() as u64);
+    }
+}
+
+/// Internal use only
+/// Records a retry of `operation` in [`qcs_api_client_common::metrics`], e.g. because of an HTTP
+/// status code such as `503` or a transport error such as `timeout`.
+#[cfg(feature = "metrics")]
+fn record_retry(operation: &'static str, cause: &str) {
+    use qcs_api_client_common::metrics::{Client, record_retry};
+
+    record_retry(Client::OpenApi, operation, cause.to_string());
+}
+
 pub mod account_api;
 pub mod authentication_api;
 pub mod client_applications_api;
@@ -193,3 +531,107 @@ pub mod quantum_processors_api;
 pub mod reservations_api;
 
 pub mod configuration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_from_response() {
+        let content = r#"{
+            "code": "invalid_argument",
+            "message": "Request is invalid",
+            "requestId": "abc-123",
+            "validationErrors": [
+                {"in": "body", "message": "must be positive", "path": ["count"]}
+            ]
+        }"#;
+        let api_error = ApiError::from_response(reqwest::StatusCode::BAD_REQUEST, content)
+            .expect("should parse API error");
+        assert_eq!(api_error.code(), "invalid_argument");
+        assert_eq!(api_error.request_id(), "abc-123");
+        assert_eq!(api_error.validation_errors().len(), 1);
+        assert_eq!(api_error.validation_errors()[0].message, "must be positive");
+        assert!(!api_error.is_not_found());
+        assert!(!api_error.is_retryable());
+
+        let error = Error::<()>::ResponseError(ResponseContent {
+            status: reqwest::StatusCode::SERVICE_UNAVAILABLE,
+            content: "upstream unavailable".to_string(),
+            entity: None,
+            retry_delay: None,
+            request_id: "client-456".to_string(),
+            api_error: ApiError::from_response(
+                reqwest::StatusCode::SERVICE_UNAVAILABLE,
+                "upstream unavailable",
+            ),
+        });
+        assert!(error.api_error().is_none());
+        assert_eq!(error.request_id(), Some("client-456"));
+        assert!(error.is_retryable());
+        assert!(!error.is_conflict());
+        assert_eq!(error.category(), ErrorCategory::Transient);
+    }
+
+    #[test]
+    fn test_transport_errors_keep_request_id() {
+        let error = Error::<()>::Io(
+            WithRequestId::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
+                .with_request_id("client-789"),
+        );
+        assert!(matches!(&error, Error::Io(e) if e.kind() == std::io::ErrorKind::ConnectionReset));
+        assert_eq!(error.request_id(), Some("client-789"));
+        assert_eq!(error.server_request_id(), Some("client-789"));
+        assert!(error.to_string().ends_with("(request ID client-789)"));
+
+        let error = Error::<()>::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert_eq!(error.request_id(), None);
+        assert!(!error.to_string().contains("request ID"));
+    }
+
+    #[test]
+    fn test_is_retryable_matches_category() {
+        let error = Error::<()>::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert_eq!(error.category(), ErrorCategory::Transient);
+        assert!(error.is_retryable());
+        assert!(ClassifyError::is_retryable(&error));
+
+        for status in [
+            reqwest::StatusCode::REQUEST_TIMEOUT,
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            reqwest::StatusCode::BAD_REQUEST,
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+        ] {
+            let api_error = ApiError::from_response(
+                status,
+                r#"{"code": "error", "message": "failed", "requestId": "abc-123"}"#,
+            )
+            .expect("should parse API error");
+            assert_eq!(
+                api_error.is_retryable(),
+                ErrorCategory::from_http_status(status).is_retryable(),
+                "{status}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_request_id_headers() {
+        #[cfg(feature = "tracing-opentelemetry")]
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build();
+        #[cfg(not(feature = "tracing-opentelemetry"))]
+        let client = reqwest::Client::new();
+        let mut request_id = RequestId::new();
+        for retry_index in ["0", "1"] {
+            let request = request_id
+                .apply(client.get("https://api.qcs.rigetti.com/v1/health"))
+                .build()
+                .expect("request should build");
+            assert_eq!(
+                request.headers()[KEY_X_REQUEST_ID],
+                request_id.to_string().as_str()
+            );
+            assert_eq!(request.headers()[KEY_X_REQUEST_RETRY_INDEX], retry_index);
+        }
+    }
+}